@@ -0,0 +1,272 @@
+//! Matching synthetic stylesheets against a synthetic document, without an
+//! index, with a `SelectorMap`, and with a `SelectorMap` and an
+//! `AncestorFilter`
+//!
+//! Run with `cargo bench --features parser`.
+
+use std::{
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
+
+use anime::{
+    parser::parse,
+    selectors::{
+        bloom::AncestorFilter,
+        index::SelectorMap,
+        matching::{Element, MatchingContext},
+        PseudoClass, SelectorList,
+    },
+};
+
+const TAGS: [&str; 8] = ["div", "span", "p", "a", "ul", "li", "section", "img"];
+
+/// A small deterministic random number generator, so runs are comparable
+struct Random(u64);
+impl Random {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % n
+    }
+}
+
+struct Node {
+    local_name: &'static str,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, String)>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// The index in `parent.children`
+    index: usize,
+}
+
+struct Document(Vec<Node>);
+
+#[derive(Clone, Copy)]
+struct Handle<'a>(&'a Document, usize);
+impl PartialEq for Handle<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.1 == other.1
+    }
+}
+impl<'a> Handle<'a> {
+    fn node(&self) -> &'a Node {
+        &self.0 .0[self.1]
+    }
+    fn sibling(&self, offset: isize) -> Option<Self> {
+        let parent = &self.0 .0[self.node().parent?];
+        let index = self.node().index as isize + offset;
+        let child = *parent.children.get(usize::try_from(index).ok()?)?;
+        Some(Handle(self.0, child))
+    }
+}
+impl Element for Handle<'_> {
+    fn parent(&self) -> Option<Self> {
+        Some(Handle(self.0, self.node().parent?))
+    }
+    fn prev_sibling(&self) -> Option<Self> {
+        self.sibling(-1)
+    }
+    fn next_sibling(&self) -> Option<Self> {
+        self.sibling(1)
+    }
+    fn first_child(&self) -> Option<Self> {
+        Some(Handle(self.0, *self.node().children.first()?))
+    }
+    fn local_name(&self) -> &str {
+        self.node().local_name
+    }
+    fn namespace(&self) -> Option<&str> {
+        None
+    }
+    fn id(&self) -> Option<&str> {
+        self.node().id.as_deref()
+    }
+    fn classes(&self) -> Vec<&str> {
+        self.node().classes.iter().map(String::as_str).collect()
+    }
+    fn is_empty(&self) -> bool {
+        // There's no text in the document
+        self.node().children.is_empty()
+    }
+    fn attribute(&self, namespace: Option<&str>, name: &str) -> Option<&str> {
+        if namespace.is_some() {
+            return None;
+        }
+        let node = self.node();
+        let (_, value) = node.attributes.iter().find(|(n, _)| n == name)?;
+        Some(value)
+    }
+    fn matches_state(&self, _pseudo_class: &PseudoClass) -> bool {
+        false
+    }
+}
+
+fn document(random: &mut Random, elements: usize) -> Document {
+    let mut nodes = vec![Node {
+        local_name: "html",
+        id: None,
+        classes: Vec::new(),
+        attributes: Vec::new(),
+        parent: None,
+        children: Vec::new(),
+        index: 0,
+    }];
+    let mut depths = vec![0];
+    while nodes.len() < elements {
+        // Prefer recent nodes as parents, for a deep tree
+        let parent = match random.below(4) {
+            0 => random.below(nodes.len()),
+            _ => nodes.len() - 1 - random.below(nodes.len().min(8)),
+        };
+        if depths[parent] > 12 {
+            continue;
+        }
+        let classes = (0..random.below(4))
+            .map(|_| format!("c{}", random.below(100)))
+            .collect();
+        let attributes = (0..random.below(2))
+            .map(|_| (format!("data-a{}", random.below(20)), "x".to_owned()))
+            .collect();
+        let id = (random.below(20) == 0).then(|| format!("id{}", nodes.len()));
+        let index = nodes[parent].children.len();
+        let child = nodes.len();
+        nodes[parent].children.push(child);
+        depths.push(depths[parent] + 1);
+        nodes.push(Node {
+            local_name: TAGS[random.below(TAGS.len())],
+            id,
+            classes,
+            attributes,
+            parent: Some(parent),
+            children: Vec::new(),
+            index,
+        });
+    }
+    Document(nodes)
+}
+
+fn compound(random: &mut Random) -> String {
+    match random.below(6) {
+        0 => TAGS[random.below(TAGS.len())].to_owned(),
+        1 => format!("#id{}", random.below(5000)),
+        2 => format!("[data-a{}]", random.below(20)),
+        3 => format!("{}.c{}", TAGS[random.below(TAGS.len())], random.below(100)),
+        _ => format!(".c{}", random.below(100)),
+    }
+}
+
+fn stylesheet(random: &mut Random, rules: usize) -> Vec<SelectorList> {
+    (0..rules)
+        .map(|_| {
+            let mut selector = compound(random);
+            for _ in 0..random.below(4) {
+                let combinator = [" ", " > ", " + ", " ~ "][random.below(4)];
+                selector = format!("{}{}{}", compound(random), combinator, selector);
+            }
+            if random.below(10) == 0 {
+                selector.push_str(":nth-child(2n+1)");
+            }
+            parse(&selector).unwrap()
+        })
+        .collect()
+}
+
+/// Match every rule against every element
+fn naive(document: &Document, rules: &[SelectorList]) -> usize {
+    let mut context = MatchingContext::new();
+    let mut matches = 0;
+    for element in 0..document.0.len() {
+        let element = Handle(document, element);
+        matches += rules
+            .iter()
+            .filter(|rule| rule.matches(&element, &mut context))
+            .count();
+    }
+    matches
+}
+
+fn index(rules: &[SelectorList]) -> SelectorMap<usize> {
+    let mut map = SelectorMap::new();
+    for (i, rule) in rules.iter().enumerate() {
+        for selector in &rule.0 {
+            map.insert(selector.clone(), i);
+        }
+    }
+    map
+}
+
+fn indexed(document: &Document, map: &SelectorMap<usize>) -> usize {
+    let mut context = MatchingContext::new();
+    (0..document.0.len())
+        .map(|element| {
+            map.matching(&Handle(document, element), &mut context, None)
+                .len()
+        })
+        .sum()
+}
+
+/// Match in tree order, keeping the filter up to date with the ancestors of
+/// each element
+fn filtered(document: &Document, map: &SelectorMap<usize>) -> usize {
+    fn visit<'a>(
+        element: Handle<'a>,
+        map: &SelectorMap<usize>,
+        context: &mut MatchingContext<Handle<'a>>,
+        filter: &mut AncestorFilter,
+    ) -> usize {
+        let mut matches = map.matching(&element, context, Some(filter)).len();
+        filter.push(&element);
+        for &child in &element.node().children {
+            matches += visit(Handle(element.0, child), map, context, filter);
+        }
+        filter.pop(&element);
+        matches
+    }
+    let mut context = MatchingContext::new();
+    visit(
+        Handle(document, 0),
+        map,
+        &mut context,
+        &mut AncestorFilter::new(),
+    )
+}
+
+fn time<T>(f: impl Fn() -> T) -> (T, Duration) {
+    let iterations = 3;
+    let start = Instant::now();
+    let mut value = f();
+    for _ in 1..iterations {
+        value = f();
+    }
+    (value, start.elapsed() / iterations)
+}
+
+fn main() {
+    let mut random = Random(1);
+    for (elements, rules) in [(1000, 100), (1000, 1000), (5000, 1000), (5000, 5000)] {
+        let document = document(&mut random, elements);
+        let rules = stylesheet(&mut random, rules);
+        let map = index(&rules);
+
+        let (expected, naive_time) = time(|| naive(&document, &rules));
+        let (indexed_matches, indexed_time) = time(|| indexed(&document, &map));
+        let (filtered_matches, filtered_time) = time(|| filtered(&document, &map));
+        assert_eq!(indexed_matches, expected);
+        assert_eq!(filtered_matches, expected);
+
+        println!(
+            "{:>5} elements, {:>5} rules, {:>6} matches: naive {:>10.2?}, indexed {:>10.2?}, indexed with Bloom filter {:>10.2?}",
+            elements,
+            rules.len(),
+            expected,
+            naive_time,
+            indexed_time,
+            filtered_time,
+        );
+    }
+}
@@ -0,0 +1,1938 @@
+//! Interpolation of values for animations and transitions, as described in
+//! https://drafts.csswg.org/css-values-4/#combining-values
+
+use std::collections::HashMap;
+
+use crate::{
+    at_rules::Keyframes,
+    data_types::*,
+    properties::{
+        self, shorthand::Animation, shorthand::SingleAnimation, AnimationDirectionValue,
+        AnimationFillModeValue, AnimationIterationCountValue, EasingFunction, Property,
+        PropertyDiscriminants, TextShadow, TextShadowValue, Transform, Visibility,
+    },
+    rules::DeclarationValue,
+    transform::{self, Matrix3d, TransformContext},
+};
+
+/// A value that can be interpolated between two keyframes (or the start and
+/// end of a transition)
+///
+/// https://drafts.csswg.org/css-values-4/#interpolation
+pub trait Animatable: Sized {
+    /// The value `progress` of the way from `self` to `to`, or `None` if the
+    /// values can't be interpolated (in which case they animate discretely).
+    ///
+    /// `progress` can be outside of 0 to 1, as easing functions can
+    /// overshoot.
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self>;
+}
+
+/// Interpolate between two values, falling back to [`discrete`] if they
+/// can't be interpolated
+pub fn interpolate<T: Animatable + Clone>(from: &T, to: &T, progress: f64) -> T {
+    from.interpolate(to, progress)
+        .unwrap_or_else(|| discrete(from, to, progress))
+}
+
+/// Flip from one value to the other half way through
+///
+/// https://drafts.csswg.org/web-animations-1/#discrete
+pub fn discrete<T: Clone>(from: &T, to: &T, progress: f64) -> T {
+    if progress < 0.5 {
+        from.clone()
+    } else {
+        to.clone()
+    }
+}
+
+/// A value that always animates discretely, like a keyword
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Discrete<T>(pub T);
+impl<T: Clone> Animatable for Discrete<T> {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        Some(discrete(self, to, progress))
+    }
+}
+
+macro_rules! discrete_animatable {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Animatable for $ty {
+                fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+                    Some(discrete(self, to, progress))
+                }
+            }
+        )*
+    };
+}
+discrete_animatable!(BlendMode, FillRule, LineStyle);
+
+/// https://drafts.csswg.org/css-display-3/#visibility
+impl Animatable for Visibility {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        // Visible for the whole interpolation if either end is visible
+        if (*self == Visibility::Visible || *to == Visibility::Visible)
+            && progress > 0.0
+            && progress < 1.0
+        {
+            return Some(Visibility::Visible);
+        }
+        Some(discrete(self, to, progress))
+    }
+}
+
+fn lerp(from: f32, to: f32, progress: f64) -> f32 {
+    (from as f64 + (to as f64 - from as f64) * progress) as f32
+}
+
+//* Numbers
+
+impl Animatable for Number {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        Some(Number(lerp(self.0, to.0, progress)))
+    }
+}
+
+/// Interpolated as a number, then rounded to the nearest integer (with
+/// halves rounded towards positive infinity)
+impl Animatable for Integer {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        let value = self.0 as f64 + (to.0 as f64 - self.0 as f64) * progress;
+        Some(Integer((value + 0.5).floor() as i32))
+    }
+}
+
+/// Clamped to 0, as easing functions can overshoot
+impl Animatable for PositiveNumber {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        PositiveNumber::new(lerp(self.get(), to.get(), progress).max(0.0))
+    }
+}
+
+impl Animatable for Percentage {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        Some(Percentage(Number(lerp(self.0 .0, to.0 .0, progress))))
+    }
+}
+
+/// A number and a percentage interpolate as numbers
+impl Animatable for NumberPercentage {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        Some(match (self, to) {
+            (NumberPercentage::Percentage(a), NumberPercentage::Percentage(b)) => {
+                NumberPercentage::Percentage(a.interpolate(b, progress)?)
+            }
+            _ => NumberPercentage::Number(Number(lerp(self.value(), to.value(), progress))),
+        })
+    }
+}
+
+/// Angles in different units interpolate in degrees
+impl Animatable for Angle {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        if self.1 == to.1 {
+            return Some(Angle(lerp(self.0, to.0, progress), self.1));
+        }
+        Some(Angle(
+            lerp(
+                self.to_radians().to_degrees(),
+                to.to_radians().to_degrees(),
+                progress,
+            ),
+            AngleUnit::Deg,
+        ))
+    }
+}
+
+//* Lengths
+
+fn is_absolute(unit: LengthUnit) -> bool {
+    use LengthUnit::*;
+    matches!(unit, Px | Cm | Mm | In | Pc | Pt)
+}
+
+/// Absolute lengths resolve to px without looking at the context
+const ABSOLUTE_CONTEXT: LengthContext = LengthContext {
+    font_size: 0.0,
+    root_font_size: 0.0,
+    viewport_width: 0.0,
+    viewport_height: 0.0,
+};
+
+/// Lengths in different units can only be interpolated if both are absolute
+/// (or one is zero), as there's no `calc()` in a plain `<length>`
+impl Animatable for Length {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        let unit = if self.1 == to.1 || self.0 == 0.0 {
+            to.1
+        } else if to.0 == 0.0 {
+            self.1
+        } else if is_absolute(self.1) && is_absolute(to.1) {
+            return Some(Length(
+                lerp(
+                    self.to_px(&ABSOLUTE_CONTEXT),
+                    to.to_px(&ABSOLUTE_CONTEXT),
+                    progress,
+                ),
+                LengthUnit::Px,
+            ));
+        } else {
+            return None;
+        };
+        Some(Length(lerp(self.0, to.0, progress), unit))
+    }
+}
+
+/// Clamped to 0, as easing functions can overshoot
+impl Animatable for PositiveLength {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        let Length(value, unit) = Length(self.value(), *self.unit())
+            .interpolate(&Length(to.value(), *to.unit()), progress)?;
+        PositiveLength::new(value.max(0.0), unit)
+    }
+}
+
+/// A length-percentage as a sum of terms
+struct Terms {
+    /// At most one length per unit, with absolute lengths combined as px
+    lengths: Vec<Length>,
+    percentage: Option<f32>,
+}
+
+impl Terms {
+    fn new(value: &LengthPercentage) -> Self {
+        let mut terms = Terms {
+            lengths: Vec::new(),
+            percentage: None,
+        };
+        match value {
+            LengthPercentage::Length(length) => terms.add_length(*length, 1.0),
+            LengthPercentage::Percentage(percentage) => terms.percentage = Some(percentage.0 .0),
+            LengthPercentage::Calc(calc) => {
+                for length in &calc.lengths {
+                    terms.add_length(*length, 1.0);
+                }
+                terms.percentage = calc.percentage.map(|percentage| percentage.0 .0);
+            }
+        }
+        terms
+    }
+
+    fn add_length(&mut self, length: Length, scale: f32) {
+        let length = if is_absolute(length.1) {
+            Length(length.to_px(&ABSOLUTE_CONTEXT), LengthUnit::Px)
+        } else {
+            length
+        };
+        match self.lengths.iter_mut().find(|other| other.1 == length.1) {
+            Some(other) => other.0 += length.0 * scale,
+            None => self.lengths.push(Length(length.0 * scale, length.1)),
+        }
+    }
+
+    /// `self * scale_self + other * scale_other`
+    fn combine(&self, other: &Terms, scale_self: f32, scale_other: f32) -> Terms {
+        let mut terms = Terms {
+            lengths: Vec::new(),
+            percentage: None,
+        };
+        for length in &self.lengths {
+            terms.add_length(*length, scale_self);
+        }
+        for length in &other.lengths {
+            terms.add_length(*length, scale_other);
+        }
+        if self.percentage.is_some() || other.percentage.is_some() {
+            terms.percentage = Some(
+                self.percentage.unwrap_or(0.0) * scale_self
+                    + other.percentage.unwrap_or(0.0) * scale_other,
+            );
+        }
+        terms
+    }
+
+    /// The simplest length-percentage for the sum, dropping zero terms
+    fn into_length_percentage(mut self) -> LengthPercentage {
+        self.lengths.retain(|length| length.0 != 0.0);
+        let percentage = self.percentage.filter(|percentage| *percentage != 0.0);
+        match (self.lengths.as_slice(), percentage) {
+            ([], None) => LengthPercentage::Length(Length(0.0, LengthUnit::Px)),
+            ([], Some(percentage)) => LengthPercentage::Percentage(Percentage(Number(percentage))),
+            ([length], None) => LengthPercentage::Length(*length),
+            _ => LengthPercentage::Calc(CalcLengthPercentage {
+                lengths: self.lengths,
+                percentage: percentage.map(|percentage| Percentage(Number(percentage))),
+            }),
+        }
+    }
+}
+
+/// Values that can't be interpolated as lengths or percentages alone are
+/// interpolated as a `calc()` sum, like `calc(5px + 50%)` half way from `10px`
+/// to `100%`
+impl Animatable for LengthPercentage {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        match (self, to) {
+            (LengthPercentage::Length(a), LengthPercentage::Length(b)) => {
+                if let Some(length) = a.interpolate(b, progress) {
+                    return Some(LengthPercentage::Length(length));
+                }
+            }
+            (LengthPercentage::Percentage(a), LengthPercentage::Percentage(b)) => {
+                return Some(LengthPercentage::Percentage(a.interpolate(b, progress)?));
+            }
+            _ => {}
+        }
+        let progress = progress as f32;
+        Some(
+            Terms::new(self)
+                .combine(&Terms::new(to), 1.0 - progress, progress)
+                .into_length_percentage(),
+        )
+    }
+}
+
+impl Animatable for Position {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        let x = |x: &PositionX| match x {
+            PositionX::Left(offset) => from_start(offset),
+            PositionX::Center => percentage(50.0),
+            PositionX::Right(offset) => from_end(offset),
+            PositionX::Value(value) => value.clone(),
+        };
+        let y = |y: &PositionY| match y {
+            PositionY::Top(offset) => from_start(offset),
+            PositionY::Center => percentage(50.0),
+            PositionY::Bottom(offset) => from_end(offset),
+            PositionY::Value(value) => value.clone(),
+        };
+        Some(Position {
+            x: PositionX::Value(x(&self.x).interpolate(&x(&to.x), progress)?),
+            y: PositionY::Value(y(&self.y).interpolate(&y(&to.y), progress)?),
+        })
+    }
+}
+
+fn percentage(value: f32) -> LengthPercentage {
+    LengthPercentage::Percentage(Percentage(Number(value)))
+}
+
+/// An offset from the left or top as a value
+fn from_start(offset: &Option<LengthPercentage>) -> LengthPercentage {
+    offset.clone().unwrap_or_else(|| percentage(0.0))
+}
+
+/// An offset from the right or bottom as a value, like `calc(100% - 10px)`
+fn from_end(offset: &Option<LengthPercentage>) -> LengthPercentage {
+    match offset {
+        Some(offset) => Terms::new(&percentage(100.0))
+            .combine(&Terms::new(offset), 1.0, -1.0)
+            .into_length_percentage(),
+        None => percentage(100.0),
+    }
+}
+
+//* Colors
+
+impl Color {
+    /// Interpolate in `space`, with premultiplied alpha. Hues take the
+    /// shorter arc, and a powerless hue (of an achromatic color) takes the
+    /// other color's hue.
+    ///
+    /// https://drafts.csswg.org/css-color-4/#interpolation
+    pub fn interpolate_in(&self, to: &Color, progress: f64, space: ColorSpace) -> Color {
+        let (mut from, from_alpha) = components(&self.to_space(space));
+        let (mut to, to_alpha) = components(&to.to_space(space));
+        // The index of the hue, and of the component that makes it powerless
+        let hue = match space {
+            ColorSpace::Hsl => Some((0, 1)),
+            ColorSpace::Lch | ColorSpace::Oklch => Some((2, 1)),
+            _ => None,
+        };
+
+        if let Some((hue, chroma)) = hue {
+            const ACHROMATIC: f32 = 1e-4;
+            if from[chroma].abs() < ACHROMATIC {
+                from[hue] = to[hue];
+            } else if to[chroma].abs() < ACHROMATIC {
+                to[hue] = from[hue];
+            }
+            if to[hue] - from[hue] > 180.0 {
+                from[hue] += 360.0;
+            } else if to[hue] - from[hue] < -180.0 {
+                to[hue] += 360.0;
+            }
+        }
+
+        let alpha = lerp(from_alpha, to_alpha, progress);
+        let mut result = [0.0; 3];
+        for (i, value) in result.iter_mut().enumerate() {
+            *value = if hue.is_some_and(|(hue, _)| hue == i) {
+                lerp(from[i], to[i], progress).rem_euclid(360.0)
+            } else {
+                let premultiplied = lerp(from[i] * from_alpha, to[i] * to_alpha, progress);
+                if alpha == 0.0 {
+                    premultiplied
+                } else {
+                    premultiplied / alpha
+                }
+            };
+        }
+        from_components(space, result, alpha.clamp(0.0, 1.0))
+    }
+}
+
+/// The components of a color in its own space (with hues in degrees), and its
+/// alpha
+fn components(color: &Color) -> ([f32; 3], f32) {
+    let components = match color {
+        Color::Rgb(c) => [c.red, c.green, c.blue],
+        Color::Hsl(c) => [c.hue.to_positive_degrees(), c.saturation, c.lightness],
+        Color::Lch(c) => [c.l, c.chroma, c.hue.to_positive_degrees()],
+        Color::Lab(c) => [c.l, c.a, c.b],
+        Color::Oklab(c) => [c.l, c.a, c.b],
+        Color::Oklch(c) => [c.l, c.chroma, c.hue.to_positive_degrees()],
+    };
+    (components, color.alpha())
+}
+
+fn from_components(space: ColorSpace, [a, b, c]: [f32; 3], alpha: f32) -> Color {
+    match space {
+        ColorSpace::Srgb => Color::Rgb(palette::Srgba::new(a, b, c, alpha)),
+        ColorSpace::Hsl => Color::Hsl(palette::Hsla::new(a, b, c, alpha)),
+        ColorSpace::Lch => Color::Lch(palette::Lcha::with_wp(a, b, c, alpha)),
+        ColorSpace::Lab => Color::Lab(palette::Laba::with_wp(a, b, c, alpha)),
+        ColorSpace::Oklab => Color::Oklab(palette::Oklaba::new(a, b, c, alpha)),
+        ColorSpace::Oklch => Color::Oklch(palette::Oklcha::new(a, b, c, alpha)),
+    }
+}
+
+/// Colors interpolate in Oklab by default
+///
+/// https://drafts.csswg.org/css-color-4/#interpolation-space
+impl Animatable for Color {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        Some(self.interpolate_in(to, progress, ColorSpace::Oklab))
+    }
+}
+
+const TRANSPARENT: Color = Color::Rgb(palette::Alpha {
+    color: palette::rgb::Rgb {
+        red: 0.0,
+        green: 0.0,
+        blue: 0.0,
+        standard: std::marker::PhantomData,
+    },
+    alpha: 0.0,
+});
+
+/// Colors where `None` is `currentcolor`, which can only be interpolated
+/// with itself
+fn interpolate_color(
+    from: &Option<Color>,
+    to: &Option<Color>,
+    progress: f64,
+) -> Option<Option<Color>> {
+    match (from, to) {
+        (Some(from), Some(to)) => Some(Some(from.interpolate(to, progress)?)),
+        (None, None) => Some(None),
+        _ => None,
+    }
+}
+
+//* Shapes
+
+impl Animatable for ShapeRadius {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        match (self, to) {
+            (ShapeRadius::Radius(a), ShapeRadius::Radius(b)) => {
+                Some(ShapeRadius::Radius(a.interpolate(b, progress)?))
+            }
+            _ if self == to => Some(self.clone()),
+            _ => None,
+        }
+    }
+}
+
+impl Animatable for RadiusCorner {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        let radii = |corner: &RadiusCorner| match corner {
+            RadiusCorner::Circle(radius) => (radius.clone(), radius.clone()),
+            RadiusCorner::Ellipse { x, y } => (x.clone(), y.clone()),
+        };
+        if let (RadiusCorner::Circle(a), RadiusCorner::Circle(b)) = (self, to) {
+            return Some(RadiusCorner::Circle(a.interpolate(b, progress)?));
+        }
+        let ((from_x, from_y), (to_x, to_y)) = (radii(self), radii(to));
+        Some(RadiusCorner::Ellipse {
+            x: from_x.interpolate(&to_x, progress)?,
+            y: from_y.interpolate(&to_y, progress)?,
+        })
+    }
+}
+
+/// An omitted position is the center
+fn interpolate_shape_position(
+    from: &Option<Position>,
+    to: &Option<Position>,
+    progress: f64,
+) -> Option<Option<Position>> {
+    let center = || Position {
+        x: PositionX::Center,
+        y: PositionY::Center,
+    };
+    match (from, to) {
+        (None, None) => Some(None),
+        _ => Some(Some(
+            from.clone()
+                .unwrap_or_else(center)
+                .interpolate(&to.clone().unwrap_or_else(center), progress)?,
+        )),
+    }
+}
+
+/// Only shapes of the same type can be interpolated, and polygons must have
+/// the same fill rule and number of points
+///
+/// https://drafts.csswg.org/css-shapes-1/#basic-shape-interpolation
+impl Animatable for BasicShape {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        Some(match (self, to) {
+            (BasicShape::Inset(a), BasicShape::Inset(b)) => BasicShape::Inset(InsetRectange {
+                top: a.top.interpolate(&b.top, progress)?,
+                left: a.left.interpolate(&b.left, progress)?,
+                bottom: a.bottom.interpolate(&b.bottom, progress)?,
+                right: a.right.interpolate(&b.right, progress)?,
+                border_radius: a.border_radius.interpolate(&b.border_radius, progress)?,
+            }),
+            (BasicShape::Circle(a), BasicShape::Circle(b)) => BasicShape::Circle(Circle {
+                radius: a.radius.interpolate(&b.radius, progress)?,
+                position: interpolate_shape_position(&a.position, &b.position, progress)?,
+            }),
+            (BasicShape::Ellipse(a), BasicShape::Ellipse(b)) => BasicShape::Ellipse(Ellipse {
+                x_radius: a.x_radius.interpolate(&b.x_radius, progress)?,
+                y_radius: a.y_radius.interpolate(&b.y_radius, progress)?,
+                position: interpolate_shape_position(&a.position, &b.position, progress)?,
+            }),
+            (BasicShape::Polygon(a), BasicShape::Polygon(b))
+                if a.fill_rule == b.fill_rule && a.points.len() == b.points.len() =>
+            {
+                BasicShape::Polygon(Polygon {
+                    fill_rule: a.fill_rule,
+                    points: a
+                        .points
+                        .iter()
+                        .zip(&b.points)
+                        .map(|((ax, ay), (bx, by))| {
+                            Some((ax.interpolate(bx, progress)?, ay.interpolate(by, progress)?))
+                        })
+                        .collect::<Option<_>>()?,
+                })
+            }
+            // Paths would need to be parsed to be interpolated
+            (BasicShape::Path(a), BasicShape::Path(b)) if a == b => self.clone(),
+            _ => return None,
+        })
+    }
+}
+
+//* Filters
+
+/// The value used when a filter function is missing from the other list
+///
+/// https://drafts.fxtf.org/filter-effects-1/#interpolation-of-filters
+fn filter_initial_value(function: &FilterFunction) -> FilterFunction {
+    use FilterFunction::*;
+
+    let number = |value| NumberPercentage::Number(Number(value));
+    match function {
+        Blur { .. } => Blur {
+            radius: Length(0.0, LengthUnit::Px),
+        },
+        Brightness { .. } => Brightness {
+            amount: number(1.0),
+        },
+        Constrast { .. } => Constrast {
+            amount: number(1.0),
+        },
+        DropShadow { color, .. } => DropShadow {
+            offset_x: Length(0.0, LengthUnit::Px),
+            offset_y: Length(0.0, LengthUnit::Px),
+            blur_radius: Some(Length(0.0, LengthUnit::Px)),
+            // currentcolor can't be interpolated with transparent
+            color: color.map(|_| TRANSPARENT),
+        },
+        Grayscale { .. } => Grayscale {
+            amount: number(0.0),
+        },
+        HueRotate { .. } => HueRotate {
+            angle: Angle(0.0, AngleUnit::Deg),
+        },
+        Invert { .. } => Invert {
+            amount: number(0.0),
+        },
+        Opacity { .. } => Opacity {
+            amount: number(1.0),
+        },
+        Saturate { .. } => Saturate {
+            amount: number(1.0),
+        },
+        Sepia { .. } => Sepia {
+            amount: number(0.0),
+        },
+    }
+}
+
+impl Animatable for FilterFunction {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        use FilterFunction::*;
+
+        let zero = Length(0.0, LengthUnit::Px);
+        Some(match (self, to) {
+            (Blur { radius: a }, Blur { radius: b }) => Blur {
+                radius: a
+                    .interpolate(b, progress)
+                    .map(|Length(value, unit)| Length(value.max(0.0), unit))?,
+            },
+            (Brightness { amount: a }, Brightness { amount: b }) => Brightness {
+                amount: a.interpolate(b, progress)?,
+            },
+            (Constrast { amount: a }, Constrast { amount: b }) => Constrast {
+                amount: a.interpolate(b, progress)?,
+            },
+            (
+                DropShadow {
+                    offset_x,
+                    offset_y,
+                    blur_radius,
+                    color,
+                },
+                DropShadow {
+                    offset_x: to_offset_x,
+                    offset_y: to_offset_y,
+                    blur_radius: to_blur_radius,
+                    color: to_color,
+                },
+            ) => DropShadow {
+                offset_x: offset_x.interpolate(to_offset_x, progress)?,
+                offset_y: offset_y.interpolate(to_offset_y, progress)?,
+                blur_radius: Some(
+                    blur_radius
+                        .unwrap_or(zero)
+                        .interpolate(&to_blur_radius.unwrap_or(zero), progress)
+                        .map(|Length(value, unit)| Length(value.max(0.0), unit))?,
+                ),
+                color: interpolate_color(color, to_color, progress)?,
+            },
+            (Grayscale { amount: a }, Grayscale { amount: b }) => Grayscale {
+                amount: a.interpolate(b, progress)?,
+            },
+            (HueRotate { angle: a }, HueRotate { angle: b }) => HueRotate {
+                angle: a.interpolate(b, progress)?,
+            },
+            (Invert { amount: a }, Invert { amount: b }) => Invert {
+                amount: a.interpolate(b, progress)?,
+            },
+            (Opacity { amount: a }, Opacity { amount: b }) => Opacity {
+                amount: a.interpolate(b, progress)?,
+            },
+            (Saturate { amount: a }, Saturate { amount: b }) => Saturate {
+                amount: a.interpolate(b, progress)?,
+            },
+            (Sepia { amount: a }, Sepia { amount: b }) => Sepia {
+                amount: a.interpolate(b, progress)?,
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Lists interpolate when the functions of the shorter list match the start
+/// of the longer one, with the missing functions padded with their initial
+/// values. An empty list is `none`.
+///
+/// https://drafts.fxtf.org/filter-effects-1/#interpolation-of-filters
+impl Animatable for Vec<FilterFunction> {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        let length = self.len().max(to.len());
+        (0..length)
+            .map(|i| {
+                let (from, to) = match (self.get(i), to.get(i)) {
+                    (Some(from), Some(to)) => (from.clone(), to.clone()),
+                    (Some(from), None) => (from.clone(), filter_initial_value(from)),
+                    (None, Some(to)) => (filter_initial_value(to), to.clone()),
+                    (None, None) => unreachable!(),
+                };
+                from.interpolate(&to, progress)
+            })
+            .collect()
+    }
+}
+
+//* Shadows
+
+impl Animatable for TextShadowValue {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        let zero = Length(0.0, LengthUnit::Px);
+        Some(TextShadowValue {
+            offset_x: self.offset_x.interpolate(&to.offset_x, progress)?,
+            offset_y: self.offset_y.interpolate(&to.offset_y, progress)?,
+            blur_radius: Some(
+                self.blur_radius
+                    .unwrap_or(zero)
+                    .interpolate(&to.blur_radius.unwrap_or(zero), progress)
+                    .map(|Length(value, unit)| Length(value.max(0.0), unit))?,
+            ),
+            color: interpolate_color(&self.color, &to.color, progress)?,
+        })
+    }
+}
+
+/// The shorter list is padded with transparent shadows
+///
+/// https://drafts.csswg.org/css-backgrounds-3/#shadow-interpolation
+impl Animatable for TextShadow {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        let padding = |shadow: &TextShadowValue| TextShadowValue {
+            offset_x: Length(0.0, LengthUnit::Px),
+            offset_y: Length(0.0, LengthUnit::Px),
+            blur_radius: Some(Length(0.0, LengthUnit::Px)),
+            // currentcolor can't be interpolated with transparent
+            color: shadow.color.map(|_| TRANSPARENT),
+        };
+        let length = self.0.len().max(to.0.len());
+        let shadows = (0..length)
+            .map(|i| match (self.0.get(i), to.0.get(i)) {
+                (Some(from), Some(to)) => from.interpolate(to, progress),
+                (Some(from), None) => from.interpolate(&padding(from), progress),
+                (None, Some(to)) => padding(to).interpolate(to, progress),
+                (None, None) => unreachable!(),
+            })
+            .collect::<Option<_>>()?;
+        Some(TextShadow(shadows))
+    }
+}
+
+//* Transforms
+
+/// The shorter list is padded with identity functions, then pairs of
+/// functions are interpolated individually, converting to a common function
+/// (like `translate3d()` for `translate()` and `translateZ()`) if needed.
+///
+/// From the first pair that doesn't share a common function, the rest of
+/// each list is interpolated as a matrix. That's only possible here if they
+/// don't contain relative lengths or percentages (otherwise see
+/// [`transform::interpolate_transforms`]).
+///
+/// https://drafts.csswg.org/css-transforms-2/#interpolation-of-transforms
+impl Animatable for Vec<TransformFunction> {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        let mut from = self.clone();
+        let mut to = to.clone();
+        if from.len() < to.len() {
+            from.extend(to[from.len()..].iter().map(identity_function));
+        } else {
+            to.extend(from[to.len()..].iter().map(identity_function));
+        }
+
+        let mut functions = Vec::new();
+        for (i, (a, b)) in from.iter().zip(&to).enumerate() {
+            match a.interpolate(b, progress) {
+                Some(function) => functions.push(function),
+                None => {
+                    if !from[i..].iter().chain(&to[i..]).all(is_absolute_function) {
+                        return None;
+                    }
+                    let matrix = transform::interpolate_transforms(
+                        &from[i..],
+                        &to[i..],
+                        progress,
+                        &ABSOLUTE_TRANSFORM_CONTEXT,
+                    );
+                    functions.push(matrix_function(&matrix));
+                    break;
+                }
+            }
+        }
+        Some(functions)
+    }
+}
+
+impl Animatable for Transform {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        Some(Transform(self.0.interpolate(&to.0, progress)?))
+    }
+}
+
+const ABSOLUTE_TRANSFORM_CONTEXT: TransformContext = TransformContext {
+    lengths: ABSOLUTE_CONTEXT,
+    reference_width: 0.0,
+    reference_height: 0.0,
+};
+
+/// Whether a function can be resolved to a matrix without a context
+fn is_absolute_function(function: &TransformFunction) -> bool {
+    use TransformFunction::*;
+
+    let length_percentage = |value: &LengthPercentage| match value {
+        LengthPercentage::Length(length) => is_absolute(length.1),
+        _ => false,
+    };
+    match function {
+        Perspective { distance } => distance
+            .as_ref()
+            .is_none_or(|distance| is_absolute(*distance.unit())),
+        Translate { x, y } => length_percentage(x) && y.as_ref().is_none_or(length_percentage),
+        Translate3d { x, y, z } => length_percentage(x) && length_percentage(y) && is_absolute(z.1),
+        TranslateX { x } => length_percentage(x),
+        TranslateY { y } => length_percentage(y),
+        TranslateZ { z } => is_absolute(z.1),
+        _ => true,
+    }
+}
+
+/// A `matrix()` for 2D matrices, otherwise a `matrix3d()`
+fn matrix_function(matrix: &Matrix3d) -> TransformFunction {
+    let number = |value: f64| Number(value as f32);
+    let m = &matrix.0;
+    if matrix.is_2d() {
+        TransformFunction::Matrix {
+            a: number(m[0][0]),
+            b: number(m[1][0]),
+            c: number(m[0][1]),
+            d: number(m[1][1]),
+            tx: number(m[0][3]),
+            ty: number(m[1][3]),
+        }
+    } else {
+        TransformFunction::Matrix3d {
+            values: matrix.to_column_major().map(number),
+        }
+    }
+}
+
+/// The function of the same type that doesn't transform anything
+fn identity_function(function: &TransformFunction) -> TransformFunction {
+    use TransformFunction::*;
+
+    let zero = || LengthPercentage::Length(Length(0.0, LengthUnit::Px));
+    let one = || NumberPercentage::Number(Number(1.0));
+    let angle = |angle: &Angle| Angle(0.0, angle.1);
+    match function {
+        Matrix { .. } | Matrix3d { .. } => matrix_function(&self::Matrix3d::IDENTITY),
+        Perspective { .. } => Perspective { distance: None },
+        Rotate { angle: a } => Rotate { angle: angle(a) },
+        Rotate3d { x, y, z, angle: a } => Rotate3d {
+            x: *x,
+            y: *y,
+            z: *z,
+            angle: angle(a),
+        },
+        RotateX { angle: a } => RotateX { angle: angle(a) },
+        RotateY { angle: a } => RotateY { angle: angle(a) },
+        RotateZ { angle: a } => RotateZ { angle: angle(a) },
+        Scale { .. } => Scale { x: one(), y: None },
+        Scale3d { .. } => Scale3d {
+            x: one(),
+            y: one(),
+            z: one(),
+        },
+        ScaleX { .. } => ScaleX { x: one() },
+        ScaleY { .. } => ScaleY { y: one() },
+        ScaleZ { .. } => ScaleZ { z: one() },
+        Skew { x, .. } => Skew {
+            x: angle(x),
+            y: None,
+        },
+        SkewX { x } => SkewX { x: angle(x) },
+        SkewY { y } => SkewY { y: angle(y) },
+        Translate { .. } => Translate { x: zero(), y: None },
+        Translate3d { .. } => Translate3d {
+            x: zero(),
+            y: zero(),
+            z: Length(0.0, LengthUnit::Px),
+        },
+        TranslateX { .. } => TranslateX { x: zero() },
+        TranslateY { .. } => TranslateY { y: zero() },
+        TranslateZ { .. } => TranslateZ {
+            z: Length(0.0, LengthUnit::Px),
+        },
+    }
+}
+
+/// Convert a function to the common function of its family, which is 2D if
+/// `is_2d` (for example `translate()` rather than `translate3d()`)
+///
+/// https://drafts.csswg.org/css-transforms-2/#transform-primitives
+fn common_function(function: &TransformFunction, is_2d: bool) -> TransformFunction {
+    use TransformFunction::*;
+
+    let zero = || LengthPercentage::Length(Length(0.0, LengthUnit::Px));
+    let one = || NumberPercentage::Number(Number(1.0));
+    let translate = |x, y, z| {
+        if is_2d {
+            Translate { x, y: Some(y) }
+        } else {
+            Translate3d { x, y, z }
+        }
+    };
+    let scale = |x, y, z| {
+        if is_2d {
+            Scale { x, y: Some(y) }
+        } else {
+            Scale3d { x, y, z }
+        }
+    };
+    let rotate = |x, y, z, angle| {
+        if is_2d {
+            Rotate { angle }
+        } else {
+            Rotate3d {
+                x: Number(x),
+                y: Number(y),
+                z: Number(z),
+                angle,
+            }
+        }
+    };
+    match function.clone() {
+        Translate { x, y } => translate(x, y.unwrap_or_else(zero), Length(0.0, LengthUnit::Px)),
+        Translate3d { x, y, z } => translate(x, y, z),
+        TranslateX { x } => translate(x, zero(), Length(0.0, LengthUnit::Px)),
+        TranslateY { y } => translate(zero(), y, Length(0.0, LengthUnit::Px)),
+        TranslateZ { z } => translate(zero(), zero(), z),
+        Scale { x, y } => {
+            let y = y.unwrap_or_else(|| x.clone());
+            scale(x, y, one())
+        }
+        Scale3d { x, y, z } => scale(x, y, z),
+        ScaleX { x } => scale(x, one(), one()),
+        ScaleY { y } => scale(one(), y, one()),
+        ScaleZ { z } => scale(one(), one(), z),
+        Rotate { angle } | RotateZ { angle } => rotate(0.0, 0.0, 1.0, angle),
+        RotateX { angle } => rotate(1.0, 0.0, 0.0, angle),
+        RotateY { angle } => rotate(0.0, 1.0, 0.0, angle),
+        Skew { x, y } => Skew {
+            x,
+            y: Some(y.unwrap_or(Angle(0.0, AngleUnit::Deg))),
+        },
+        SkewX { x } => Skew {
+            x,
+            y: Some(Angle(0.0, AngleUnit::Deg)),
+        },
+        SkewY { y } => Skew {
+            x: Angle(0.0, AngleUnit::Deg),
+            y: Some(y),
+        },
+        function => function,
+    }
+}
+
+fn is_2d_function(function: &TransformFunction) -> bool {
+    use TransformFunction::*;
+
+    matches!(
+        function,
+        Matrix { .. }
+            | Rotate { .. }
+            | RotateZ { .. }
+            | Scale { .. }
+            | ScaleX { .. }
+            | ScaleY { .. }
+            | Skew { .. }
+            | SkewX { .. }
+            | SkewY { .. }
+            | Translate { .. }
+            | TranslateX { .. }
+            | TranslateY { .. }
+    )
+}
+
+/// Functions that don't share a common function (like `rotate()` and
+/// `scale()`) can't be interpolated individually
+impl Animatable for TransformFunction {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        use TransformFunction::*;
+
+        // Optional values default to the identity
+        let zero = || LengthPercentage::Length(Length(0.0, LengthUnit::Px));
+        let zero_angle = Angle(0.0, AngleUnit::Deg);
+        Some(match (self, to) {
+            (Matrix { .. }, Matrix { .. })
+            | (Matrix3d { .. }, Matrix3d { .. })
+            | (Matrix { .. }, Matrix3d { .. })
+            | (Matrix3d { .. }, Matrix { .. }) => {
+                let from = self::Matrix3d::from_function(self, &ABSOLUTE_TRANSFORM_CONTEXT);
+                let to = self::Matrix3d::from_function(to, &ABSOLUTE_TRANSFORM_CONTEXT);
+                matrix_function(&from.interpolate(&to, progress))
+            }
+            (Perspective { distance: a }, Perspective { distance: b }) => Perspective {
+                distance: match (a, b) {
+                    (Some(a), Some(b)) => Some(a.interpolate(b, progress)?),
+                    (None, None) => None,
+                    // An infinite distance has to be interpolated as a matrix
+                    _ => return None,
+                },
+            },
+            (Rotate { angle: a }, Rotate { angle: b }) => Rotate {
+                angle: a.interpolate(b, progress)?,
+            },
+            (RotateX { angle: a }, RotateX { angle: b }) => RotateX {
+                angle: a.interpolate(b, progress)?,
+            },
+            (RotateY { angle: a }, RotateY { angle: b }) => RotateY {
+                angle: a.interpolate(b, progress)?,
+            },
+            (RotateZ { angle: a }, RotateZ { angle: b }) => RotateZ {
+                angle: a.interpolate(b, progress)?,
+            },
+            (
+                Rotate3d {
+                    x: ax,
+                    y: ay,
+                    z: az,
+                    angle: a,
+                },
+                Rotate3d {
+                    x: bx,
+                    y: by,
+                    z: bz,
+                    angle: b,
+                },
+            ) => interpolate_rotate3d([ax.0, ay.0, az.0], a, [bx.0, by.0, bz.0], b, progress)?,
+            (Scale { x: ax, y: ay }, Scale { x: bx, y: by }) => Scale {
+                x: ax.interpolate(bx, progress)?,
+                y: match (ay, by) {
+                    (None, None) => None,
+                    _ => Some(
+                        ay.as_ref()
+                            .unwrap_or(ax)
+                            .interpolate(by.as_ref().unwrap_or(bx), progress)?,
+                    ),
+                },
+            },
+            (
+                Scale3d {
+                    x: ax,
+                    y: ay,
+                    z: az,
+                },
+                Scale3d {
+                    x: bx,
+                    y: by,
+                    z: bz,
+                },
+            ) => Scale3d {
+                x: ax.interpolate(bx, progress)?,
+                y: ay.interpolate(by, progress)?,
+                z: az.interpolate(bz, progress)?,
+            },
+            (ScaleX { x: a }, ScaleX { x: b }) => ScaleX {
+                x: a.interpolate(b, progress)?,
+            },
+            (ScaleY { y: a }, ScaleY { y: b }) => ScaleY {
+                y: a.interpolate(b, progress)?,
+            },
+            (ScaleZ { z: a }, ScaleZ { z: b }) => ScaleZ {
+                z: a.interpolate(b, progress)?,
+            },
+            (Skew { x: ax, y: ay }, Skew { x: bx, y: by }) => Skew {
+                x: ax.interpolate(bx, progress)?,
+                y: match (ay, by) {
+                    (None, None) => None,
+                    _ => Some(
+                        ay.unwrap_or(zero_angle)
+                            .interpolate(&by.unwrap_or(zero_angle), progress)?,
+                    ),
+                },
+            },
+            (SkewX { x: a }, SkewX { x: b }) => SkewX {
+                x: a.interpolate(b, progress)?,
+            },
+            (SkewY { y: a }, SkewY { y: b }) => SkewY {
+                y: a.interpolate(b, progress)?,
+            },
+            (Translate { x: ax, y: ay }, Translate { x: bx, y: by }) => Translate {
+                x: ax.interpolate(bx, progress)?,
+                y: match (ay, by) {
+                    (None, None) => None,
+                    _ => Some(
+                        ay.clone()
+                            .unwrap_or_else(zero)
+                            .interpolate(&by.clone().unwrap_or_else(zero), progress)?,
+                    ),
+                },
+            },
+            (
+                Translate3d {
+                    x: ax,
+                    y: ay,
+                    z: az,
+                },
+                Translate3d {
+                    x: bx,
+                    y: by,
+                    z: bz,
+                },
+            ) => Translate3d {
+                x: ax.interpolate(bx, progress)?,
+                y: ay.interpolate(by, progress)?,
+                z: az.interpolate(bz, progress)?,
+            },
+            (TranslateX { x: a }, TranslateX { x: b }) => TranslateX {
+                x: a.interpolate(b, progress)?,
+            },
+            (TranslateY { y: a }, TranslateY { y: b }) => TranslateY {
+                y: a.interpolate(b, progress)?,
+            },
+            (TranslateZ { z: a }, TranslateZ { z: b }) => TranslateZ {
+                z: a.interpolate(b, progress)?,
+            },
+            _ => {
+                let is_2d = is_2d_function(self) && is_2d_function(to);
+                let from = common_function(self, is_2d);
+                let to = common_function(to, is_2d);
+                if std::mem::discriminant(&from) != std::mem::discriminant(&to) {
+                    return None;
+                }
+                // Pairs of the same function are all handled above
+                return from.interpolate(&to, progress);
+            }
+        })
+    }
+}
+
+/// Rotations around the same axis (or where either angle is zero)
+/// interpolate their angle, which allows turns of over 180deg. Otherwise the
+/// rotations are interpolated as quaternions.
+fn interpolate_rotate3d(
+    from_axis: [f32; 3],
+    from: &Angle,
+    to_axis: [f32; 3],
+    to: &Angle,
+    progress: f64,
+) -> Option<TransformFunction> {
+    let axis = |[x, y, z]: [f32; 3]| transform::normalize([x as f64, y as f64, z as f64]);
+    let (from_axis, to_axis) = (axis(from_axis), axis(to_axis));
+    let rotate3d = |[x, y, z]: [f64; 3], angle| TransformFunction::Rotate3d {
+        x: Number(x as f32),
+        y: Number(y as f32),
+        z: Number(z as f32),
+        angle,
+    };
+
+    let same_axis = from_axis
+        .iter()
+        .zip(&to_axis)
+        .all(|(a, b)| (a - b).abs() < 1e-6);
+    if same_axis || from.0 == 0.0 || to.0 == 0.0 {
+        let axis = if from.0 == 0.0 { to_axis } else { from_axis };
+        return Some(rotate3d(axis, from.interpolate(to, progress)?));
+    }
+
+    let from = transform::quaternion(from_axis, from.to_radians() as f64);
+    let to = transform::quaternion(to_axis, to.to_radians() as f64);
+    let [x, y, z, w] = transform::slerp(from, to, progress);
+    let angle = 2.0 * w.clamp(-1.0, 1.0).acos();
+    let axis = if [x, y, z] == [0.0; 3] {
+        [0.0, 0.0, 1.0]
+    } else {
+        transform::normalize([x, y, z])
+    };
+    Some(rotate3d(
+        axis,
+        Angle(angle.to_degrees() as f32, AngleUnit::Deg),
+    ))
+}
+
+//* Properties
+
+/// Keyword-or-length-percentage values interpolate when both are
+/// length-percentages
+macro_rules! length_percentage_animatable {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl Animatable for properties::$ty {
+                fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+                    match (self, to) {
+                        (
+                            properties::$ty::LengthPercentage(a),
+                            properties::$ty::LengthPercentage(b),
+                        ) => Some(properties::$ty::LengthPercentage(a.interpolate(b, progress)?)),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+length_percentage_animatable!(
+    ColumnGap,
+    InsetValue,
+    LetterSpacing,
+    MarginValue,
+    RowGap,
+    TextDecorationThickness,
+    TextUnderlineOffset,
+    WordSpacing,
+);
+
+/// Properties that wrap a single animatable value
+macro_rules! newtype_animatable {
+    ($($ty:ident),* $(,)?) => {
+        $(
+            impl Animatable for properties::$ty {
+                fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+                    Some(properties::$ty(self.0.interpolate(&to.0, progress)?))
+                }
+            }
+        )*
+    };
+}
+newtype_animatable!(
+    Bottom,
+    FlexGrow,
+    FlexShrink,
+    InsetBlockEnd,
+    InsetBlockStart,
+    InsetInlineEnd,
+    InsetInlineStart,
+    Left,
+    MarginBlockEnd,
+    MarginBlockStart,
+    MarginBottom,
+    MarginInlineEnd,
+    MarginInlineStart,
+    MarginLeft,
+    MarginRight,
+    MarginTop,
+    Order,
+    PaddingBlockEnd,
+    PaddingBlockStart,
+    PaddingBottom,
+    PaddingInlineEnd,
+    PaddingInlineStart,
+    PaddingLeft,
+    PaddingRight,
+    PaddingTop,
+    PerspectiveOrigin,
+    Right,
+    TextDecorationColor,
+    Top,
+);
+
+impl Animatable for properties::ZIndex {
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+        match (self, to) {
+            (properties::ZIndex::Integer(a), properties::ZIndex::Integer(b)) => {
+                Some(properties::ZIndex::Integer(a.interpolate(b, progress)?))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Declarations of the same property interpolate their values, and any others
+/// (including properties whose values aren't [`Animatable`] yet) can't be
+/// interpolated
+macro_rules! property_animatable {
+    ($($variant:ident),* $(,)?) => {
+        impl Animatable for Property {
+            fn interpolate(&self, to: &Self, progress: f64) -> Option<Self> {
+                match (self, to) {
+                    $(
+                        (Property::$variant(a), Property::$variant(b)) => {
+                            Some(Property::$variant(a.interpolate(b, progress)?))
+                        }
+                    )*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+property_animatable!(
+    Bottom,
+    ColumnGap,
+    FlexGrow,
+    FlexShrink,
+    InsetBlockEnd,
+    InsetBlockStart,
+    InsetInlineEnd,
+    InsetInlineStart,
+    Left,
+    LetterSpacing,
+    MarginBlockEnd,
+    MarginBlockStart,
+    MarginBottom,
+    MarginInlineEnd,
+    MarginInlineStart,
+    MarginLeft,
+    MarginRight,
+    MarginTop,
+    Order,
+    PaddingBlockEnd,
+    PaddingBlockStart,
+    PaddingBottom,
+    PaddingInlineEnd,
+    PaddingInlineStart,
+    PaddingLeft,
+    PaddingRight,
+    PaddingTop,
+    PerspectiveOrigin,
+    Right,
+    RowGap,
+    TextDecorationColor,
+    TextDecorationThickness,
+    TextShadow,
+    TextUnderlineOffset,
+    Top,
+    Transform,
+    Visibility,
+    WordSpacing,
+    ZIndex,
+);
+
+//* Keyframes
+
+/// The timing of a single animation, in ms
+///
+/// https://drafts.csswg.org/web-animations-1/#timing-model
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationTiming {
+    pub duration: f64,
+    pub delay: f64,
+    /// Infinite for `infinite`
+    pub iteration_count: f64,
+    pub direction: AnimationDirectionValue,
+    pub fill_mode: AnimationFillModeValue,
+}
+
+/// The progress of an animation at a point in time (see
+/// [`AnimationTiming::progress`])
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnimationProgress {
+    /// The progress through the current iteration from 0 to 1, after
+    /// applying the direction
+    pub progress: f64,
+    /// The index of the current iteration
+    pub iteration: f64,
+    /// The before flag for `steps()` easing functions (see
+    /// [`EasingFunction::evaluate_with_before_flag`])
+    pub before_flag: bool,
+}
+
+impl AnimationTiming {
+    /// Omitted values take their initial values
+    pub fn new(animation: &SingleAnimation) -> Self {
+        Self {
+            duration: animation
+                .duration
+                .map_or(0.0, |duration| duration.to_ms() as f64),
+            delay: animation.delay.map_or(0.0, |delay| delay.to_ms() as f64),
+            iteration_count: match &animation.iteration_count {
+                Some(AnimationIterationCountValue::Infinite) => f64::INFINITY,
+                Some(AnimationIterationCountValue::Finite(count)) => count.get() as f64,
+                None => 1.0,
+            },
+            direction: animation
+                .direction
+                .unwrap_or(AnimationDirectionValue::Normal),
+            fill_mode: animation.fill_mode.unwrap_or(AnimationFillModeValue::None),
+        }
+    }
+
+    /// The length of all the iterations
+    pub fn active_duration(&self) -> f64 {
+        if self.duration == 0.0 || self.iteration_count == 0.0 {
+            0.0
+        } else {
+            self.duration * self.iteration_count
+        }
+    }
+
+    /// The progress at `time` ms after the animation started (including the
+    /// delay), or `None` if the animation has no effect then (when it's
+    /// delayed or finished, and isn't filling)
+    ///
+    /// https://drafts.csswg.org/web-animations-1/#calculating-progress
+    pub fn progress(&self, time: f64) -> Option<AnimationProgress> {
+        let active_duration = self.active_duration();
+        let end = (self.delay + active_duration).max(0.0);
+        let before_active = self.delay.min(end).max(0.0);
+        let active_after = (self.delay + active_duration).min(end).max(0.0);
+
+        let fills = |fill_mode| {
+            self.fill_mode == fill_mode || self.fill_mode == AnimationFillModeValue::Both
+        };
+        let (phase, active_time) = if time < before_active {
+            if !fills(AnimationFillModeValue::Backwards) {
+                return None;
+            }
+            (Phase::Before, (time - self.delay).max(0.0))
+        } else if time >= active_after {
+            if !fills(AnimationFillModeValue::Forwards) {
+                return None;
+            }
+            (
+                Phase::After,
+                (time - self.delay).min(active_duration).max(0.0),
+            )
+        } else {
+            (Phase::Active, time - self.delay)
+        };
+
+        let overall = if self.duration == 0.0 {
+            match phase {
+                Phase::Before => 0.0,
+                _ => self.iteration_count,
+            }
+        } else {
+            active_time / self.duration
+        };
+        let mut simple = if overall.is_infinite() {
+            0.0
+        } else {
+            overall % 1.0
+        };
+        // The end of an iteration is 100% rather than the start of the next
+        if simple == 0.0
+            && phase != Phase::Before
+            && active_time == active_duration
+            && self.iteration_count != 0.0
+        {
+            simple = 1.0;
+        }
+        let iteration = if phase == Phase::After && self.iteration_count.is_infinite() {
+            f64::INFINITY
+        } else if simple == 1.0 {
+            overall.floor() - 1.0
+        } else {
+            overall.floor()
+        };
+
+        let odd = iteration.is_finite() && iteration % 2.0 == 1.0;
+        let reverse = match self.direction {
+            AnimationDirectionValue::Normal => false,
+            AnimationDirectionValue::Reverse => true,
+            AnimationDirectionValue::Alternate => odd,
+            AnimationDirectionValue::AlternateReverse => !odd,
+        };
+        Some(AnimationProgress {
+            progress: if reverse { 1.0 - simple } else { simple },
+            iteration,
+            before_flag: match phase {
+                Phase::Before => !reverse,
+                Phase::Active => false,
+                Phase::After => reverse,
+            },
+        })
+    }
+}
+
+/// https://drafts.csswg.org/web-animations-1/#animation-effect-phases-and-states
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Before,
+    Active,
+    After,
+}
+
+/// A keyframe of a single property
+struct PropertyKeyframe<'a> {
+    offset: f64,
+    value: &'a Property,
+    timing_function: Option<&'a EasingFunction>,
+}
+
+impl Keyframes {
+    /// The value of each property in the keyframes at `time` after `animation`
+    /// started (ignoring its name and play state), or nothing if the
+    /// animation has no effect then.
+    ///
+    /// `underlying` is the element's values of the properties, which are used
+    /// where the keyframes don't include a property at 0% or 100%. Without an
+    /// underlying value, the closest keyframe's value is used. Keyframes with
+    /// a named range are ignored, as they only apply to view timelines, as are
+    /// unparsed declarations.
+    ///
+    /// https://drafts.csswg.org/css-animations-1/#keyframes
+    pub fn sample(
+        &self,
+        animation: &SingleAnimation,
+        time: Time,
+        underlying: &[Property],
+    ) -> Vec<Property> {
+        let AnimationProgress {
+            progress,
+            before_flag,
+            ..
+        } = match AnimationTiming::new(animation).progress(time.to_ms() as f64) {
+            Some(progress) => progress,
+            None => return Vec::new(),
+        };
+        let default_timing_function = animation
+            .timing_function
+            .clone()
+            .unwrap_or(EasingFunction::Ease);
+
+        // Later keyframes with the same offset (and timing function) override
+        // the values of earlier ones
+        let mut properties: Vec<(PropertyDiscriminants, Vec<PropertyKeyframe>)> = Vec::new();
+        for keyframe in &self.keyframes {
+            for offset in keyframe.selectors.iter().filter_map(|s| s.offset()) {
+                let values = keyframe
+                    .declarations
+                    .iter()
+                    .filter_map(|value| match value {
+                        DeclarationValue::Parsed(property) => Some(property),
+                        DeclarationValue::Unparsed { .. } => None,
+                    });
+                for value in values {
+                    let property = PropertyDiscriminants::from(value);
+                    if is_animation_property(property) {
+                        continue;
+                    }
+                    let keyframes = match properties.iter_mut().find(|(p, _)| *p == property) {
+                        Some((_, keyframes)) => keyframes,
+                        None => {
+                            properties.push((property, Vec::new()));
+                            &mut properties.last_mut().unwrap().1
+                        }
+                    };
+                    let timing_function = keyframe.timing_function.as_ref();
+                    match keyframes.iter_mut().find(|keyframe| {
+                        keyframe.offset == offset && keyframe.timing_function == timing_function
+                    }) {
+                        Some(keyframe) => keyframe.value = value,
+                        None => keyframes.push(PropertyKeyframe {
+                            offset,
+                            value,
+                            timing_function,
+                        }),
+                    }
+                }
+            }
+        }
+
+        let underlying: HashMap<_, _> = underlying
+            .iter()
+            .map(|value| (PropertyDiscriminants::from(value), value))
+            .collect();
+        properties
+            .into_iter()
+            .map(|(property, mut keyframes)| {
+                keyframes.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+                // Implicit 0% and 100% keyframes
+                let first = keyframes[0].value;
+                let last = keyframes[keyframes.len() - 1].value;
+                if keyframes[0].offset != 0.0 {
+                    keyframes.insert(
+                        0,
+                        PropertyKeyframe {
+                            offset: 0.0,
+                            value: underlying.get(&property).copied().unwrap_or(first),
+                            timing_function: None,
+                        },
+                    );
+                }
+                if keyframes[keyframes.len() - 1].offset != 1.0 {
+                    keyframes.push(PropertyKeyframe {
+                        offset: 1.0,
+                        value: underlying.get(&property).copied().unwrap_or(last),
+                        timing_function: None,
+                    });
+                }
+
+                // The keyframes either side of the progress, where 100% uses
+                // the last interval
+                let end = keyframes
+                    .iter()
+                    .position(|keyframe| keyframe.offset > progress)
+                    .unwrap_or(keyframes.len() - 1)
+                    .max(1);
+                let (from, to) = (&keyframes[end - 1], &keyframes[end]);
+                if from.offset == to.offset {
+                    return to.value.clone();
+                }
+                let segment_progress = (progress - from.offset) / (to.offset - from.offset);
+                let eased = from
+                    .timing_function
+                    .unwrap_or(&default_timing_function)
+                    .evaluate_with_before_flag(segment_progress, before_flag);
+                interpolate(from.value, to.value, eased)
+            })
+            .collect()
+    }
+}
+
+/// Whether `property` is one of the `animation-*` properties, which are
+/// ignored in keyframes
+pub(crate) fn is_animation_property(property: PropertyDiscriminants) -> bool {
+    use PropertyDiscriminants::*;
+
+    matches!(
+        property,
+        Animation
+            | AnimationComposition
+            | AnimationDelay
+            | AnimationDirection
+            | AnimationDuration
+            | AnimationFillMode
+            | AnimationIterationCount
+            | AnimationName
+            | AnimationPlayState
+            | AnimationRange
+            | AnimationRangeEnd
+            | AnimationRangeStart
+            | AnimationTimeline
+            | AnimationTimingFunction
+    )
+}
+
+/// The value of each property animated by `animation` at `time` after it
+/// started, where each animation uses the last of `keyframes` with its name,
+/// and later animations override the values of earlier ones (see
+/// [`Keyframes::sample`])
+pub fn sample_animation(
+    animation: &Animation,
+    keyframes: &[Keyframes],
+    time: Time,
+    underlying: &[Property],
+) -> Vec<Property> {
+    let mut values: Vec<Property> = Vec::new();
+    for single in &animation.0 {
+        let name = match &single.name {
+            Some(name) => name,
+            None => continue,
+        };
+        let rule = match keyframes.iter().rev().find(|rule| rule.name.matches(name)) {
+            Some(rule) => rule,
+            None => continue,
+        };
+        for value in rule.sample(single, time, underlying) {
+            let property = PropertyDiscriminants::from(&value);
+            values.retain(|other| PropertyDiscriminants::from(other) != property);
+            values.push(value);
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn px(value: f32) -> Length {
+        Length(value, LengthUnit::Px)
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(
+            Number(1.0).interpolate(&Number(3.0), 0.25),
+            Some(Number(1.5))
+        );
+        // Halves round towards positive infinity
+        assert_eq!(Integer(0).interpolate(&Integer(3), 0.5), Some(Integer(2)));
+        assert_eq!(Integer(0).interpolate(&Integer(-3), 0.5), Some(Integer(-1)));
+        // Overshooting easing can't make a positive number negative
+        let zero = PositiveNumber::ZERO;
+        let one = PositiveNumber::ONE;
+        assert_eq!(one.interpolate(&zero, 1.5), Some(zero));
+    }
+
+    #[test]
+    fn angles() {
+        let deg = Angle(90.0, AngleUnit::Deg);
+        let turn = Angle(0.5, AngleUnit::Turn);
+        assert_eq!(
+            Angle(0.0, AngleUnit::Turn).interpolate(&turn, 0.5),
+            Some(Angle(0.25, AngleUnit::Turn))
+        );
+        let mixed = deg.interpolate(&turn, 0.5).unwrap();
+        assert_eq!(mixed.1, AngleUnit::Deg);
+        assert!((mixed.0 - 135.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn lengths() {
+        assert_eq!(px(10.0).interpolate(&px(20.0), 0.5), Some(px(15.0)));
+        // Absolute units are converted to px
+        assert_eq!(
+            px(0.0).interpolate(&Length(1.0, LengthUnit::In), 0.5),
+            Some(Length(0.5, LengthUnit::In))
+        );
+        assert_eq!(
+            px(48.0).interpolate(&Length(1.0, LengthUnit::In), 0.5),
+            Some(px(72.0))
+        );
+        // A zero length takes the other's unit
+        assert_eq!(
+            Length(10.0, LengthUnit::Em).interpolate(&px(0.0), 0.5),
+            Some(Length(5.0, LengthUnit::Em))
+        );
+        // Relative and absolute units can't be combined without calc()
+        assert_eq!(
+            Length(1.0, LengthUnit::Em).interpolate(&px(10.0), 0.5),
+            None
+        );
+    }
+
+    #[test]
+    fn length_percentages() {
+        let from = LengthPercentage::Length(px(10.0));
+        let to = LengthPercentage::Percentage(Percentage(Number(100.0)));
+        assert_eq!(
+            from.interpolate(&to, 0.5),
+            Some(LengthPercentage::Calc(CalcLengthPercentage {
+                lengths: vec![px(5.0)],
+                percentage: Some(Percentage(Number(50.0))),
+            }))
+        );
+        assert_eq!(from.interpolate(&to, 1.0), Some(to.clone()));
+        // Incompatible lengths also become calc()
+        let em = LengthPercentage::Length(Length(2.0, LengthUnit::Em));
+        assert_eq!(
+            em.interpolate(&from, 0.5),
+            Some(LengthPercentage::Calc(CalcLengthPercentage {
+                lengths: vec![Length(1.0, LengthUnit::Em), px(5.0)],
+                percentage: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn blur_keeps_unit() {
+        let from = FilterFunction::Blur {
+            radius: Length(10.0, LengthUnit::Em),
+        };
+        let to = FilterFunction::Blur { radius: px(0.0) };
+        assert_eq!(
+            from.interpolate(&to, 0.5),
+            Some(FilterFunction::Blur {
+                radius: Length(5.0, LengthUnit::Em)
+            })
+        );
+        // Clamped to 0 when overshooting
+        assert_eq!(
+            from.interpolate(&to, 1.5),
+            Some(FilterFunction::Blur {
+                radius: Length(0.0, LengthUnit::Em)
+            })
+        );
+    }
+
+    #[test]
+    fn filter_lists() {
+        let from = vec![FilterFunction::Blur { radius: px(4.0) }];
+        let to = vec![
+            FilterFunction::Blur { radius: px(8.0) },
+            FilterFunction::Grayscale {
+                amount: NumberPercentage::Number(Number(1.0)),
+            },
+        ];
+        let halfway = from.interpolate(&to, 0.5).unwrap();
+        assert_eq!(halfway[0], FilterFunction::Blur { radius: px(6.0) });
+        assert_eq!(halfway.len(), 2);
+        // Lists with different functions can't be interpolated
+        let other = vec![FilterFunction::Grayscale {
+            amount: NumberPercentage::Number(Number(1.0)),
+        }];
+        assert_eq!(from.interpolate(&other, 0.5), None);
+        assert_eq!(interpolate(&from, &other, 0.4), from);
+        assert_eq!(interpolate(&from, &other, 0.6), other);
+    }
+
+    #[test]
+    fn visibility() {
+        let visible = Visibility::Visible;
+        let hidden = Visibility::Hidden;
+        assert_eq!(hidden.interpolate(&visible, 0.1), Some(visible));
+        assert_eq!(visible.interpolate(&hidden, 0.9), Some(visible));
+        assert_eq!(visible.interpolate(&hidden, 1.0), Some(hidden));
+        assert_eq!(hidden.interpolate(&visible, 0.0), Some(hidden));
+    }
+
+    #[test]
+    fn colors() {
+        let from = Color::Hsl(palette::Hsla::new(350.0, 1.0, 0.5, 1.0));
+        let to = Color::Hsl(palette::Hsla::new(30.0, 1.0, 0.5, 1.0));
+        // Hues take the shorter arc
+        let (hsl, _) = components(&from.interpolate_in(&to, 0.5, ColorSpace::Hsl));
+        assert!((hsl[0] - 10.0).abs() < 1e-3);
+        // Alpha is premultiplied
+        let transparent = Color::Rgb(palette::Srgba::new(0.0, 0.0, 1.0, 0.0));
+        let red = Color::Rgb(palette::Srgba::new(1.0, 0.0, 0.0, 1.0));
+        let (rgb, alpha) = components(&transparent.interpolate_in(&red, 0.5, ColorSpace::Srgb));
+        assert_eq!(alpha, 0.5);
+        assert_eq!(rgb, [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn transforms() {
+        let from = vec![TransformFunction::TranslateX {
+            x: LengthPercentage::Length(px(0.0)),
+        }];
+        let to = vec![
+            TransformFunction::TranslateX {
+                x: LengthPercentage::Length(px(100.0)),
+            },
+            TransformFunction::Rotate {
+                angle: Angle(90.0, AngleUnit::Deg),
+            },
+        ];
+        let halfway = from.interpolate(&to, 0.5).unwrap();
+        assert_eq!(
+            halfway,
+            vec![
+                TransformFunction::TranslateX {
+                    x: LengthPercentage::Length(px(50.0)),
+                },
+                TransformFunction::Rotate {
+                    angle: Angle(45.0, AngleUnit::Deg),
+                },
+            ]
+        );
+    }
+
+    fn timing(
+        iteration_count: f64,
+        direction: AnimationDirectionValue,
+        fill_mode: AnimationFillModeValue,
+    ) -> AnimationTiming {
+        AnimationTiming {
+            duration: 1000.0,
+            delay: 0.0,
+            iteration_count,
+            direction,
+            fill_mode,
+        }
+    }
+
+    #[test]
+    fn progress() {
+        let timing = timing(
+            2.0,
+            AnimationDirectionValue::Alternate,
+            AnimationFillModeValue::None,
+        );
+        let progress = timing.progress(250.0).unwrap();
+        assert_eq!((progress.progress, progress.iteration), (0.25, 0.0));
+        // Odd iterations are reversed
+        let progress = timing.progress(1250.0).unwrap();
+        assert_eq!((progress.progress, progress.iteration), (0.75, 1.0));
+        assert_eq!(timing.progress(2000.0), None);
+        assert_eq!(timing.progress(-1.0), None);
+    }
+
+    #[test]
+    fn progress_fill() {
+        let mut timing = timing(
+            2.0,
+            AnimationDirectionValue::Alternate,
+            AnimationFillModeValue::Both,
+        );
+        timing.delay = 500.0;
+        // Before the delay, the first iteration's start fills backwards
+        let before = timing.progress(0.0).unwrap();
+        assert_eq!((before.progress, before.iteration), (0.0, 0.0));
+        assert!(before.before_flag);
+        // The end of the last (reversed) iteration fills forwards
+        let after = timing.progress(5000.0).unwrap();
+        assert_eq!((after.progress, after.iteration), (0.0, 1.0));
+        // Filling forwards in reverse sets the before flag too
+        assert!(after.before_flag);
+        // The end of an iteration is 100% rather than the next one's 0%
+        timing.direction = AnimationDirectionValue::Normal;
+        let after = timing.progress(2500.0).unwrap();
+        assert_eq!((after.progress, after.iteration), (1.0, 1.0));
+    }
+
+    #[test]
+    fn progress_edge_cases() {
+        let timing = timing(
+            f64::INFINITY,
+            AnimationDirectionValue::Normal,
+            AnimationFillModeValue::None,
+        );
+        let progress = timing.progress(10_250.0).unwrap();
+        assert_eq!((progress.progress, progress.iteration), (0.25, 10.0));
+
+        // A zero duration jumps straight to the end
+        let mut timing = timing;
+        timing.duration = 0.0;
+        timing.iteration_count = 1.0;
+        timing.fill_mode = AnimationFillModeValue::Forwards;
+        assert_eq!(timing.active_duration(), 0.0);
+        let progress = timing.progress(0.0).unwrap();
+        assert_eq!((progress.progress, progress.iteration), (1.0, 0.0));
+    }
+
+    fn animation(timing_function: EasingFunction) -> SingleAnimation {
+        SingleAnimation {
+            name: None,
+            duration: PositiveTime::new(1.0, TimeUnit::S),
+            timing_function: Some(timing_function),
+            delay: None,
+            iteration_count: None,
+            direction: None,
+            fill_mode: None,
+            play_state: None,
+        }
+    }
+
+    fn order(value: i32) -> Property {
+        Property::Order(properties::Order(Integer(value)))
+    }
+
+    #[test]
+    fn sample_keyframes() {
+        use crate::at_rules::{Keyframe, KeyframeSelector, KeyframesName};
+
+        let keyframes = Keyframes {
+            name: KeyframesName::Custom(CustomIdent("a".to_owned())),
+            keyframes: vec![Keyframe {
+                selectors: vec![KeyframeSelector::Percentage(Percentage(Number(50.0)))],
+                declarations: vec![DeclarationValue::Parsed(order(10))],
+                timing_function: Some(EasingFunction::Linear),
+            }],
+        };
+        let animation = animation(EasingFunction::Steps(1, properties::EasingStepsJump::End));
+        let at = |ms: f32, underlying: &[Property]| {
+            keyframes.sample(&animation, Time(ms, TimeUnit::Ms), underlying)
+        };
+
+        // The implicit 0% keyframe uses the animation's timing function, and
+        // the 50% keyframe its own
+        assert_eq!(at(250.0, &[order(0)]), vec![order(0)]);
+        assert_eq!(at(750.0, &[order(0)]), vec![order(5)]);
+        // Without an underlying value, the keyframe's value is used throughout
+        assert_eq!(at(250.0, &[]), vec![order(10)]);
+        assert_eq!(at(750.0, &[]), vec![order(10)]);
+        // Nothing after the animation, as it doesn't fill
+        assert_eq!(at(1000.0, &[order(0)]), Vec::new());
+    }
+
+    #[test]
+    fn sample_overlapping_keyframes() {
+        use crate::at_rules::{Keyframe, KeyframeSelector, KeyframesName};
+
+        let keyframe = |offset: f32, value: i32| Keyframe {
+            selectors: vec![KeyframeSelector::Percentage(Percentage(Number(offset)))],
+            declarations: vec![DeclarationValue::Parsed(order(value))],
+            timing_function: None,
+        };
+        // Later keyframes at the same offset override earlier ones
+        let keyframes = Keyframes {
+            name: KeyframesName::Custom(CustomIdent("a".to_owned())),
+            keyframes: vec![keyframe(0.0, 0), keyframe(100.0, 10), keyframe(100.0, 20)],
+        };
+        let animation = animation(EasingFunction::Linear);
+        assert_eq!(
+            keyframes.sample(&animation, Time(500.0, TimeUnit::Ms), &[]),
+            vec![order(10)]
+        );
+    }
+}
@@ -1,24 +1,599 @@
-use crate::data_types;
+use std::{collections::HashMap, string::String as StdString};
+
+use strum::{EnumString, IntoStaticStr};
+
+use crate::{
+    data_types,
+    media_queries::MediaQueryList,
+    properties::{
+        AnimationNameValue, EasingFunction, FamilyName, FontFeatureSettings, FontStretch,
+        FontVariationSettings, FontWeightAbsolute, TimelineRangeName,
+    },
+    rules::{DeclarationValue, Rule},
+    supports::SupportsCondition,
+};
 
 // TODO
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum AtRule {
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-feature-values#@annotation
-    FontFeatureValues {
-        swash: (),
-        annotation: (),
-        ornaments: (),
-        stylistic: (),
-        styleset: (),
-        character_variant: (),
-    },
+    /// Only allowed as the first rule of a stylesheet
+    Charset(data_types::String),
+
+    /// Only allowed after `@charset` and before any other rules (except
+    /// `@layer` statements)
+    Import(ImportRule),
+
+    /// Only allowed after `@charset` and `@import`, and before any other
+    /// rules
+    Namespace(NamespaceRule),
+
+    FontFeatureValues(Box<FontFeatureValues>),
+
+    FontFace(FontFace),
+
+    Keyframes(Keyframes),
+
+    Layer(LayerRule),
+
+    /// `@layer a, b;`, which declares the order of layers without adding
+    /// rules to them
+    LayerStatement(Vec<LayerName>),
+
+    Media(MediaRule),
+
+    Supports(SupportsRule),
+
+    /// An at-rule this crate doesn't know, kept as written so that it isn't
+    /// lost when a stylesheet is bundled
+    Unknown(UnknownAtRule),
+}
+
+/// The name (without `@`), prelude and block contents of an unknown at-rule,
+/// with whitespace trimmed from the ends of the prelude and block
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnknownAtRule {
+    pub name: StdString,
+    pub prelude: StdString,
+    /// `None` for a statement ending in `;`
+    pub block: Option<StdString>,
+}
+
+//* Imports
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@import
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportRule {
+    pub url: data_types::Url,
+    /// The layer to put the imported rules in
+    pub layer: Option<ImportLayer>,
+    /// Only import the stylesheet if this is supported
+    pub supports: Option<SupportsCondition>,
+    /// Only apply the imported rules if this matches (or if it's empty)
+    pub media: MediaQueryList,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportLayer {
+    /// `layer`
+    Anonymous,
+    /// `layer(name)`
+    Named(LayerName),
+}
+impl ImportLayer {
+    pub fn name(&self) -> Option<&LayerName> {
+        match self {
+            ImportLayer::Anonymous => None,
+            ImportLayer::Named(name) => Some(name),
+        }
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@namespace
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamespaceRule {
+    /// `None` declares the default namespace
+    pub prefix: Option<data_types::CustomIdent>,
+    pub url: data_types::Url,
+}
+
+//* Font Face
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face
+///
+/// Descriptors that aren't set are `None` (or empty), which means their
+/// initial value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontFace {
+    pub font_family: FamilyName,
+    /// The sources to try in order, until one is supported and loads
+    pub src: Vec<FontFaceSource>,
+    pub font_style: Option<FontFaceStyle>,
+    pub font_weight: Option<FontFaceWeight>,
+    pub font_stretch: Option<FontFaceStretch>,
+    pub font_display: Option<FontDisplay>,
+    /// The code points the font is used for, where an empty list is all of
+    /// them
+    pub unicode_range: Vec<data_types::UnicodeRange>,
+    pub font_feature_settings: Option<FontFeatureSettings>,
+    pub font_variation_settings: Option<FontVariationSettings>,
+    pub ascent_override: Option<FontFaceMetricOverride>,
+    pub descent_override: Option<FontFaceMetricOverride>,
+    pub line_gap_override: Option<FontFaceMetricOverride>,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face/size-adjust
+    pub size_adjust: Option<data_types::Percentage>,
+}
+impl FontFace {
+    /// A font face with only the required descriptors
+    pub fn new(font_family: FamilyName, src: Vec<FontFaceSource>) -> Self {
+        Self {
+            font_family,
+            src,
+            font_style: None,
+            font_weight: None,
+            font_stretch: None,
+            font_display: None,
+            unicode_range: Vec::new(),
+            font_feature_settings: None,
+            font_variation_settings: None,
+            ascent_override: None,
+            descent_override: None,
+            line_gap_override: None,
+            size_adjust: None,
+        }
+    }
 
-    FontFace {
-        ascent_override: FontFaceAscentOverride,
+    /// Whether the font is used for `c`, according to `unicode-range`
+    pub fn covers(&self, c: char) -> bool {
+        self.unicode_range.is_empty() || self.unicode_range.iter().any(|range| range.contains(c))
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face/src
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontFaceSource {
+    Url {
+        url: data_types::Url,
+        /// A source with an unknown format is skipped
+        format: Option<FontFaceFormat>,
+        /// A source is skipped unless all of its technologies are supported
+        tech: Vec<FontTech>,
     },
+    /// `local()`, a font installed on the system
+    Local(FamilyName),
+}
+/// The argument of `format()`, where strings are the legacy syntax
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontFaceFormat {
+    Keyword(FontFormat),
+    String(data_types::String),
+}
+impl FontFaceFormat {
+    /// The format, if it's known (strings are matched like keywords)
+    pub fn format(&self) -> Option<FontFormat> {
+        match self {
+            FontFaceFormat::Keyword(format) => Some(*format),
+            FontFaceFormat::String(string) => string.0.parse().ok(),
+        }
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face/src#font_formats
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum FontFormat {
+    Collection,
+    EmbeddedOpentype,
+    Opentype,
+    Svg,
+    Truetype,
+    Woff,
+    Woff2,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face/src#font_technologies
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum FontTech {
+    FeaturesOpentype,
+    FeaturesAat,
+    FeaturesGraphite,
+    #[strum(serialize = "color-COLRv0")]
+    ColorColrv0,
+    #[strum(serialize = "color-COLRv1")]
+    ColorColrv1,
+    #[strum(serialize = "color-SVG")]
+    ColorSvg,
+    ColorSbix,
+    #[strum(serialize = "color-CBDT")]
+    ColorCbdt,
+    Variations,
+    Palettes,
+    Incremental,
 }
 
-pub enum FontFaceAscentOverride {
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face/font-style
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontFaceStyle {
+    Auto,
+    Normal,
+    Italic,
+    /// A range of angles between -90deg and 90deg, where a single angle is
+    /// both the start and the end (and no angle is 14deg)
+    Oblique(Option<(data_types::Angle, data_types::Angle)>),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face/font-weight
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontFaceWeight {
+    Auto,
+    /// A single weight is both the start and the end
+    Range(FontWeightAbsolute, FontWeightAbsolute),
+}
+impl FontFaceWeight {
+    /// The weights covered, from lightest to boldest, or `None` for `auto`
+    pub fn range(&self) -> Option<(f32, f32)> {
+        match self {
+            FontFaceWeight::Auto => None,
+            FontFaceWeight::Range(start, end) => Some(ordered(start.value(), end.value())),
+        }
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face/font-stretch
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontFaceStretch {
+    Auto,
+    /// A single width is both the start and the end
+    Range(FontStretch, FontStretch),
+}
+impl FontFaceStretch {
+    /// The width percentages covered, from narrowest to widest, or `None` for
+    /// `auto`
+    pub fn range(&self) -> Option<(f32, f32)> {
+        let percentage = |stretch: &FontStretch| match stretch {
+            FontStretch::Keyword(keyword) => keyword.percentage(),
+            FontStretch::Percentage(percentage) => percentage.0 .0,
+        };
+        match self {
+            FontFaceStretch::Auto => None,
+            FontFaceStretch::Range(start, end) => Some(ordered(percentage(start), percentage(end))),
+        }
+    }
+}
+
+/// Ranges written backwards are used as if they were swapped
+fn ordered(start: f32, end: f32) -> (f32, f32) {
+    if start > end {
+        (end, start)
+    } else {
+        (start, end)
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face/font-display
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum FontDisplay {
+    Auto,
+    Block,
+    Swap,
+    Fallback,
+    Optional,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face/ascent-override
+///
+/// Also used for `descent-override` and `line-gap-override`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontFaceMetricOverride {
     Normal,
     Percentage(data_types::Percentage),
 }
+
+//* Font Feature Values
+/// Names for the values of `font-variant-alternates` functions, for some
+/// font families
+///
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-feature-values
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontFeatureValues {
+    pub families: Vec<FamilyName>,
+    pub swash: FeatureValues,
+    pub annotation: FeatureValues,
+    pub ornaments: FeatureValues,
+    pub stylistic: FeatureValues,
+    pub styleset: FeatureValues,
+    pub character_variant: FeatureValues,
+}
+/// The values defined in one feature block, like `@swash { fancy: 1; }`
+pub type FeatureValues = HashMap<data_types::CustomIdent, Vec<u32>>;
+impl FontFeatureValues {
+    pub fn new(families: Vec<FamilyName>) -> Self {
+        Self {
+            families,
+            swash: HashMap::new(),
+            annotation: HashMap::new(),
+            ornaments: HashMap::new(),
+            stylistic: HashMap::new(),
+            styleset: HashMap::new(),
+            character_variant: HashMap::new(),
+        }
+    }
+
+    pub fn block(&self, block: FeatureValueBlock) -> &FeatureValues {
+        match block {
+            FeatureValueBlock::Swash => &self.swash,
+            FeatureValueBlock::Annotation => &self.annotation,
+            FeatureValueBlock::Ornaments => &self.ornaments,
+            FeatureValueBlock::Stylistic => &self.stylistic,
+            FeatureValueBlock::Styleset => &self.styleset,
+            FeatureValueBlock::CharacterVariant => &self.character_variant,
+        }
+    }
+    pub fn block_mut(&mut self, block: FeatureValueBlock) -> &mut FeatureValues {
+        match block {
+            FeatureValueBlock::Swash => &mut self.swash,
+            FeatureValueBlock::Annotation => &mut self.annotation,
+            FeatureValueBlock::Ornaments => &mut self.ornaments,
+            FeatureValueBlock::Stylistic => &mut self.stylistic,
+            FeatureValueBlock::Styleset => &mut self.styleset,
+            FeatureValueBlock::CharacterVariant => &mut self.character_variant,
+        }
+    }
+
+    /// Define `name` in `block`, replacing any earlier definition, unless
+    /// `values` has the wrong number of values for the block (in which case
+    /// the declaration is ignored and `false` is returned)
+    pub fn insert(
+        &mut self,
+        block: FeatureValueBlock,
+        name: data_types::CustomIdent,
+        values: Vec<u32>,
+    ) -> bool {
+        if !block.accepts(&values) {
+            return false;
+        }
+        self.block_mut(block).insert(name, values);
+        true
+    }
+
+    /// Whether the rule applies to `family` (family names are ASCII
+    /// case-insensitive)
+    pub fn applies_to(&self, family: &FamilyName) -> bool {
+        let family = family.name();
+        self.families
+            .iter()
+            .any(|name| name.name().eq_ignore_ascii_case(&family))
+    }
+
+    /// The values `name` refers to in `block` for `family`, where later rules
+    /// take precedence
+    pub fn lookup<'a>(
+        rules: &'a [FontFeatureValues],
+        family: &FamilyName,
+        block: FeatureValueBlock,
+        name: &data_types::CustomIdent,
+    ) -> Option<&'a [u32]> {
+        rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.applies_to(family))
+            .find_map(|rule| rule.block(block).get(name))
+            .map(Vec::as_slice)
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-feature-values#feature_value_blocks
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum FeatureValueBlock {
+    Swash,
+    Annotation,
+    Ornaments,
+    Stylistic,
+    Styleset,
+    CharacterVariant,
+}
+impl FeatureValueBlock {
+    /// Whether `values` is allowed for a name in this block
+    ///
+    /// https://drafts.csswg.org/css-fonts-4/#multi-value-features
+    pub fn accepts(&self, values: &[u32]) -> bool {
+        match self {
+            FeatureValueBlock::Swash
+            | FeatureValueBlock::Annotation
+            | FeatureValueBlock::Ornaments
+            | FeatureValueBlock::Stylistic => values.len() == 1,
+            // Style sets above 20 are allowed, but have no effect
+            FeatureValueBlock::Styleset => {
+                !values.is_empty() && values.iter().all(|value| (1..=99).contains(value))
+            }
+            // A character variant, then the value to enable it with
+            FeatureValueBlock::CharacterVariant => {
+                matches!(values, [variant] | [variant, _] if (1..=99).contains(variant))
+            }
+        }
+    }
+}
+
+//* Conditional Rules
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaRule {
+    pub queries: MediaQueryList,
+    pub rules: Vec<Rule>,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@supports
+#[derive(Clone, Debug, PartialEq)]
+pub struct SupportsRule {
+    pub condition: SupportsCondition,
+    pub rules: Vec<Rule>,
+}
+
+//* Cascade Layers
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@layer
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerRule {
+    /// `None` for an anonymous layer
+    pub name: Option<LayerName>,
+    pub rules: Vec<Rule>,
+}
+/// A layer name, with a part for each nested layer (like `framework.base`)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LayerName(pub Vec<data_types::CustomIdent>);
+
+//* Keyframes
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@keyframes
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyframes {
+    pub name: KeyframesName,
+    pub keyframes: Vec<Keyframe>,
+}
+/// The name of a [`Keyframes`] rule, which can be a string to allow names
+/// that aren't valid identifiers
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyframesName {
+    Custom(data_types::CustomIdent),
+    String(data_types::String),
+}
+impl KeyframesName {
+    pub fn as_str(&self) -> &str {
+        match self {
+            KeyframesName::Custom(ident) => &ident.0,
+            KeyframesName::String(string) => &string.0,
+        }
+    }
+
+    /// Whether this is the name an `animation-name` refers to (identifiers
+    /// and strings with the same value are the same name)
+    pub fn matches(&self, name: &AnimationNameValue) -> bool {
+        match name {
+            AnimationNameValue::None => false,
+            AnimationNameValue::Custom(ident) => self.as_str() == ident.0,
+            AnimationNameValue::String(string) => self.as_str() == string.0,
+        }
+    }
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@keyframes#keyframe_block
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keyframe {
+    pub selectors: Vec<KeyframeSelector>,
+    /// Animation properties (other than `animation-timing-function`) and
+    /// `!important` declarations are ignored in keyframes
+    pub declarations: Vec<DeclarationValue>,
+    /// The `animation-timing-function` used from this keyframe until the
+    /// next, which defaults to the animation's timing function
+    pub timing_function: Option<EasingFunction>,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@keyframes#keyframe_selector
+#[derive(Clone, Debug, PartialEq)]
+pub enum KeyframeSelector {
+    /// 0%
+    From,
+    /// 100%
+    To,
+    Percentage(data_types::Percentage),
+    /// A percentage of a named range of a view timeline, like `entry 50%`
+    Range {
+        name: TimelineRangeName,
+        offset: data_types::Percentage,
+    },
+}
+impl KeyframeSelector {
+    /// The offset from 0 to 1, or `None` for a named range (which can only
+    /// be resolved against a view timeline) or a percentage outside 0-100%
+    pub fn offset(&self) -> Option<f64> {
+        let offset = match self {
+            KeyframeSelector::From => 0.0,
+            KeyframeSelector::To => 1.0,
+            KeyframeSelector::Percentage(percentage) => percentage.0 .0 as f64 / 100.0,
+            KeyframeSelector::Range { .. } => return None,
+        };
+        (0.0..=1.0).contains(&offset).then_some(offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_types::CustomIdent;
+
+    fn family(name: &str) -> FamilyName {
+        FamilyName::Quoted(data_types::String(name.to_owned()))
+    }
+
+    fn ident(name: &str) -> CustomIdent {
+        CustomIdent(name.to_owned())
+    }
+
+    #[test]
+    fn accepts() {
+        use FeatureValueBlock::*;
+        assert!(Swash.accepts(&[0]));
+        assert!(Stylistic.accepts(&[3]));
+        assert!(!Swash.accepts(&[]));
+        assert!(!Ornaments.accepts(&[1, 2]));
+        assert!(Styleset.accepts(&[1, 20, 99]));
+        assert!(!Styleset.accepts(&[]));
+        assert!(!Styleset.accepts(&[0]));
+        assert!(!Styleset.accepts(&[1, 100]));
+        assert!(CharacterVariant.accepts(&[1]));
+        assert!(CharacterVariant.accepts(&[99, 0]));
+        assert!(!CharacterVariant.accepts(&[0]));
+        assert!(!CharacterVariant.accepts(&[0, 1]));
+        assert!(!CharacterVariant.accepts(&[100, 1]));
+        assert!(!CharacterVariant.accepts(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn insert() {
+        let mut rule = FontFeatureValues::new(vec![family("A")]);
+        assert!(rule.insert(FeatureValueBlock::Swash, ident("fancy"), vec![1]));
+        assert!(rule.insert(FeatureValueBlock::Swash, ident("fancy"), vec![2]));
+        assert!(!rule.insert(FeatureValueBlock::Swash, ident("fancy"), vec![3, 4]));
+        assert!(!rule.insert(FeatureValueBlock::Styleset, ident("none"), vec![0]));
+        assert_eq!(rule.swash.get(&ident("fancy")), Some(&vec![2]));
+        assert!(rule.styleset.is_empty());
+    }
+
+    #[test]
+    fn lookup() {
+        let mut first = FontFeatureValues::new(vec![
+            family("A"),
+            FamilyName::Unquoted(vec![ident("Other"), ident("Family")]),
+        ]);
+        first.insert(FeatureValueBlock::Swash, ident("fancy"), vec![1]);
+        first.insert(FeatureValueBlock::Styleset, ident("alt"), vec![2, 4]);
+        let mut second = FontFeatureValues::new(vec![family("a")]);
+        second.insert(FeatureValueBlock::Swash, ident("fancy"), vec![2]);
+        let rules = [first, second];
+
+        let lookup = |family: &FamilyName, block, name| {
+            FontFeatureValues::lookup(&rules, family, block, &ident(name))
+        };
+        // Later rules win, and family names are case-insensitive
+        assert_eq!(
+            lookup(&family("A"), FeatureValueBlock::Swash, "fancy"),
+            Some(&[2][..])
+        );
+        assert_eq!(
+            lookup(&family("A"), FeatureValueBlock::Styleset, "alt"),
+            Some(&[2, 4][..])
+        );
+        assert_eq!(
+            lookup(&family("other family"), FeatureValueBlock::Swash, "fancy"),
+            Some(&[1][..])
+        );
+        // Names are per block and per family
+        assert_eq!(
+            lookup(&family("A"), FeatureValueBlock::Ornaments, "fancy"),
+            None
+        );
+        assert_eq!(
+            lookup(&family("B"), FeatureValueBlock::Swash, "fancy"),
+            None
+        );
+        assert_eq!(
+            lookup(&family("A"), FeatureValueBlock::Swash, "Fancy"),
+            None
+        );
+    }
+}
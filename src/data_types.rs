@@ -1,20 +1,92 @@
 //! CSS Data Types, as described in https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_Types
 
-use std::string::String as StdString;
+use std::{convert::TryInto, string::String as StdString};
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/custom-ident
-pub struct CustomIdent(StdString);
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CustomIdent(pub StdString);
 
 /// A [`CustomIdent`] prefixed by two dashes
-pub struct DashedIdent(StdString);
+#[derive(Clone, Debug, PartialEq)]
+pub struct DashedIdent(pub StdString);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/string
-pub struct String(StdString);
+#[derive(Clone, Debug, PartialEq)]
+pub struct String(pub StdString);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/url()
-pub struct Url(StdString); // TODO: use url::Url
+#[derive(Clone, Debug, PartialEq)]
+pub struct Url(pub StdString); // TODO: use url::Url
 
+/// A four character OpenType tag, as used for font features and variation
+/// axes
+///
+/// https://docs.microsoft.com/en-us/typography/opentype/spec/otff#data-types
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OpenTypeTag([u8; 4]);
+impl OpenTypeTag {
+    /// Returns `None` unless `tag` is exactly four characters in U+20-7E
+    pub fn new(tag: &str) -> Option<Self> {
+        let bytes: [u8; 4] = tag.as_bytes().try_into().ok()?;
+        bytes
+            .iter()
+            .all(|b| (0x20..=0x7E).contains(b))
+            .then_some(Self(bytes))
+    }
+    pub fn as_str(&self) -> &str {
+        // Only ASCII bytes are allowed by `new`
+        std::str::from_utf8(&self.0).unwrap()
+    }
+}
+
+/// An inclusive range of Unicode code points, like `U+0025-00FF` or `U+4??`
+///
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@font-face/unicode-range
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UnicodeRange {
+    start: u32,
+    end: u32,
+}
+impl UnicodeRange {
+    /// The largest code point
+    pub const MAX: u32 = 0x10FFFF;
+    /// All code points, which is the default `unicode-range`
+    pub const ALL: Self = Self {
+        start: 0,
+        end: Self::MAX,
+    };
+
+    /// Returns `None` if `start` is after `end` or `end` is past
+    /// [`UnicodeRange::MAX`]
+    pub fn new(start: u32, end: u32) -> Option<Self> {
+        (start <= end && end <= Self::MAX).then_some(Self { start, end })
+    }
+    /// A range with trailing `?` wildcards, like `U+4??` (a prefix of `0x4` with two
+    /// wildcards) for U+400-4FF
+    pub fn wildcard(prefix: u32, wildcards: u32) -> Option<Self> {
+        let size = 16u32.checked_pow(wildcards)?;
+        let start = prefix.checked_mul(size)?;
+        Self::new(start, start.checked_add(size - 1)?)
+    }
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+    pub fn end(&self) -> u32 {
+        self.end
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.contains_code_point(c as u32)
+    }
+    /// Like [`UnicodeRange::contains`], but also accepts surrogates
+    pub fn contains_code_point(&self, code_point: u32) -> bool {
+        (self.start..=self.end).contains(&code_point)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum Keyword {}
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_Types#textual_data_types
+#[derive(Clone, Debug, PartialEq)]
 pub enum Textual {
     CustomIdent(CustomIdent),
     DashedIdent(DashedIdent),
@@ -29,14 +101,31 @@ pub enum Textual {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/integer
-pub struct Integer(i32);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Integer(pub i32);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/number
-pub struct Number(f32);
-/// A number that cannot be negative // TODO: constructor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Number(pub f32);
+/// A number that cannot be negative
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct PositiveNumber(f32);
+impl PositiveNumber {
+    pub const ZERO: Self = Self(0.0);
+    pub const ONE: Self = Self(1.0);
+
+    /// Returns `None` if `value` is negative
+    pub fn new(value: f32) -> Option<Self> {
+        (value >= 0.0).then_some(Self(value))
+    }
+    pub fn get(&self) -> f32 {
+        self.0
+    }
+}
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/dimension
-pub struct Dimension(f32, Unit);
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dimension(pub f32, pub Unit);
 // TODO
+#[derive(Clone, Debug, PartialEq)]
 pub enum Unit {
     Length(LengthUnit),
     Angle(AngleUnit),
@@ -51,15 +140,26 @@ pub trait IntoUnit {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/percentage
-pub struct Percentage(Number);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Percentage(pub Number);
 /// A ratio in the form (width, height)
 ///
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/ratio
-pub struct Ratio(Number, Number);
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ratio(pub Number, pub Number);
+impl Ratio {
+    /// The width divided by the height, which is NaN for the degenerate ratio
+    /// `0/0`
+    pub fn value(&self) -> f64 {
+        self.0 .0 as f64 / self.1 .0 as f64
+    }
+}
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/flex_value
-pub struct Flex(Number);
+#[derive(Clone, Debug, PartialEq)]
+pub struct Flex(pub Number);
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_Types#numeric_data_types
+#[derive(Clone, Debug, PartialEq)]
 pub enum Numeric {
     Integer(Integer),
     Number(Number),
@@ -71,8 +171,26 @@ pub enum Numeric {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/length
-pub struct Length(f32, LengthUnit);
-pub struct PositiveLength(f32, LengthUnit); // TODO: constructor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Length(pub f32, pub LengthUnit);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PositiveLength(f32, LengthUnit);
+impl PositiveLength {
+    /// Returns `None` if `value` is negative
+    pub fn new(value: f32, unit: LengthUnit) -> Option<Self> {
+        (value >= 0.0).then_some(Self(value, unit))
+    }
+    pub fn value(&self) -> f32 {
+        self.0
+    }
+    pub fn unit(&self) -> &LengthUnit {
+        &self.1
+    }
+    pub fn to_px(&self, context: &LengthContext) -> f32 {
+        Length(self.0, self.1).to_px(context)
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum LengthUnit {
     //* Font Relative Lengths
     /// Represents the width, or more precisely the advance measure, of the
@@ -132,6 +250,38 @@ pub enum LengthUnit {
     /// One point. 1pt = 1/72nd of 1in.
     Pt,
 }
+impl Length {
+    /// Resolve to px
+    pub fn to_px(&self, context: &LengthContext) -> f32 {
+        let Length(value, unit) = *self;
+        match unit {
+            LengthUnit::Ch | LengthUnit::Ex => value * context.font_size * 0.5,
+            LengthUnit::Em => value * context.font_size,
+            LengthUnit::Rem => value * context.root_font_size,
+            LengthUnit::Vh => value * context.viewport_height / 100.0,
+            LengthUnit::Vw => value * context.viewport_width / 100.0,
+            LengthUnit::Vmin => value * context.viewport_width.min(context.viewport_height) / 100.0,
+            LengthUnit::Vmax => value * context.viewport_width.max(context.viewport_height) / 100.0,
+            LengthUnit::Px => value,
+            LengthUnit::Cm => value * 96.0 / 2.54,
+            LengthUnit::Mm => value * 96.0 / 25.4,
+            LengthUnit::In => value * 96.0,
+            LengthUnit::Pc => value * 16.0,
+            LengthUnit::Pt => value * 96.0 / 72.0,
+        }
+    }
+}
+
+/// The values needed to resolve relative lengths to px
+///
+/// `ch` and `ex` are approximated as 0.5em.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LengthContext {
+    pub font_size: f32,
+    pub root_font_size: f32,
+    pub viewport_width: f32,
+    pub viewport_height: f32,
+}
 impl IntoDimension for Length {
     fn into_dimension(self) -> Dimension {
         Dimension(self.0, Unit::Length(self.1))
@@ -146,7 +296,27 @@ impl IntoUnit for LengthUnit {
 /// Clockwise Angle
 ///
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/angle
-pub struct Angle(f32, AngleUnit); // TODO: Optional unit?
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Angle(pub f32, pub AngleUnit); // TODO: Optional unit?
+impl Angle {
+    pub fn to_radians(&self) -> f32 {
+        match self.1 {
+            AngleUnit::Deg => self.0.to_radians(),
+            AngleUnit::Grad => self.0 * std::f32::consts::PI / 200.0,
+            AngleUnit::Rad => self.0,
+            AngleUnit::Turn => self.0 * std::f32::consts::TAU,
+        }
+    }
+    pub fn to_degrees(&self) -> f32 {
+        match self.1 {
+            AngleUnit::Deg => self.0,
+            AngleUnit::Grad => self.0 * 0.9,
+            AngleUnit::Rad => self.0.to_degrees(),
+            AngleUnit::Turn => self.0 * 360.0,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AngleUnit {
     /// Represents an angle in degrees. One full circle is 360deg.
     Deg,
@@ -170,9 +340,35 @@ impl IntoUnit for AngleUnit {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/time
-pub struct Time(f32, TimeUnit);
-pub struct PositiveTime(f32, TimeUnit); // TODO: construct with only t >= 0
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Time(pub f32, pub TimeUnit);
+impl Time {
+    pub fn to_ms(&self) -> f32 {
+        match self.1 {
+            TimeUnit::S => self.0 * 1000.0,
+            TimeUnit::Ms => self.0,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PositiveTime(f32, TimeUnit);
+impl PositiveTime {
+    /// Returns `None` if `value` is negative
+    pub fn new(value: f32, unit: TimeUnit) -> Option<Self> {
+        (value >= 0.0).then_some(Self(value, unit))
+    }
+    pub fn value(&self) -> f32 {
+        self.0
+    }
+    pub fn unit(&self) -> &TimeUnit {
+        &self.1
+    }
+    pub fn to_ms(&self) -> f32 {
+        Time(self.0, self.1).to_ms()
+    }
+}
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TimeUnit {
     /// Represents a time in seconds
     S,
@@ -191,7 +387,9 @@ impl IntoUnit for TimeUnit {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/frequency
-pub struct Frequency(f32, FrequencyUnit);
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frequency(pub f32, pub FrequencyUnit);
+#[derive(Clone, Debug, PartialEq)]
 pub enum FrequencyUnit {
     /// Represents a frequency in hertz
     Hz,
@@ -199,7 +397,19 @@ pub enum FrequencyUnit {
     KHz,
 }
 
-pub struct Resolution(f32, ResolutionUnit);
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resolution(pub f32, pub ResolutionUnit);
+impl Resolution {
+    /// Resolve to dots per px
+    pub fn to_dppx(&self) -> f32 {
+        match self.1 {
+            ResolutionUnit::Dpi => self.0 / 96.0,
+            ResolutionUnit::DpCm => self.0 * 2.54 / 96.0,
+            ResolutionUnit::DpPx => self.0,
+        }
+    }
+}
+#[derive(Clone, Debug, PartialEq)]
 pub enum ResolutionUnit {
     /// Represents the number of dots per inch. Screens typically contains 72
     /// or 96 dots per inch, but the dpi for printed documents is usually much
@@ -220,6 +430,7 @@ pub enum ResolutionUnit {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_Types#quantities
+#[derive(Clone, Debug, PartialEq)]
 pub enum Quantity {
     Length(Length),
     Angle(Angle),
@@ -230,11 +441,39 @@ pub enum Quantity {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/length-percentage
+#[derive(Clone, Debug, PartialEq)]
 pub enum LengthPercentage {
     Length(Length),
     Percentage(Percentage),
+    Calc(CalcLengthPercentage),
+}
+impl LengthPercentage {
+    /// Resolve to px, with percentages relative to `percentage_basis` (in px)
+    pub fn to_px(&self, context: &LengthContext, percentage_basis: f32) -> f32 {
+        match self {
+            LengthPercentage::Length(length) => length.to_px(context),
+            LengthPercentage::Percentage(percentage) => percentage.0 .0 * percentage_basis / 100.0,
+            LengthPercentage::Calc(calc) => {
+                calc.lengths
+                    .iter()
+                    .map(|length| length.to_px(context))
+                    .sum::<f32>()
+                    + calc
+                        .percentage
+                        .map_or(0.0, |percentage| percentage.0 .0 * percentage_basis / 100.0)
+            }
+        }
+    }
+}
+/// A `calc()` sum of lengths and a percentage, like `calc(10px + 2em - 50%)`
+#[derive(Clone, Debug, PartialEq)]
+pub struct CalcLengthPercentage {
+    /// The length of each unit in the sum
+    pub lengths: Vec<Length>,
+    pub percentage: Option<Percentage>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum LengthNumber {
     Length(Length),
     Number(Number),
@@ -246,18 +485,21 @@ pub enum LengthNumber {
 // }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/angle-percentage
+#[derive(Clone, Debug, PartialEq)]
 pub enum AnglePercentage {
     Angle(Angle),
     Percentage(Percentage),
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/time-percentage
+#[derive(Clone, Debug, PartialEq)]
 pub enum TimePercentage {
     Time(Time),
     Percentage(Percentage),
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_Types#combinations_of_types
+#[derive(Clone, Debug, PartialEq)]
 pub enum Combination {
     LengthPercentage(LengthPercentage),
     // FrequencyPercentage(FrequencyPercentage),
@@ -265,25 +507,136 @@ pub enum Combination {
     TimePercentage(TimePercentage),
 }
 
-// TODO
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/color_value
+///
+/// Components use the ranges of the `palette` types (like 0 to 1 for sRGB
+/// channels, and 0 to 100 for CIE lightness).
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Color {
     // Keyword(ColorKeyword),
-    Rgb,
+    /// `rgb()`, hex and named colors
+    Rgb(palette::Srgba),
+    Hsl(palette::Hsla),
+    /// CIE LCH, which (unlike `palette`'s default) is relative to D50
+    Lch(palette::Lcha<palette::white_point::D50>),
+    /// CIE Lab, which (unlike `palette`'s default) is relative to D50
+    Lab(palette::Laba<palette::white_point::D50>),
+    Oklab(palette::Oklaba),
+    Oklch(palette::Oklcha),
+}
+impl Color {
+    pub fn alpha(&self) -> f32 {
+        match self {
+            Color::Rgb(color) => color.alpha,
+            Color::Hsl(color) => color.alpha,
+            Color::Lch(color) => color.alpha,
+            Color::Lab(color) => color.alpha,
+            Color::Oklab(color) => color.alpha,
+            Color::Oklch(color) => color.alpha,
+        }
+    }
+
+    /// Convert to CIE XYZ relative to D65 (without gamut mapping), which
+    /// every color space can be converted to and from
+    pub fn to_xyz(&self) -> palette::Xyz {
+        use palette::{chromatic_adaptation::AdaptFrom, convert::IntoColorUnclamped};
+
+        let d50 = |xyz: palette::Xyz<palette::white_point::D50>| palette::Xyz::adapt_from(xyz);
+        match self {
+            Color::Rgb(color) => color.color.into_linear().into_color_unclamped(),
+            Color::Hsl(color) => {
+                let rgb: palette::Srgb = color.color.into_color_unclamped();
+                rgb.into_linear().into_color_unclamped()
+            }
+            Color::Lch(color) => d50(color.color.into_color_unclamped()),
+            Color::Lab(color) => d50(color.color.into_color_unclamped()),
+            Color::Oklab(color) => color.color.into_color_unclamped(),
+            Color::Oklch(color) => {
+                let oklab: palette::Oklab = color.color.into_color_unclamped();
+                oklab.into_color_unclamped()
+            }
+        }
+    }
+
+    pub fn space(&self) -> ColorSpace {
+        match self {
+            Color::Rgb(_) => ColorSpace::Srgb,
+            Color::Hsl(_) => ColorSpace::Hsl,
+            Color::Lch(_) => ColorSpace::Lch,
+            Color::Lab(_) => ColorSpace::Lab,
+            Color::Oklab(_) => ColorSpace::Oklab,
+            Color::Oklch(_) => ColorSpace::Oklch,
+        }
+    }
+
+    /// Convert to another color space (without gamut mapping)
+    pub fn to_space(&self, space: ColorSpace) -> Color {
+        use palette::{
+            chromatic_adaptation::AdaptFrom, convert::FromColorUnclamped, white_point::D50, Alpha,
+        };
+
+        if self.space() == space {
+            return *self;
+        }
+        let xyz = self.to_xyz();
+        let alpha = self.alpha();
+        let srgb = || palette::Srgb::from_linear(palette::LinSrgb::from_color_unclamped(xyz));
+        let lab =
+            || palette::Lab::<D50>::from_color_unclamped(palette::Xyz::<D50>::adapt_from(xyz));
+        match space {
+            ColorSpace::Srgb => Color::Rgb(Alpha {
+                color: srgb(),
+                alpha,
+            }),
+            ColorSpace::Hsl => Color::Hsl(Alpha {
+                color: palette::Hsl::from_color_unclamped(srgb()),
+                alpha,
+            }),
+            ColorSpace::Lab => Color::Lab(Alpha {
+                color: lab(),
+                alpha,
+            }),
+            ColorSpace::Lch => Color::Lch(Alpha {
+                color: palette::Lch::from_color_unclamped(lab()),
+                alpha,
+            }),
+            ColorSpace::Oklab => Color::Oklab(Alpha {
+                color: palette::Oklab::from_color_unclamped(xyz),
+                alpha,
+            }),
+            ColorSpace::Oklch => Color::Oklch(Alpha {
+                color: palette::Oklch::from_color_unclamped(palette::Oklab::from_color_unclamped(
+                    xyz,
+                )),
+                alpha,
+            }),
+        }
+    }
+}
+/// The color spaces a [`Color`] can be in
+///
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/color-interpolation-method
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorSpace {
+    Srgb,
     Hsl,
-    Lch,
     Lab,
+    Lch,
+    Oklab,
+    Oklch,
 }
 
 /// Specifies the transparency of a color. May be a <number>, in which case 0 is
 /// fully transparent and 1 is fully opaque, or a <percentage>, in which case 0%
 /// is fully transparent and 100% fully opaque.
+#[derive(Clone, Debug, PartialEq)]
 pub enum Alpha {
     Number(Number), // Only 0 to 1
     Percentage(Percentage),
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/image
+#[derive(Clone, Debug, PartialEq)]
 pub enum Image {
     Url(Url),
     Gradient(Gradient),
@@ -296,6 +649,7 @@ pub enum Image {
 // TODO: Gradient Types
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/gradient
+#[derive(Clone, Debug, PartialEq)]
 pub enum Gradient {
     Linear {
         from: Color,
@@ -311,6 +665,7 @@ pub enum Gradient {
     },
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum RadialGradientShape {}
 
 // pub enum GradientDirection {
@@ -328,6 +683,7 @@ pub enum RadialGradientShape {}
 
 // TODO: Move to data types
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/filter-function
+#[derive(Clone, Debug, PartialEq)]
 pub enum FilterFunction {
     /// https://developer.mozilla.org/en-US/docs/Web/CSS/filter-function/blur()
     Blur { radius: Length },
@@ -359,12 +715,106 @@ pub enum FilterFunction {
     /// https://developer.mozilla.org/en-US/docs/Web/CSS/filter-function/sepia()
     Sepia { amount: NumberPercentage },
 }
+#[derive(Clone, Debug, PartialEq)]
 pub enum NumberPercentage {
     Number(Number),
     Percentage(Percentage),
 }
+impl NumberPercentage {
+    /// The value as a number, where 100% is 1
+    pub fn value(&self) -> f32 {
+        match self {
+            NumberPercentage::Number(number) => number.0,
+            NumberPercentage::Percentage(percentage) => percentage.0 .0 / 100.0,
+        }
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransformFunction {
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/matrix()
+    Matrix {
+        a: Number,
+        b: Number,
+        c: Number,
+        d: Number,
+        tx: Number,
+        ty: Number,
+    },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/matrix3d()
+    ///
+    /// Values are in column-major order.
+    Matrix3d { values: [Number; 16] },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/perspective()
+    ///
+    /// `None` is the `none` keyword (an infinite distance).
+    Perspective { distance: Option<PositiveLength> },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/rotate()
+    Rotate { angle: Angle },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/rotate3d()
+    Rotate3d {
+        x: Number,
+        y: Number,
+        z: Number,
+        angle: Angle,
+    },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/rotateX()
+    RotateX { angle: Angle },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/rotateY()
+    RotateY { angle: Angle },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/rotateZ()
+    RotateZ { angle: Angle },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/scale()
+    ///
+    /// `y` defaults to `x`.
+    Scale {
+        x: NumberPercentage,
+        y: Option<NumberPercentage>,
+    },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/scale3d()
+    Scale3d {
+        x: NumberPercentage,
+        y: NumberPercentage,
+        z: NumberPercentage,
+    },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/scaleX()
+    ScaleX { x: NumberPercentage },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/scaleY()
+    ScaleY { y: NumberPercentage },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/scaleZ()
+    ScaleZ { z: NumberPercentage },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/skew()
+    ///
+    /// `y` defaults to 0.
+    Skew { x: Angle, y: Option<Angle> },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/skewX()
+    SkewX { x: Angle },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/skewY()
+    SkewY { y: Angle },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/translate()
+    ///
+    /// `y` defaults to 0.
+    Translate {
+        x: LengthPercentage,
+        y: Option<LengthPercentage>,
+    },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/translate3d()
+    Translate3d {
+        x: LengthPercentage,
+        y: LengthPercentage,
+        z: Length,
+    },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/translateX()
+    TranslateX { x: LengthPercentage },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/translateY()
+    TranslateY { y: LengthPercentage },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-function/translateZ()
+    TranslateZ { z: Length },
+}
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/blend-mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BlendMode {
     Normal,
     Multiply,
@@ -385,11 +835,13 @@ pub enum BlendMode {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/position_value
+#[derive(Clone, Debug, PartialEq)]
 pub struct Position {
     pub x: PositionX,
     pub y: PositionY, // TODO: formal syntax vs BackgroundPosition syntax
 }
 /// X position (with optional offset)
+#[derive(Clone, Debug, PartialEq)]
 pub enum PositionX {
     Left(Option<LengthPercentage>),
     Center,
@@ -397,6 +849,7 @@ pub enum PositionX {
     Value(LengthPercentage),
 }
 /// Y position (with optional offset)
+#[derive(Clone, Debug, PartialEq)]
 pub enum PositionY {
     Top(Option<LengthPercentage>),
     Center,
@@ -405,6 +858,7 @@ pub enum PositionY {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/basic-shape
+#[derive(Clone, Debug, PartialEq)]
 pub enum BasicShape {
     Inset(InsetRectange),
     Circle(Circle),
@@ -414,39 +868,45 @@ pub enum BasicShape {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/basic-shape/inset()
+#[derive(Clone, Debug, PartialEq)]
 pub struct InsetRectange {
-    top: LengthPercentage,
-    left: LengthPercentage,
-    bottom: LengthPercentage,
-    right: LengthPercentage,
-    border_radius: RadiusCorner,
+    pub top: LengthPercentage,
+    pub left: LengthPercentage,
+    pub bottom: LengthPercentage,
+    pub right: LengthPercentage,
+    pub border_radius: RadiusCorner,
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/basic-shape/circle()
+#[derive(Clone, Debug, PartialEq)]
 pub struct Circle {
-    radius: ShapeRadius,
-    position: Option<Position>,
+    pub radius: ShapeRadius,
+    pub position: Option<Position>,
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/basic-shape/ellipse()
+#[derive(Clone, Debug, PartialEq)]
 pub struct Ellipse {
-    x_radius: ShapeRadius,
-    y_radius: ShapeRadius,
-    position: Option<Position>,
+    pub x_radius: ShapeRadius,
+    pub y_radius: ShapeRadius,
+    pub position: Option<Position>,
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/basic-shape/polygon()
+#[derive(Clone, Debug, PartialEq)]
 pub struct Polygon {
-    fill_rule: FillRule,
-    points: Vec<(LengthPercentage, LengthPercentage)>, // TODO: minimum 3
+    pub fill_rule: FillRule,
+    pub points: Vec<(LengthPercentage, LengthPercentage)>, // TODO: minimum 3
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub struct Path {
     // TODO: SvgPath?
-    fill_rule: FillRule,
-    svg_path: String,
+    pub fill_rule: FillRule,
+    pub svg_path: String,
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum ShapeRadius {
     // TODO: positive
     Radius(LengthPercentage),
@@ -454,20 +914,39 @@ pub enum ShapeRadius {
     FarthestSide,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum FillRule {
     Nonzero,
     EvenOdd,
 }
 
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/width#values
+#[derive(Clone, Debug, PartialEq)]
 pub enum WidthValue {
     Length(Length),
     Percentage(Percentage),
     Auto,
     MaxContent,
     MinContent,
-    FitContent(LengthPercentage),
+    /// `fit-content`, or `fit-content(<length-percentage>)`
+    FitContent(Option<LengthPercentage>),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/max-width#values
+///
+/// Like [`WidthValue`], but with `none` in place of `auto`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MaxSizeValue {
+    None,
+    Length(Length),
+    Percentage(Percentage),
+    MaxContent,
+    MinContent,
+    /// `fit-content`, or `fit-content(<length-percentage>)`
+    FitContent(Option<LengthPercentage>),
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum LineStyle {
     None,
     Hidden,
@@ -481,6 +960,7 @@ pub enum LineStyle {
     Outset,
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum LineWidth {
     Thin,
     Medium,
@@ -488,6 +968,7 @@ pub enum LineWidth {
     Value(PositiveLength),
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum RadiusCorner {
     Circle(LengthPercentage),
     Ellipse {
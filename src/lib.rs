@@ -1,8 +1,15 @@
+pub mod animation;
 pub mod at_rules;
 pub mod data_types;
 pub mod functions;
+#[cfg(feature = "parser")]
+pub mod loader;
+pub mod media_queries;
 pub mod properties;
+pub mod rules;
 pub mod selectors;
+pub mod supports;
+pub mod transform;
 
 #[cfg(feature = "parser")]
 pub mod parser;
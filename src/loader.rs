@@ -0,0 +1,552 @@
+//! Bundling a stylesheet and everything it imports into a single stylesheet
+
+use std::{
+    collections::HashMap,
+    error, fmt, fs, io,
+    path::{Path, PathBuf},
+    string::String as StdString,
+};
+
+use crate::{
+    at_rules::{AtRule, ImportRule, LayerRule, MediaRule, NamespaceRule, SupportsRule},
+    data_types::{CustomIdent, Url},
+    parser::parse_stylesheet,
+    rules::Rule,
+};
+
+/// Finds and reads stylesheets
+pub trait Loader {
+    /// The location of the stylesheet `url` refers to, relative to the
+    /// location of the stylesheet importing it (or `None` for the entry
+    /// point)
+    ///
+    /// Every URL for the same stylesheet must give the same location, as
+    /// locations are compared to find cycles.
+    fn resolve(&self, url: &str, base: Option<&str>) -> Result<StdString, LoadError>;
+
+    fn load(&mut self, location: &str) -> Result<StdString, LoadError>;
+}
+
+/// Loads stylesheets from files, where URLs are paths relative to the
+/// importing stylesheet (or to the root, for the entry point and for paths
+/// starting with `/`)
+#[derive(Clone, Debug)]
+pub struct FileSystemLoader {
+    pub root: PathBuf,
+}
+impl FileSystemLoader {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+impl Loader for FileSystemLoader {
+    fn resolve(&self, url: &str, base: Option<&str>) -> Result<StdString, LoadError> {
+        if url.contains("://") {
+            return Err(LoadError::UnsupportedUrl(url.to_owned()));
+        }
+        let path = match (url.strip_prefix('/'), base) {
+            (Some(url), _) => self.root.join(url),
+            (None, Some(base)) => Path::new(base).parent().unwrap_or(&self.root).join(url),
+            (None, None) => self.root.join(url),
+        };
+        // Canonical paths are the same however the file is referred to
+        let path = fs::canonicalize(&path).map_err(|error| LoadError::Io {
+            location: path.to_string_lossy().into_owned(),
+            error,
+        })?;
+        Ok(path.to_string_lossy().into_owned())
+    }
+
+    fn load(&mut self, location: &str) -> Result<StdString, LoadError> {
+        fs::read_to_string(location).map_err(|error| LoadError::Io {
+            location: location.to_owned(),
+            error,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io {
+        location: StdString,
+        error: io::Error,
+    },
+    /// A URL the loader can't load (like a remote URL for a
+    /// [`FileSystemLoader`])
+    UnsupportedUrl(StdString),
+    /// Stylesheets that import each other, from the first stylesheet in the
+    /// cycle back to itself
+    Cycle(Vec<StdString>),
+    /// An imported stylesheet with a different default namespace than the
+    /// entry point (where `None` is no default namespace), which can't be
+    /// combined since the default namespace applies to the whole bundle
+    DefaultNamespaceConflict(Option<Url>, Option<Url>),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io { location, error } => write!(f, "couldn't load {}: {}", location, error),
+            LoadError::UnsupportedUrl(url) => write!(f, "can't load {}", url),
+            LoadError::Cycle(locations) => write!(f, "import cycle: {}", locations.join(" -> ")),
+            LoadError::DefaultNamespaceConflict(first, second) => {
+                let url =
+                    |url: &Option<Url>| url.as_ref().map_or("(none)", |url| &url.0).to_owned();
+                write!(
+                    f,
+                    "default namespace is both {} and {}",
+                    url(first),
+                    url(second),
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            LoadError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Load the stylesheet at `url` and replace its `@import` rules with the
+/// rules they import, recursively
+///
+/// Imported rules are wrapped in the `@import`'s media queries, supports
+/// condition and layer, so `@import "a.css" layer(base) supports(display:
+/// grid) print` becomes `@media print { @supports (display: grid) { @layer
+/// base { ... } } }`. `@charset` rules are dropped.
+///
+/// `@namespace` rules from every stylesheet are moved to the start. Where
+/// stylesheets use the same prefix for different namespaces, the later
+/// stylesheet's prefix is renamed (like `svg` to `svg2`) in its selectors.
+/// Every stylesheet must have the same default namespace as the entry point
+/// though, as it applies to every selector in the bundle.
+pub fn bundle(loader: &mut impl Loader, url: &str) -> Result<Vec<Rule>, LoadError> {
+    let mut bundler = Bundler {
+        loader,
+        stack: Vec::new(),
+        default_namespace: None,
+        namespaces: Vec::new(),
+    };
+    let location = bundler.loader.resolve(url, None)?;
+    let rules = bundler.load(location)?;
+    let default_namespace = bundler
+        .default_namespace
+        .map(|url| NamespaceRule { prefix: None, url });
+    Ok(default_namespace
+        .into_iter()
+        .chain(bundler.namespaces)
+        .map(|namespace| Rule::At(AtRule::Namespace(namespace)))
+        .chain(rules)
+        .collect())
+}
+
+struct Bundler<'a, L> {
+    loader: &'a mut L,
+    /// The stylesheets being loaded, from the entry point to the current one
+    stack: Vec<StdString>,
+    /// The entry point's default namespace
+    default_namespace: Option<Url>,
+    /// The prefixed namespaces of every stylesheet, where each prefix is
+    /// unique
+    namespaces: Vec<NamespaceRule>,
+}
+
+impl<L: Loader> Bundler<'_, L> {
+    fn load(&mut self, location: StdString) -> Result<Vec<Rule>, LoadError> {
+        if let Some(i) = self.stack.iter().position(|loading| *loading == location) {
+            let mut cycle = self.stack[i..].to_vec();
+            cycle.push(location);
+            return Err(LoadError::Cycle(cycle));
+        }
+        let source = self.loader.load(&location)?;
+        let stylesheet = parse_stylesheet(&source);
+        let renamed = self.add_namespaces(&stylesheet)?;
+        self.stack.push(location);
+
+        let mut rules = Vec::new();
+        for rule in stylesheet {
+            match rule {
+                Rule::At(AtRule::Import(import)) => {
+                    let base = self.stack.last().map(StdString::as_str);
+                    let location = self.loader.resolve(&import.url.0, base)?;
+                    let imported = self.load(location)?;
+                    rules.extend(wrap(import, imported));
+                }
+                Rule::At(AtRule::Namespace(_)) | Rule::At(AtRule::Charset(_)) => {}
+                mut rule => {
+                    if !renamed.is_empty() {
+                        rename_namespaces(&mut rule, &renamed);
+                    }
+                    rules.push(rule);
+                }
+            }
+        }
+
+        self.stack.pop();
+        Ok(rules)
+    }
+
+    /// Add the namespaces of a stylesheet to the bundle, returning the
+    /// prefixes that had to be renamed (and their new names)
+    fn add_namespaces(
+        &mut self,
+        stylesheet: &[Rule],
+    ) -> Result<HashMap<StdString, StdString>, LoadError> {
+        // Only the last declaration of a prefix (or the default namespace)
+        // is used
+        let mut default_namespace = None;
+        let mut prefixes: Vec<(CustomIdent, Url)> = Vec::new();
+        for rule in stylesheet {
+            match rule {
+                Rule::At(AtRule::Namespace(NamespaceRule { prefix: None, url })) => {
+                    default_namespace = Some(url.clone());
+                }
+                Rule::At(AtRule::Namespace(NamespaceRule {
+                    prefix: Some(prefix),
+                    url,
+                })) => {
+                    prefixes.retain(|(existing, _)| existing != prefix);
+                    prefixes.push((prefix.clone(), url.clone()));
+                }
+                _ => {}
+            }
+        }
+
+        if self.stack.is_empty() {
+            self.default_namespace = default_namespace;
+        } else if default_namespace != self.default_namespace {
+            return Err(LoadError::DefaultNamespaceConflict(
+                self.default_namespace.clone(),
+                default_namespace,
+            ));
+        }
+
+        let mut renamed = HashMap::new();
+        for (prefix, url) in prefixes {
+            let mut name = prefix.0.clone();
+            for n in 2.. {
+                let existing = self.namespaces.iter().find(|namespace| {
+                    namespace
+                        .prefix
+                        .as_ref()
+                        .is_some_and(|existing| existing.0 == name)
+                });
+                match existing {
+                    Some(existing) if existing.url == url => break,
+                    Some(_) => name = format!("{}{}", prefix.0, n),
+                    None => {
+                        self.namespaces.push(NamespaceRule {
+                            prefix: Some(CustomIdent(name.clone())),
+                            url,
+                        });
+                        break;
+                    }
+                }
+            }
+            if name != prefix.0 {
+                renamed.insert(prefix.0, name);
+            }
+        }
+        Ok(renamed)
+    }
+}
+
+/// Rename namespace prefixes in the selectors of a rule and the rules nested
+/// in it
+fn rename_namespaces(rule: &mut Rule, renamed: &HashMap<StdString, StdString>) {
+    let rules = match rule {
+        Rule::Style(style) => {
+            style.selectors.rename_namespaces(&mut |prefix| {
+                if let Some(name) = renamed.get(prefix) {
+                    *prefix = name.clone();
+                }
+            });
+            &mut style.rules
+        }
+        Rule::At(AtRule::Media(MediaRule { rules, .. }))
+        | Rule::At(AtRule::Supports(SupportsRule { rules, .. }))
+        | Rule::At(AtRule::Layer(LayerRule { rules, .. })) => rules,
+        Rule::At(_) => return,
+    };
+    for rule in rules {
+        rename_namespaces(rule, renamed);
+    }
+}
+
+/// Wrap imported rules in the conditions and layer of the `@import`
+fn wrap(import: ImportRule, mut rules: Vec<Rule>) -> Vec<Rule> {
+    if let Some(layer) = import.layer {
+        rules = vec![Rule::At(AtRule::Layer(LayerRule {
+            name: layer.name().cloned(),
+            rules,
+        }))];
+    }
+    if let Some(condition) = import.supports {
+        rules = vec![Rule::At(AtRule::Supports(SupportsRule {
+            condition,
+            rules,
+        }))];
+    }
+    if !import.media.0.is_empty() {
+        rules = vec![Rule::At(AtRule::Media(MediaRule {
+            queries: import.media,
+            rules,
+        }))];
+    }
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::at_rules::LayerName;
+
+    /// Loads stylesheets from memory, where URLs are the locations
+    struct MemoryLoader(HashMap<&'static str, &'static str>);
+    impl MemoryLoader {
+        fn new(stylesheets: &[(&'static str, &'static str)]) -> Self {
+            Self(stylesheets.iter().copied().collect())
+        }
+    }
+    impl Loader for MemoryLoader {
+        fn resolve(&self, url: &str, _: Option<&str>) -> Result<StdString, LoadError> {
+            Ok(url.to_owned())
+        }
+
+        fn load(&mut self, location: &str) -> Result<StdString, LoadError> {
+            self.0
+                .get(location)
+                .map(|source| source.to_string())
+                .ok_or_else(|| LoadError::UnsupportedUrl(location.to_owned()))
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let imported = "b { -webkit-box-flex: 1; transition: none; --x: { y } }
+            @font-feature-values Font { @swash { fancy: 1 } }";
+        let rest = "a { display: grid !important; transition-delay: var(--delay) }
+            @page :first { margin: 0 }";
+        let mut loader = MemoryLoader::new(&[
+            (
+                "main.css",
+                "@charset \"utf-8\";
+                @import \"a.css\" print;
+                a { display: grid !important; transition-delay: var(--delay) }
+                @page :first { margin: 0 }",
+            ),
+            ("a.css", imported),
+        ]);
+
+        let mut expected = wrap(
+            parse_stylesheet("@import \"a.css\" print;")
+                .into_iter()
+                .find_map(|rule| match rule {
+                    Rule::At(AtRule::Import(import)) => Some(import),
+                    _ => None,
+                })
+                .unwrap(),
+            parse_stylesheet(imported),
+        );
+        expected.extend(parse_stylesheet(rest));
+        assert_eq!(bundle(&mut loader, "main.css").unwrap(), expected);
+        // Nothing was dropped along the way
+        assert!(matches!(
+            expected.as_slice(),
+            [Rule::At(AtRule::Media(media)), Rule::Style(style), Rule::At(AtRule::Unknown(_))]
+                if media.rules.len() == 2 && style.declarations.len() == 2
+        ));
+    }
+
+    fn namespace(prefix: Option<&str>, url: &str) -> Rule {
+        Rule::At(AtRule::Namespace(NamespaceRule {
+            prefix: prefix.map(|prefix| CustomIdent(prefix.to_owned())),
+            url: Url(url.to_owned()),
+        }))
+    }
+
+    fn selectors(rule: &Rule) -> StdString {
+        match rule {
+            Rule::Style(style) => style.selectors.to_string(),
+            rule => panic!("{:?}", rule),
+        }
+    }
+
+    #[test]
+    fn namespaces() {
+        let mut loader = MemoryLoader::new(&[
+            (
+                "main.css",
+                "@import \"a.css\";
+                @namespace svg url(svg);
+                @namespace math url(math);
+                svg|a, math|b {}",
+            ),
+            (
+                "a.css",
+                "@namespace svg url(other);
+                @namespace svg2 url(svg);
+                @namespace math url(math);
+                @namespace xlink url(xlink);
+                svg|a:is(svg2|b) [svg|href] { @media print { svg|c { & math|d {} } } }
+                xlink|e, other|f {}",
+            ),
+        ]);
+        let rules = bundle(&mut loader, "main.css").unwrap();
+        assert_eq!(
+            rules[..5],
+            [
+                namespace(Some("svg"), "svg"),
+                namespace(Some("math"), "math"),
+                namespace(Some("svg2"), "other"),
+                namespace(Some("svg22"), "svg"),
+                namespace(Some("xlink"), "xlink"),
+            ]
+        );
+        // Prefixes are renamed in every selector of the stylesheet, including
+        // nested ones, and undeclared prefixes are left alone
+        assert_eq!(selectors(&rules[5]), "svg2|a:is(svg22|b) [svg2|href]");
+        let nested = match &rules[5] {
+            Rule::Style(style) => match &style.rules[..] {
+                [Rule::At(AtRule::Media(media))] => media.rules[0].clone(),
+                rules => panic!("{:?}", rules),
+            },
+            rule => panic!("{:?}", rule),
+        };
+        assert_eq!(selectors(&nested), "& svg2|c");
+        match &nested {
+            Rule::Style(style) => assert_eq!(selectors(&style.rules[0]), "& math|d"),
+            rule => panic!("{:?}", rule),
+        }
+        assert_eq!(selectors(&rules[6]), "xlink|e, other|f");
+        assert_eq!(selectors(&rules[7]), "svg|a, math|b");
+    }
+
+    #[test]
+    fn default_namespaces() {
+        let mut loader = MemoryLoader::new(&[
+            ("main.css", "@import \"a.css\"; @namespace url(html); a {}"),
+            ("a.css", "@namespace url(html); @namespace url(html); b {}"),
+        ]);
+        let rules = bundle(&mut loader, "main.css").unwrap();
+        assert_eq!(rules[0], namespace(None, "html"));
+        assert_eq!(rules.len(), 3);
+
+        let mut loader = MemoryLoader::new(&[
+            ("main.css", "@import \"a.css\"; a {}"),
+            ("a.css", "@namespace url(svg); b {}"),
+        ]);
+        let error = bundle(&mut loader, "main.css").unwrap_err();
+        assert!(matches!(
+            &error,
+            LoadError::DefaultNamespaceConflict(None, Some(url)) if url.0 == "svg"
+        ));
+        assert_eq!(
+            error.to_string(),
+            "default namespace is both (none) and svg"
+        );
+
+        // Only the last default namespace of a stylesheet is used
+        let mut loader = MemoryLoader::new(&[
+            ("main.css", "@import \"a.css\"; @namespace url(html);"),
+            ("a.css", "@namespace url(html); @namespace url(svg);"),
+        ]);
+        assert!(bundle(&mut loader, "main.css").is_err());
+    }
+
+    #[test]
+    fn cycles() {
+        let mut loader = MemoryLoader::new(&[
+            ("main.css", "@import \"a.css\";"),
+            ("a.css", "@import \"b.css\"; a {}"),
+            ("b.css", "@import \"a.css\"; b {}"),
+        ]);
+        let error = bundle(&mut loader, "main.css").unwrap_err();
+        assert!(matches!(
+            &error,
+            LoadError::Cycle(cycle) if *cycle == ["a.css", "b.css", "a.css"]
+        ));
+        assert_eq!(error.to_string(), "import cycle: a.css -> b.css -> a.css");
+
+        let mut loader = MemoryLoader::new(&[("main.css", "@import \"main.css\";")]);
+        assert!(matches!(
+            bundle(&mut loader, "main.css"),
+            Err(LoadError::Cycle(cycle)) if *cycle == ["main.css", "main.css"]
+        ));
+
+        // Importing the same stylesheet twice isn't a cycle
+        let mut loader = MemoryLoader::new(&[
+            ("main.css", "@import \"a.css\"; @import \"b.css\";"),
+            ("a.css", "@import \"c.css\";"),
+            ("b.css", "@import \"c.css\";"),
+            ("c.css", "c {}"),
+        ]);
+        assert_eq!(bundle(&mut loader, "main.css").unwrap().len(), 2);
+
+        let mut loader = MemoryLoader::new(&[("main.css", "@import \"missing.css\";")]);
+        assert!(matches!(
+            bundle(&mut loader, "main.css"),
+            Err(LoadError::UnsupportedUrl(url)) if url == "missing.css"
+        ));
+    }
+
+    #[test]
+    fn ordering() {
+        let mut loader = MemoryLoader::new(&[
+            (
+                "main.css",
+                "@charset \"utf-8\";
+                @import \"a.css\" layer(base) supports(display: grid) print;
+                @import \"b.css\" layer;
+                @namespace svg url(svg);
+                main {}",
+            ),
+            (
+                "a.css",
+                "@charset \"utf-8\";
+                @import \"c.css\" screen;
+                @namespace math url(math);
+                a {}",
+            ),
+            ("b.css", "b {}"),
+            ("c.css", "c {}"),
+        ]);
+        let rules = bundle(&mut loader, "main.css").unwrap();
+        // Namespaces come first, in the order the stylesheets were loaded,
+        // and `@charset` rules are dropped
+        assert_eq!(rules.len(), 5);
+        assert_eq!(rules[0], namespace(Some("svg"), "svg"));
+        assert_eq!(rules[1], namespace(Some("math"), "math"));
+
+        // Imported rules replace the `@import`, wrapped in its media
+        // queries, then supports condition, then layer
+        let layer = match &rules[2] {
+            Rule::At(AtRule::Media(media)) => match &media.rules[..] {
+                [Rule::At(AtRule::Supports(supports))] => match &supports.rules[..] {
+                    [Rule::At(AtRule::Layer(layer))] => layer,
+                    rules => panic!("{:?}", rules),
+                },
+                rules => panic!("{:?}", rules),
+            },
+            rule => panic!("{:?}", rule),
+        };
+        assert_eq!(
+            layer.name,
+            Some(LayerName(vec![CustomIdent("base".to_owned())]))
+        );
+        assert!(matches!(
+            &layer.rules[..],
+            [Rule::At(AtRule::Media(media)), a] if selectors(&media.rules[0]) == "c" && selectors(a) == "a"
+        ));
+        assert!(matches!(
+            &rules[3],
+            Rule::At(AtRule::Layer(LayerRule { name: None, rules })) if selectors(&rules[0]) == "b"
+        ));
+        assert_eq!(selectors(&rules[4]), "main");
+    }
+}
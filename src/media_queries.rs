@@ -0,0 +1,771 @@
+//! Media queries, as described in https://drafts.csswg.org/mediaqueries-5/
+
+use std::string::String as StdString;
+
+use strum::{EnumString, IntoStaticStr};
+
+use crate::data_types::{Integer, Length, LengthContext, Ratio, Resolution};
+
+/// A comma-separated list of media queries, which matches if any of them do
+/// (or if it's empty)
+///
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media#syntax
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MediaQueryList(pub Vec<MediaQuery>);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_media_queries/Using_media_queries
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaQuery {
+    /// Only allowed with a media type
+    pub qualifier: Option<MediaQualifier>,
+    /// `None` for a query that's only a condition, like `(width > 400px)`
+    pub media_type: Option<MediaType>,
+    /// Without a media type, this can't be `None`. With one, it's joined with
+    /// `and` (and can't use `or` at the top level).
+    pub condition: Option<MediaCondition>,
+}
+impl MediaQuery {
+    /// `not all`, which is what invalid queries in a list become
+    pub fn not_all() -> Self {
+        Self {
+            qualifier: Some(MediaQualifier::Not),
+            media_type: Some(MediaType::All),
+            condition: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MediaQualifier {
+    /// Negates the whole query
+    Not,
+    /// Hides the query from legacy user agents, without changing its meaning
+    Only,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media#media_types
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MediaType {
+    All,
+    Print,
+    Screen,
+    /// Deprecated (like `tv`) and unknown media types, which match nothing
+    Other(StdString),
+}
+
+/// https://drafts.csswg.org/mediaqueries-5/#media-conditions
+#[derive(Clone, Debug, PartialEq)]
+pub enum MediaCondition {
+    Feature(MediaFeature),
+    Not(Box<MediaCondition>),
+    And(Vec<MediaCondition>),
+    Or(Vec<MediaCondition>),
+    /// Parentheses or a function that isn't a valid condition or feature,
+    /// which is unknown (so neither it nor `not` of it matches)
+    ///
+    /// https://drafts.csswg.org/mediaqueries-5/#typedef-general-enclosed
+    GeneralEnclosed,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media#media_features
+#[derive(Clone, Debug, PartialEq)]
+pub enum MediaFeature {
+    /// A feature on its own, like `(hover)`, which matches unless its value
+    /// is zero, `none` or `no-preference`
+    Boolean(MediaFeatureName),
+    /// `(name: value)`, where range features can have a prefix, like
+    /// `(min-width: 400px)`
+    Plain {
+        name: MediaFeatureName,
+        prefix: Option<RangePrefix>,
+        value: MediaFeatureValue,
+    },
+    /// The range syntax, like `(width >= 400px)` or
+    /// `(400px <= width < 800px)`
+    Range {
+        name: MediaFeatureName,
+        /// A value and comparison left of the name
+        start: Option<(MediaFeatureValue, RangeOperator)>,
+        /// A comparison and value right of the name
+        end: Option<(RangeOperator, MediaFeatureValue)>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangePrefix {
+    /// `min-`, at least the value
+    Min,
+    /// `max-`, at most the value
+    Max,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RangeOperator {
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `>`
+    Gt,
+    /// `>=`
+    Ge,
+    /// `=`
+    Eq,
+}
+impl RangeOperator {
+    pub fn compare(&self, left: f64, right: f64) -> bool {
+        match self {
+            RangeOperator::Lt => left < right,
+            RangeOperator::Le => left <= right,
+            RangeOperator::Gt => left > right,
+            RangeOperator::Ge => left >= right,
+            RangeOperator::Eq => left == right,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MediaFeatureValue {
+    Length(Length),
+    Integer(Integer),
+    Ratio(Ratio),
+    Resolution(Resolution),
+    Keyword(StdString),
+}
+impl MediaFeatureValue {
+    /// The value as a number in the unit the environment uses (px, dppx or
+    /// a ratio's quotient), or `None` for keywords
+    fn to_number(&self, environment: &MediaEnvironment) -> Option<f64> {
+        Some(match self {
+            MediaFeatureValue::Length(length) => length.to_px(&environment.length_context()) as f64,
+            MediaFeatureValue::Integer(integer) => integer.0 as f64,
+            MediaFeatureValue::Ratio(ratio) => ratio.value(),
+            MediaFeatureValue::Resolution(resolution) => resolution.to_dppx() as f64,
+            MediaFeatureValue::Keyword(_) => return None,
+        })
+    }
+}
+
+/// https://drafts.csswg.org/mediaqueries-5/#media-descriptor-table
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum MediaFeatureName {
+    AnyHover,
+    AnyPointer,
+    AspectRatio,
+    Color,
+    ColorGamut,
+    ColorIndex,
+    DeviceAspectRatio,
+    DeviceHeight,
+    DeviceWidth,
+    DisplayMode,
+    DynamicRange,
+    ForcedColors,
+    Grid,
+    Height,
+    Hover,
+    InvertedColors,
+    Monochrome,
+    Orientation,
+    OverflowBlock,
+    OverflowInline,
+    Pointer,
+    PrefersColorScheme,
+    PrefersContrast,
+    PrefersReducedData,
+    PrefersReducedMotion,
+    PrefersReducedTransparency,
+    Resolution,
+    Scan,
+    Scripting,
+    Update,
+    VideoDynamicRange,
+    Width,
+}
+
+/// The values a media feature takes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MediaFeatureType {
+    Length,
+    Integer,
+    Ratio,
+    Resolution,
+    /// `0` or `1`
+    Boolean,
+    Keyword(&'static [&'static str]),
+}
+impl MediaFeatureType {
+    /// Whether the feature can be compared with the range syntax and
+    /// `min-`/`max-` prefixes
+    pub fn is_range(&self) -> bool {
+        !matches!(
+            self,
+            MediaFeatureType::Boolean | MediaFeatureType::Keyword(_)
+        )
+    }
+}
+
+impl MediaFeatureName {
+    pub fn value_type(&self) -> MediaFeatureType {
+        use MediaFeatureName::*;
+        use MediaFeatureType::Keyword;
+        match self {
+            AspectRatio | DeviceAspectRatio => MediaFeatureType::Ratio,
+            Color | ColorIndex | Monochrome => MediaFeatureType::Integer,
+            DeviceHeight | DeviceWidth | Height | Width => MediaFeatureType::Length,
+            Grid => MediaFeatureType::Boolean,
+            Resolution => MediaFeatureType::Resolution,
+            AnyHover | Hover => Keyword(&["none", "hover"]),
+            AnyPointer | Pointer => Keyword(&["none", "coarse", "fine"]),
+            ColorGamut => Keyword(&["srgb", "p3", "rec2020"]),
+            DisplayMode => Keyword(&[
+                "fullscreen",
+                "standalone",
+                "minimal-ui",
+                "browser",
+                "picture-in-picture",
+            ]),
+            DynamicRange | VideoDynamicRange => Keyword(&["standard", "high"]),
+            ForcedColors => Keyword(&["none", "active"]),
+            InvertedColors => Keyword(&["none", "inverted"]),
+            Orientation => Keyword(&["portrait", "landscape"]),
+            OverflowBlock => Keyword(&["none", "scroll", "paged"]),
+            OverflowInline => Keyword(&["none", "scroll"]),
+            PrefersColorScheme => Keyword(&["light", "dark"]),
+            PrefersContrast => Keyword(&["no-preference", "more", "less", "custom"]),
+            PrefersReducedData | PrefersReducedMotion | PrefersReducedTransparency => {
+                Keyword(&["no-preference", "reduce"])
+            }
+            Scan => Keyword(&["interlace", "progressive"]),
+            Scripting => Keyword(&["none", "initial-only", "enabled"]),
+            Update => Keyword(&["none", "slow", "fast"]),
+        }
+    }
+}
+
+//* Environment
+/// The device and user preferences that media queries are evaluated against
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaEnvironment {
+    /// [`MediaType::Screen`] or [`MediaType::Print`]
+    pub media_type: MediaType,
+    /// The viewport width in px
+    pub width: f32,
+    /// The viewport height in px
+    pub height: f32,
+    pub device_width: f32,
+    pub device_height: f32,
+    /// The initial font size in px, which `em` and `rem` in media queries are
+    /// relative to
+    pub font_size: f32,
+    /// Device pixels per px
+    pub resolution: f32,
+    /// Bits per color component, or 0 for monochrome devices
+    pub color: u32,
+    /// Entries in the color lookup table, or 0 without one
+    pub color_index: u32,
+    /// Bits per pixel of a monochrome device, or 0 for color devices
+    pub monochrome: u32,
+    pub color_gamut: ColorGamut,
+    pub display_mode: DisplayMode,
+    pub dynamic_range: DynamicRange,
+    pub video_dynamic_range: DynamicRange,
+    pub forced_colors: bool,
+    /// Whether the device is grid-based, like a terminal
+    pub grid: bool,
+    pub hover: Hover,
+    pub any_hover: Hover,
+    pub pointer: Pointer,
+    pub any_pointer: Pointer,
+    pub inverted_colors: bool,
+    pub overflow_block: OverflowBlock,
+    pub overflow_inline: OverflowInline,
+    pub prefers_color_scheme: ColorScheme,
+    pub prefers_contrast: Contrast,
+    pub prefers_reduced_data: bool,
+    pub prefers_reduced_motion: bool,
+    pub prefers_reduced_transparency: bool,
+    pub scan: Scan,
+    pub scripting: Scripting,
+    pub update: Update,
+}
+impl Default for MediaEnvironment {
+    /// A desktop browser window with a mouse
+    fn default() -> Self {
+        Self {
+            media_type: MediaType::Screen,
+            width: 1280.0,
+            height: 720.0,
+            device_width: 1920.0,
+            device_height: 1080.0,
+            font_size: 16.0,
+            resolution: 1.0,
+            color: 8,
+            color_index: 0,
+            monochrome: 0,
+            color_gamut: ColorGamut::Srgb,
+            display_mode: DisplayMode::Browser,
+            dynamic_range: DynamicRange::Standard,
+            video_dynamic_range: DynamicRange::Standard,
+            forced_colors: false,
+            grid: false,
+            hover: Hover::Hover,
+            any_hover: Hover::Hover,
+            pointer: Pointer::Fine,
+            any_pointer: Pointer::Fine,
+            inverted_colors: false,
+            overflow_block: OverflowBlock::Scroll,
+            overflow_inline: OverflowInline::Scroll,
+            prefers_color_scheme: ColorScheme::Light,
+            prefers_contrast: Contrast::NoPreference,
+            prefers_reduced_data: false,
+            prefers_reduced_motion: false,
+            prefers_reduced_transparency: false,
+            scan: Scan::Progressive,
+            scripting: Scripting::Enabled,
+            update: Update::Fast,
+        }
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/color-gamut
+///
+/// Each gamut includes the ones before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ColorGamut {
+    Srgb,
+    P3,
+    Rec2020,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/display-mode
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DisplayMode {
+    Browser,
+    MinimalUi,
+    Standalone,
+    Fullscreen,
+    PictureInPicture,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/dynamic-range
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum DynamicRange {
+    Standard,
+    High,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/hover
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Hover {
+    None,
+    Hover,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/pointer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Pointer {
+    None,
+    Coarse,
+    Fine,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/overflow-block
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum OverflowBlock {
+    None,
+    Scroll,
+    Paged,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/overflow-inline
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum OverflowInline {
+    None,
+    Scroll,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/prefers-color-scheme
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/prefers-contrast
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Contrast {
+    NoPreference,
+    More,
+    Less,
+    Custom,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/scan
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Scan {
+    Interlace,
+    Progressive,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/scripting
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Scripting {
+    None,
+    InitialOnly,
+    Enabled,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@media/update
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Update {
+    None,
+    Slow,
+    Fast,
+}
+
+/// The environment's value of a media feature
+enum EnvironmentValue {
+    Number(f64),
+    Keyword(&'static str),
+}
+
+impl MediaEnvironment {
+    fn length_context(&self) -> LengthContext {
+        LengthContext {
+            font_size: self.font_size,
+            root_font_size: self.font_size,
+            viewport_width: self.width,
+            viewport_height: self.height,
+        }
+    }
+
+    fn value(&self, name: MediaFeatureName) -> EnvironmentValue {
+        use EnvironmentValue::{Keyword, Number};
+        use MediaFeatureName::*;
+        let preference = |reduce| if reduce { "reduce" } else { "no-preference" };
+        match name {
+            AspectRatio => Number(self.width as f64 / self.height as f64),
+            DeviceAspectRatio => Number(self.device_width as f64 / self.device_height as f64),
+            Color => Number(self.color as f64),
+            ColorIndex => Number(self.color_index as f64),
+            Monochrome => Number(self.monochrome as f64),
+            DeviceHeight => Number(self.device_height as f64),
+            DeviceWidth => Number(self.device_width as f64),
+            Height => Number(self.height as f64),
+            Width => Number(self.width as f64),
+            Grid => Number(self.grid as u8 as f64),
+            Resolution => Number(self.resolution as f64),
+            AnyHover => Keyword(self.any_hover.into()),
+            Hover => Keyword(self.hover.into()),
+            AnyPointer => Keyword(self.any_pointer.into()),
+            Pointer => Keyword(self.pointer.into()),
+            ColorGamut => Keyword(self.color_gamut.into()),
+            DisplayMode => Keyword(self.display_mode.into()),
+            DynamicRange => Keyword(self.dynamic_range.into()),
+            VideoDynamicRange => Keyword(self.video_dynamic_range.into()),
+            ForcedColors => Keyword(if self.forced_colors { "active" } else { "none" }),
+            InvertedColors => Keyword(if self.inverted_colors {
+                "inverted"
+            } else {
+                "none"
+            }),
+            Orientation => Keyword(if self.height >= self.width {
+                "portrait"
+            } else {
+                "landscape"
+            }),
+            OverflowBlock => Keyword(self.overflow_block.into()),
+            OverflowInline => Keyword(self.overflow_inline.into()),
+            PrefersColorScheme => Keyword(self.prefers_color_scheme.into()),
+            PrefersContrast => Keyword(self.prefers_contrast.into()),
+            PrefersReducedData => Keyword(preference(self.prefers_reduced_data)),
+            PrefersReducedMotion => Keyword(preference(self.prefers_reduced_motion)),
+            PrefersReducedTransparency => Keyword(preference(self.prefers_reduced_transparency)),
+            Scan => Keyword(self.scan.into()),
+            Scripting => Keyword(self.scripting.into()),
+            Update => Keyword(self.update.into()),
+        }
+    }
+}
+
+//* Evaluation
+impl MediaQueryList {
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        self.0.is_empty() || self.0.iter().any(|query| query.matches(environment))
+    }
+}
+
+impl MediaQuery {
+    /// Whether the query matches, where an unknown result (from
+    /// [`MediaCondition::GeneralEnclosed`]) doesn't
+    pub fn matches(&self, environment: &MediaEnvironment) -> bool {
+        let media_type = match &self.media_type {
+            None | Some(MediaType::All) => true,
+            Some(MediaType::Other(_)) => false,
+            Some(media_type) => *media_type == environment.media_type,
+        };
+        let matches = match &self.condition {
+            Some(condition) if media_type => condition.evaluate(environment),
+            _ => Some(media_type),
+        };
+        match self.qualifier {
+            Some(MediaQualifier::Not) => matches.map(|matches| !matches),
+            _ => matches,
+        }
+        .unwrap_or(false)
+    }
+}
+
+impl MediaCondition {
+    /// Evaluate the condition with three-valued logic, where `None` is
+    /// unknown
+    ///
+    /// https://drafts.csswg.org/mediaqueries-5/#evaluating
+    pub fn evaluate(&self, environment: &MediaEnvironment) -> Option<bool> {
+        match self {
+            MediaCondition::Feature(feature) => feature.evaluate(environment),
+            MediaCondition::Not(condition) => {
+                condition.evaluate(environment).map(|matches| !matches)
+            }
+            MediaCondition::And(conditions) => {
+                let mut result = Some(true);
+                for condition in conditions {
+                    match condition.evaluate(environment) {
+                        Some(false) => return Some(false),
+                        None => result = None,
+                        Some(true) => {}
+                    }
+                }
+                result
+            }
+            MediaCondition::Or(conditions) => {
+                let mut result = Some(false);
+                for condition in conditions {
+                    match condition.evaluate(environment) {
+                        Some(true) => return Some(true),
+                        None => result = None,
+                        Some(false) => {}
+                    }
+                }
+                result
+            }
+            MediaCondition::GeneralEnclosed => None,
+        }
+    }
+}
+
+impl MediaFeature {
+    /// Evaluate the feature, which is unknown if the value has the wrong type
+    pub fn evaluate(&self, environment: &MediaEnvironment) -> Option<bool> {
+        match self {
+            MediaFeature::Boolean(name) => Some(match environment.value(*name) {
+                EnvironmentValue::Number(number) => number != 0.0,
+                EnvironmentValue::Keyword(keyword) => !matches!(keyword, "none" | "no-preference"),
+            }),
+            MediaFeature::Plain {
+                name,
+                prefix,
+                value,
+            } => match (environment.value(*name), value) {
+                (EnvironmentValue::Keyword(actual), MediaFeatureValue::Keyword(keyword)) => {
+                    Some(keyword_matches(*name, actual, keyword))
+                }
+                (EnvironmentValue::Number(actual), value) => {
+                    let value = value.to_number(environment)?;
+                    Some(match prefix {
+                        Some(RangePrefix::Min) => actual >= value,
+                        Some(RangePrefix::Max) => actual <= value,
+                        None => actual == value,
+                    })
+                }
+                _ => None,
+            },
+            MediaFeature::Range { name, start, end } => {
+                let actual = match environment.value(*name) {
+                    EnvironmentValue::Number(actual) => actual,
+                    EnvironmentValue::Keyword(_) => return None,
+                };
+                let start = match start {
+                    Some((value, operator)) => {
+                        operator.compare(value.to_number(environment)?, actual)
+                    }
+                    None => true,
+                };
+                let end = match end {
+                    Some((operator, value)) => {
+                        operator.compare(actual, value.to_number(environment)?)
+                    }
+                    None => true,
+                };
+                Some(start && end)
+            }
+        }
+    }
+}
+
+fn keyword_matches(name: MediaFeatureName, actual: &str, keyword: &str) -> bool {
+    let keyword = keyword.to_ascii_lowercase();
+    match name {
+        // Wider gamuts include narrower ones
+        MediaFeatureName::ColorGamut => {
+            let gamut = |keyword: &str| match keyword {
+                "srgb" => Some(ColorGamut::Srgb),
+                "p3" => Some(ColorGamut::P3),
+                "rec2020" => Some(ColorGamut::Rec2020),
+                _ => None,
+            };
+            gamut(&keyword)
+                .is_some_and(|wanted| gamut(actual).is_some_and(|actual| actual >= wanted))
+        }
+        // High dynamic range devices support the standard range too
+        MediaFeatureName::DynamicRange | MediaFeatureName::VideoDynamicRange => {
+            keyword == "standard" || keyword == actual
+        }
+        _ => keyword == actual,
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn matches(query: &str, environment: &MediaEnvironment) -> bool {
+        parse::<MediaQueryList>(query).unwrap().matches(environment)
+    }
+
+    fn evaluate(condition: &str) -> Option<bool> {
+        let query = parse::<MediaQuery>(condition).unwrap();
+        query
+            .condition
+            .unwrap()
+            .evaluate(&MediaEnvironment::default())
+    }
+
+    #[test]
+    fn media_types() {
+        let screen = MediaEnvironment::default();
+        let print = MediaEnvironment {
+            media_type: MediaType::Print,
+            ..MediaEnvironment::default()
+        };
+        assert!(matches("", &screen));
+        assert!(matches("all", &print));
+        assert!(matches("screen", &screen));
+        assert!(!matches("screen", &print));
+        assert!(matches("not screen", &print));
+        assert!(matches("only print", &print));
+        assert!(!matches("tv", &screen));
+        assert!(matches("tv, print", &print));
+        assert!(matches("screen and (color)", &screen));
+        assert!(!matches("print and (color)", &screen));
+    }
+
+    #[test]
+    fn plain_features() {
+        let environment = MediaEnvironment::default();
+        assert!(matches("(min-width: 1000px)", &environment));
+        assert!(!matches("(max-width: 1000px)", &environment));
+        assert!(matches("(width: 1280px)", &environment));
+        // em is relative to the initial font size, not the page's
+        assert!(matches("(width: 80em)", &environment));
+        assert!(matches("(min-aspect-ratio: 16/9)", &environment));
+        assert!(!matches("(min-aspect-ratio: 2/1)", &environment));
+        assert!(matches("(min-resolution: 1dppx)", &environment));
+        assert!(!matches("(min-resolution: 2x)", &environment));
+        assert!(matches("(orientation: landscape)", &environment));
+        assert!(matches("(ORIENTATION: LANDSCAPE)", &environment));
+        assert!(matches("(hover: hover)", &environment));
+        assert!(!matches("(pointer: coarse)", &environment));
+        assert!(matches("(min-color: 8)", &environment));
+    }
+
+    #[test]
+    fn boolean_features() {
+        let environment = MediaEnvironment::default();
+        assert!(matches("(color)", &environment));
+        assert!(!matches("(monochrome)", &environment));
+        assert!(!matches("(grid)", &environment));
+        assert!(matches("(hover)", &environment));
+        assert!(!matches("(prefers-reduced-motion)", &environment));
+        assert!(!matches("(forced-colors)", &environment));
+        let reduced = MediaEnvironment {
+            prefers_reduced_motion: true,
+            ..MediaEnvironment::default()
+        };
+        assert!(matches("(prefers-reduced-motion)", &reduced));
+        assert!(matches("(prefers-reduced-motion: reduce)", &reduced));
+    }
+
+    #[test]
+    fn ranges() {
+        let environment = MediaEnvironment::default();
+        assert!(matches("(width > 1000px)", &environment));
+        assert!(!matches("(width < 1000px)", &environment));
+        assert!(matches("(width >= 1280px)", &environment));
+        assert!(!matches("(width > 1280px)", &environment));
+        assert!(matches("(1000px < width)", &environment));
+        assert!(matches("(1000px <= width <= 1400px)", &environment));
+        assert!(!matches("(1300px <= width <= 1400px)", &environment));
+        assert!(matches("(400px <= height < 800px)", &environment));
+        assert!(matches("(aspect-ratio > 1)", &environment));
+    }
+
+    #[test]
+    fn keyword_ranges() {
+        let environment = MediaEnvironment {
+            color_gamut: ColorGamut::P3,
+            dynamic_range: DynamicRange::High,
+            ..MediaEnvironment::default()
+        };
+        assert!(matches("(color-gamut: srgb)", &environment));
+        assert!(matches("(color-gamut: p3)", &environment));
+        assert!(!matches("(color-gamut: rec2020)", &environment));
+        assert!(matches("(dynamic-range: standard)", &environment));
+        assert!(matches("(dynamic-range: high)", &environment));
+        assert!(!matches("(video-dynamic-range: high)", &environment));
+    }
+
+    #[test]
+    fn logic() {
+        let environment = MediaEnvironment::default();
+        assert!(matches("(color) and (min-width: 1000px)", &environment));
+        assert!(!matches("(color) and (grid)", &environment));
+        assert!(matches("(grid) or (color)", &environment));
+        assert!(!matches("(grid) or (monochrome)", &environment));
+        assert!(matches("not (grid)", &environment));
+        assert!(matches("((grid) or (color)) and (hover)", &environment));
+        assert!(matches("screen and (not (grid))", &environment));
+    }
+
+    #[test]
+    fn unknown() {
+        let environment = MediaEnvironment::default();
+        assert_eq!(evaluate("(unknown-feature)"), None);
+        assert_eq!(evaluate("(width: fast)"), None);
+        assert_eq!(evaluate("(hover: 10px)"), None);
+        assert_eq!(evaluate("not (unknown-feature)"), None);
+        assert_eq!(evaluate("(unknown-feature) and (grid)"), Some(false));
+        assert_eq!(evaluate("(unknown-feature) and (color)"), None);
+        assert_eq!(evaluate("(unknown-feature) or (color)"), Some(true));
+        assert_eq!(evaluate("(unknown-feature) or (grid)"), None);
+        assert_eq!(evaluate("(width > 1000px) or func(arg)"), Some(true));
+        // An unknown result doesn't match, even under not
+        assert!(!matches("(unknown-feature)", &environment));
+        assert!(!matches("not all and (unknown-feature)", &environment));
+        assert!(matches("(unknown-feature), (color)", &environment));
+    }
+
+    #[test]
+    fn invalid_queries() {
+        let environment = MediaEnvironment::default();
+        // Each invalid query is `not all` on its own
+        assert!(!matches("screen and", &environment));
+        assert!(!matches("(color) and (grid) or (hover)", &environment));
+        assert!(matches("screen and, (color)", &environment));
+        assert!(!matches("not", &environment));
+    }
+}
@@ -0,0 +1,270 @@
+use super::{
+    supports::{parse_declaration, parse_keyword},
+    Ast, FromTokens, ParseError, Token,
+};
+use crate::{
+    at_rules::*,
+    data_types::{Angle, CustomIdent, Percentage, String, Url},
+    properties::{FontStretch, FontWeightAbsolute},
+};
+
+/// The prelude of `@import`, after the at-keyword
+impl FromTokens for ImportRule {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let url = parse_url_or_string(ast)?;
+        let layer = match ast.peek() {
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("layer") => {
+                ast.next_token()?;
+                Some(ImportLayer::Anonymous)
+            }
+            Some(Token::Function(name)) if name.eq_ignore_ascii_case("layer") => {
+                ast.next_token()?;
+                Some(ImportLayer::Named(ast.parse_nested_block(Ast::parse)?))
+            }
+            _ => None,
+        };
+        let supports = match ast.peek() {
+            Some(Token::Function(name)) if name.eq_ignore_ascii_case("supports") => {
+                ast.next_token()?;
+                Some(ast.parse_nested_block(|ast| {
+                    // A bare declaration doesn't need its own parentheses
+                    match ast.try_parse(|ast| {
+                        let condition = ast.parse()?;
+                        ast.expect_exhausted()?;
+                        Ok(condition)
+                    }) {
+                        Ok(condition) => Ok(condition),
+                        Err(_) => parse_declaration(ast),
+                    }
+                })?)
+            }
+            _ => None,
+        };
+        Ok(ImportRule {
+            url,
+            layer,
+            supports,
+            media: ast.parse()?,
+        })
+    }
+}
+
+/// The prelude of `@namespace`, after the at-keyword
+impl FromTokens for NamespaceRule {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(NamespaceRule {
+            prefix: ast.try_parse(Ast::parse).ok(),
+            url: parse_url_or_string(ast)?,
+        })
+    }
+}
+
+fn parse_url_or_string(ast: &mut Ast) -> Result<Url, ParseError> {
+    match ast.try_parse(Ast::parse) {
+        Ok(url) => Ok(url),
+        Err(_) => Ok(Url(ast.parse::<String>()?.0)),
+    }
+}
+
+/// Dot-separated identifiers, without whitespace between them
+impl FromTokens for LayerName {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let mut names = vec![ast.parse()?];
+        while ast.peek_including_whitespace() == Some(&Token::Delim('.')) {
+            ast.next_token()?;
+            match ast.next_token_including_whitespace()? {
+                Token::Ident(name) => names.push(CustomIdent(name)),
+                token => return Err(ParseError::UnexpectedToken(token)),
+            }
+        }
+        Ok(LayerName(names))
+    }
+}
+
+/// The prelude of `@keyframes`, where `none` isn't a valid name
+impl FromTokens for KeyframesName {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if let Ok(string) = ast.try_parse(Ast::parse) {
+            return Ok(KeyframesName::String(string));
+        }
+        let name: CustomIdent = ast.parse()?;
+        if name.0.eq_ignore_ascii_case("none") {
+            return Err(ParseError::InvalidValue);
+        }
+        Ok(KeyframesName::Custom(name))
+    }
+}
+
+/// `from`, `to`, a percentage from 0% to 100%, or a timeline range name and
+/// a percentage
+impl FromTokens for KeyframeSelector {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let percentage = |ast: &mut Ast| {
+            let percentage: Percentage = ast.parse()?;
+            if !(0.0..=100.0).contains(&percentage.0 .0) {
+                return Err(ParseError::InvalidValue);
+            }
+            Ok(percentage)
+        };
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("from"))
+            .is_ok()
+        {
+            return Ok(KeyframeSelector::From);
+        }
+        if ast.try_parse(|ast| ast.expect_ident_matching("to")).is_ok() {
+            return Ok(KeyframeSelector::To);
+        }
+        if let Ok(name) = ast.try_parse(Ast::parse) {
+            return Ok(KeyframeSelector::Range {
+                name,
+                offset: percentage(ast)?,
+            });
+        }
+        Ok(KeyframeSelector::Percentage(percentage(ast)?))
+    }
+}
+
+/// `url(...) [format(...)]? [tech(...)]?` or `local(<family-name>)`, where
+/// an unknown format keyword or technology makes the source invalid
+impl FromTokens for FontFaceSource {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let function = |ast: &Ast, expected: &str| matches!(ast.peek(), Some(Token::Function(name)) if name.eq_ignore_ascii_case(expected));
+        if function(ast, "local") {
+            ast.next_token()?;
+            return Ok(FontFaceSource::Local(ast.parse_nested_block(Ast::parse)?));
+        }
+        let url = ast.parse()?;
+        let format = match function(ast, "format") {
+            true => {
+                ast.next_token()?;
+                Some(
+                    ast.parse_nested_block(|ast| match ast.try_parse(Ast::parse) {
+                        Ok(string) => Ok(FontFaceFormat::String(string)),
+                        Err(_) => Ok(FontFaceFormat::Keyword(parse_keyword(ast)?)),
+                    })?,
+                )
+            }
+            false => None,
+        };
+        let tech = match function(ast, "tech") {
+            true => {
+                ast.next_token()?;
+                ast.parse_nested_block(|ast| ast.parse_comma_separated(parse_keyword))?
+            }
+            false => Vec::new(),
+        };
+        Ok(FontFaceSource::Url { url, format, tech })
+    }
+}
+
+/// The value of `src`, where invalid sources are dropped as long as one is
+/// left
+pub(super) fn parse_font_face_src(ast: &mut Ast) -> Result<Vec<FontFaceSource>, ParseError> {
+    let sources: Vec<_> = ast
+        .parse_comma_separated(|ast| {
+            let source = ast.try_parse(|ast| {
+                let source = ast.parse()?;
+                ast.expect_exhausted()?;
+                Ok(source)
+            });
+            ast.position = ast.tokens.len();
+            Ok(source.ok())
+        })?
+        .into_iter()
+        .flatten()
+        .collect();
+    match sources.is_empty() {
+        true => Err(ParseError::InvalidValue),
+        false => Ok(sources),
+    }
+}
+
+/// `auto | normal | italic | oblique <angle [-90deg,90deg]>{0,2}`
+impl FromTokens for FontFaceStyle {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let ident = ast.expect_ident()?;
+        Ok(match ident.to_ascii_lowercase().as_str() {
+            "auto" => FontFaceStyle::Auto,
+            "normal" => FontFaceStyle::Normal,
+            "italic" => FontFaceStyle::Italic,
+            "oblique" => {
+                let angle = |ast: &mut Ast| {
+                    let angle: Angle = ast.parse()?;
+                    if !(-90.0..=90.0).contains(&angle.to_degrees()) {
+                        return Err(ParseError::InvalidValue);
+                    }
+                    Ok(angle)
+                };
+                match ast.is_exhausted() {
+                    true => FontFaceStyle::Oblique(None),
+                    false => {
+                        let start = angle(ast)?;
+                        let end = ast.try_parse(angle).unwrap_or(start);
+                        FontFaceStyle::Oblique(Some((start, end)))
+                    }
+                }
+            }
+            _ => return Err(ParseError::UnexpectedToken(Token::Ident(ident))),
+        })
+    }
+}
+
+/// `auto | <font-weight-absolute>{1,2}`
+impl FromTokens for FontFaceWeight {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("auto"))
+            .is_ok()
+        {
+            return Ok(FontFaceWeight::Auto);
+        }
+        let start: FontWeightAbsolute = ast.parse()?;
+        let end = ast.try_parse(Ast::parse).unwrap_or_else(|_| start.clone());
+        Ok(FontFaceWeight::Range(start, end))
+    }
+}
+
+/// `auto | <font-stretch>{1,2}`
+impl FromTokens for FontFaceStretch {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("auto"))
+            .is_ok()
+        {
+            return Ok(FontFaceStretch::Auto);
+        }
+        let start: FontStretch = ast.parse()?;
+        let end = ast.try_parse(Ast::parse).unwrap_or_else(|_| start.clone());
+        Ok(FontFaceStretch::Range(start, end))
+    }
+}
+
+impl FromTokens for FontDisplay {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        parse_keyword(ast)
+    }
+}
+
+/// `normal | <percentage [0,∞]>`
+impl FromTokens for FontFaceMetricOverride {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("normal"))
+            .is_ok()
+        {
+            return Ok(FontFaceMetricOverride::Normal);
+        }
+        Ok(FontFaceMetricOverride::Percentage(
+            parse_non_negative_percentage(ast)?,
+        ))
+    }
+}
+
+pub(super) fn parse_non_negative_percentage(ast: &mut Ast) -> Result<Percentage, ParseError> {
+    let percentage: Percentage = ast.parse()?;
+    if percentage.0 .0 < 0.0 {
+        return Err(ParseError::InvalidValue);
+    }
+    Ok(percentage)
+}
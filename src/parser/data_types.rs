@@ -0,0 +1,336 @@
+use std::string::String as StdString;
+
+use super::{Ast, FromTokens, ParseError, Token};
+use crate::data_types::*;
+
+impl FromTokens for CustomIdent {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let ident = ast.expect_ident()?;
+        // CSS-wide keywords and `default` are reserved
+        const RESERVED: &[&str] = &[
+            "initial",
+            "inherit",
+            "unset",
+            "revert",
+            "revert-layer",
+            "default",
+        ];
+        if RESERVED
+            .iter()
+            .any(|reserved| ident.eq_ignore_ascii_case(reserved))
+        {
+            return Err(ParseError::InvalidValue);
+        }
+        Ok(CustomIdent(ident))
+    }
+}
+
+impl FromTokens for String {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::String(string) => Ok(String(string)),
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+}
+
+/// `url(...)`, unquoted or with a string
+impl FromTokens for Url {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::Url(url) => Ok(Url(url)),
+            Token::Function(name) if name.eq_ignore_ascii_case("url") => {
+                ast.parse_nested_block(|ast| Ok(Url(ast.parse::<String>()?.0)))
+            }
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+}
+
+/// A string of four printable ASCII characters
+impl FromTokens for OpenTypeTag {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let String(tag) = ast.parse()?;
+        OpenTypeTag::new(&tag).ok_or(ParseError::InvalidValue)
+    }
+}
+
+/// `U+` followed by up to six hex digits, then either `?` wildcards (up to
+/// six characters in all) or `-` and up to six hex digits for the end
+///
+/// The tokens after the `u` can be any mix of numbers, dimensions, idents and
+/// delims, so they're joined back into the text they came from first.
+///
+/// https://drafts.csswg.org/css-syntax-3/#urange-syntax
+impl FromTokens for UnicodeRange {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("u") => {}
+            token => return Err(ParseError::UnexpectedToken(token)),
+        }
+        let mut text = StdString::new();
+        while let Some(token) = ast.peek_including_whitespace() {
+            match token {
+                Token::Delim(c) => text.push(*c),
+                Token::Ident(ident) => text.push_str(ident),
+                Token::Number { representation, .. } => text.push_str(representation),
+                Token::Dimension {
+                    representation,
+                    unit,
+                    ..
+                } => {
+                    text.push_str(representation);
+                    text.push_str(unit);
+                }
+                _ => break,
+            }
+            ast.position += 1;
+        }
+        parse_unicode_range(&text).ok_or(ParseError::InvalidValue)
+    }
+}
+
+fn parse_unicode_range(text: &str) -> Option<UnicodeRange> {
+    let text = text.strip_prefix('+')?;
+    let digits = text.bytes().take_while(u8::is_ascii_hexdigit).count();
+    let wildcards = text[digits..].bytes().take_while(|b| *b == b'?').count();
+    if !(1..=6).contains(&(digits + wildcards)) {
+        return None;
+    }
+    let start = match digits {
+        0 => 0,
+        _ => u32::from_str_radix(&text[..digits], 16).ok()?,
+    };
+    let rest = &text[digits + wildcards..];
+    if wildcards > 0 {
+        return match rest.is_empty() {
+            true => UnicodeRange::wildcard(start, wildcards as u32),
+            false => None,
+        };
+    }
+    let end = match rest.strip_prefix('-') {
+        Some(end) if (1..=6).contains(&end.len()) && end.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            u32::from_str_radix(end, 16).ok()?
+        }
+        Some(_) => return None,
+        None if rest.is_empty() => start,
+        None => return None,
+    };
+    UnicodeRange::new(start, end)
+}
+
+impl FromTokens for Integer {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::Number {
+                value,
+                is_integer: true,
+                ..
+            } => Ok(Integer(value as i32)),
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+}
+
+impl FromTokens for Number {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::Number { value, .. } => Ok(Number(value)),
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+}
+
+impl FromTokens for PositiveNumber {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let Number(value) = ast.parse()?;
+        PositiveNumber::new(value).ok_or(ParseError::InvalidValue)
+    }
+}
+
+impl FromTokens for Time {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::Dimension { value, unit, .. } if unit.eq_ignore_ascii_case("s") => {
+                Ok(Time(value, TimeUnit::S))
+            }
+            Token::Dimension { value, unit, .. } if unit.eq_ignore_ascii_case("ms") => {
+                Ok(Time(value, TimeUnit::Ms))
+            }
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+}
+
+impl FromTokens for PositiveTime {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let Time(value, unit) = ast.parse()?;
+        PositiveTime::new(value, unit).ok_or(ParseError::InvalidValue)
+    }
+}
+
+impl FromTokens for DashedIdent {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::Ident(ident) if ident.starts_with("--") => Ok(DashedIdent(ident)),
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+}
+
+impl FromTokens for Percentage {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::Percentage(value) => Ok(Percentage(Number(value))),
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+}
+
+/// A ratio like `16/9`, or a single number like `2` (which is `2/1`)
+impl FromTokens for Ratio {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let positive = |ast: &mut Ast| match ast.next_token()? {
+            Token::Number { value, .. } if value >= 0.0 => Ok(Number(value)),
+            token => Err(ParseError::UnexpectedToken(token)),
+        };
+        let width = positive(ast)?;
+        if ast.peek() != Some(&Token::Delim('/')) {
+            return Ok(Ratio(width, Number(1.0)));
+        }
+        ast.next_token()?;
+        Ok(Ratio(width, positive(ast)?))
+    }
+}
+
+impl FromTokens for Resolution {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::Dimension { value, unit, .. } if value >= 0.0 => {
+                let unit = match unit.to_ascii_lowercase().as_str() {
+                    "dpi" => ResolutionUnit::Dpi,
+                    "dpcm" => ResolutionUnit::DpCm,
+                    "dppx" | "x" => ResolutionUnit::DpPx,
+                    _ => return Err(ParseError::InvalidValue),
+                };
+                Ok(Resolution(value, unit))
+            }
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+}
+
+impl FromTokens for Angle {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let token = ast.next_token()?;
+        match &token {
+            Token::Dimension { value, unit, .. } => {
+                let unit = match unit.to_ascii_lowercase().as_str() {
+                    "deg" => AngleUnit::Deg,
+                    "grad" => AngleUnit::Grad,
+                    "rad" => AngleUnit::Rad,
+                    "turn" => AngleUnit::Turn,
+                    _ => return Err(ParseError::UnexpectedToken(token.clone())),
+                };
+                Ok(Angle(*value, unit))
+            }
+            _ => Err(ParseError::UnexpectedToken(token.clone())),
+        }
+    }
+}
+
+impl FromTokens for Length {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let token = ast.next_token()?;
+        match &token {
+            // Unitless zero is allowed for lengths
+            Token::Number { value, .. } if *value == 0.0 => Ok(Length(0.0, LengthUnit::Px)),
+            Token::Dimension { value, unit, .. } => match length_unit(unit) {
+                Some(unit) => Ok(Length(*value, unit)),
+                None => Err(ParseError::UnexpectedToken(token.clone())),
+            },
+            _ => Err(ParseError::UnexpectedToken(token.clone())),
+        }
+    }
+}
+
+fn length_unit(unit: &str) -> Option<LengthUnit> {
+    Some(match unit.to_ascii_lowercase().as_str() {
+        "ch" => LengthUnit::Ch,
+        "em" => LengthUnit::Em,
+        "ex" => LengthUnit::Ex,
+        "rem" => LengthUnit::Rem,
+        "vh" => LengthUnit::Vh,
+        "vw" => LengthUnit::Vw,
+        "vmin" => LengthUnit::Vmin,
+        "vmax" => LengthUnit::Vmax,
+        "px" => LengthUnit::Px,
+        "cm" => LengthUnit::Cm,
+        "mm" => LengthUnit::Mm,
+        "in" => LengthUnit::In,
+        "pc" => LengthUnit::Pc,
+        "pt" => LengthUnit::Pt,
+        _ => return None,
+    })
+}
+
+impl FromTokens for LengthPercentage {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if let Ok(percentage) = ast.try_parse(Ast::parse) {
+            return Ok(LengthPercentage::Percentage(percentage));
+        }
+        Ok(LengthPercentage::Length(ast.parse()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn range(input: &str) -> Option<(u32, u32)> {
+        parse::<UnicodeRange>(input)
+            .ok()
+            .map(|range| (range.start(), range.end()))
+    }
+
+    #[test]
+    fn unicode_ranges() {
+        assert_eq!(range("U+26"), Some((0x26, 0x26)));
+        assert_eq!(range("u+0025-00FF"), Some((0x25, 0xFF)));
+        assert_eq!(range("U+0-7F"), Some((0, 0x7F)));
+        assert_eq!(range("U+A5"), Some((0xA5, 0xA5)));
+        assert_eq!(range("U+A5-FF"), Some((0xA5, 0xFF)));
+        // Hex digits that look like an exponent
+        assert_eq!(range("U+00E9"), Some((0xE9, 0xE9)));
+        assert_eq!(range("U+1E00-1EFF"), Some((0x1E00, 0x1EFF)));
+        assert_eq!(range("U+1F600"), Some((0x1F600, 0x1F600)));
+        assert_eq!(range("U+10FFFF"), Some((0x10FFFF, 0x10FFFF)));
+        assert_eq!(range("U+4??"), Some((0x400, 0x4FF)));
+        assert_eq!(range("U+?"), Some((0, 0xF)));
+    }
+
+    #[test]
+    fn invalid_unicode_ranges() {
+        assert_eq!(range("U+"), None);
+        assert_eq!(range("U+1234567"), None);
+        assert_eq!(range("U+110000"), None);
+        assert_eq!(range("U+??????"), None);
+        assert_eq!(range("U+FF-A5"), None);
+        assert_eq!(range("U+4??-5FF"), None);
+        assert_eq!(range("U+?4"), None);
+        assert_eq!(range("U+12-"), None);
+        assert_eq!(range("U+12-1234567"), None);
+        assert_eq!(range("U+G"), None);
+        assert_eq!(range("U +12"), None);
+        assert_eq!(range("V+12"), None);
+    }
+
+    #[test]
+    fn opentype_tags() {
+        assert!(parse::<OpenTypeTag>("\"liga\"").is_ok());
+        assert!(parse::<OpenTypeTag>("\"lig\"").is_err());
+        assert!(parse::<OpenTypeTag>("\"ligat\"").is_err());
+        assert!(parse::<OpenTypeTag>("liga").is_err());
+    }
+}
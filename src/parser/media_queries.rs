@@ -0,0 +1,285 @@
+use super::{Ast, FromTokens, ParseError, Token};
+use crate::{data_types::Integer, media_queries::*};
+
+/// Invalid queries become `not all` instead of invalidating the whole list
+///
+/// https://drafts.csswg.org/mediaqueries-5/#error-handling
+impl FromTokens for MediaQueryList {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast.is_exhausted() {
+            return Ok(MediaQueryList::default());
+        }
+        let queries = ast.parse_comma_separated(|ast| {
+            let query = ast.try_parse(|ast| {
+                let query = ast.parse()?;
+                ast.expect_exhausted()?;
+                Ok(query)
+            });
+            ast.position = ast.tokens.len();
+            Ok(query.unwrap_or_else(|_| MediaQuery::not_all()))
+        })?;
+        Ok(MediaQueryList(queries))
+    }
+}
+
+impl FromTokens for MediaQuery {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let with_type = ast.try_parse(|ast| {
+            let qualifier = ast
+                .try_parse(|ast| {
+                    ast.expect_keyword(&[
+                        ("not", MediaQualifier::Not),
+                        ("only", MediaQualifier::Only),
+                    ])
+                })
+                .ok();
+            let media_type = parse_media_type(ast)?;
+            let condition = match ast.try_parse(|ast| ast.expect_ident_matching("and")) {
+                Ok(()) => Some(parse_condition(ast, false)?),
+                Err(_) => None,
+            };
+            Ok(MediaQuery {
+                qualifier,
+                media_type: Some(media_type),
+                condition,
+            })
+        });
+        if let Ok(query) = with_type {
+            return Ok(query);
+        }
+        Ok(MediaQuery {
+            qualifier: None,
+            media_type: None,
+            condition: Some(parse_condition(ast, true)?),
+        })
+    }
+}
+
+fn parse_media_type(ast: &mut Ast) -> Result<MediaType, ParseError> {
+    let ident = ast.expect_ident()?;
+    Ok(match ident.to_ascii_lowercase().as_str() {
+        "all" => MediaType::All,
+        "print" => MediaType::Print,
+        "screen" => MediaType::Screen,
+        "only" | "not" | "and" | "or" | "layer" => {
+            return Err(ParseError::UnexpectedToken(Token::Ident(ident)))
+        }
+        _ => MediaType::Other(ident),
+    })
+}
+
+/// `<media-condition>`, or `<media-condition-without-or>` if `allow_or` is
+/// false
+fn parse_condition(ast: &mut Ast, allow_or: bool) -> Result<MediaCondition, ParseError> {
+    if ast
+        .try_parse(|ast| ast.expect_ident_matching("not"))
+        .is_ok()
+    {
+        return Ok(MediaCondition::Not(Box::new(parse_in_parens(ast)?)));
+    }
+    let first = parse_in_parens(ast)?;
+    let keyword = match ast.peek() {
+        Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("and") => "and",
+        Some(Token::Ident(ident)) if allow_or && ident.eq_ignore_ascii_case("or") => "or",
+        _ => return Ok(first),
+    };
+    let mut conditions = vec![first];
+    while ast
+        .try_parse(|ast| ast.expect_ident_matching(keyword))
+        .is_ok()
+    {
+        conditions.push(parse_in_parens(ast)?);
+    }
+    Ok(match keyword {
+        "and" => MediaCondition::And(conditions),
+        _ => MediaCondition::Or(conditions),
+    })
+}
+
+/// `<media-in-parens>`, where anything in parentheses (or a function) that
+/// isn't a condition or a feature is unknown rather than invalid
+fn parse_in_parens(ast: &mut Ast) -> Result<MediaCondition, ParseError> {
+    match ast.next_token()? {
+        Token::OpenParen => ast.parse_nested_block(|ast| {
+            let exhaustive = |ast: &mut Ast, parse: fn(&mut Ast) -> Result<_, _>| {
+                ast.try_parse(|ast| {
+                    let value = parse(ast)?;
+                    ast.expect_exhausted()?;
+                    Ok(value)
+                })
+            };
+            if let Ok(condition) = exhaustive(ast, |ast| parse_condition(ast, true)) {
+                return Ok(condition);
+            }
+            if let Ok(feature) = exhaustive(ast, |ast| Ok(MediaCondition::Feature(ast.parse()?))) {
+                return Ok(feature);
+            }
+            ast.position = ast.tokens.len();
+            Ok(MediaCondition::GeneralEnclosed)
+        }),
+        Token::Function(_) => ast.parse_nested_block(|ast| {
+            ast.position = ast.tokens.len();
+            Ok(MediaCondition::GeneralEnclosed)
+        }),
+        token => Err(ParseError::UnexpectedToken(token)),
+    }
+}
+
+/// The contents of a media feature's parentheses
+impl FromTokens for MediaFeature {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if let Ok(ident) = ast.try_parse(Ast::expect_ident) {
+            if ast.is_exhausted() {
+                return Ok(MediaFeature::Boolean(parse_name(&ident)?));
+            }
+            if ast.peek() == Some(&Token::Colon) {
+                ast.next_token()?;
+                let lowercase = ident.to_ascii_lowercase();
+                let (prefix, name) = if let Some(name) = lowercase.strip_prefix("min-") {
+                    (Some(RangePrefix::Min), parse_name(name)?)
+                } else if let Some(name) = lowercase.strip_prefix("max-") {
+                    (Some(RangePrefix::Max), parse_name(name)?)
+                } else {
+                    (None, parse_name(&lowercase)?)
+                };
+                if prefix.is_some() && !name.value_type().is_range() {
+                    return Err(ParseError::InvalidValue);
+                }
+                let value = parse_value(ast, name.value_type())?;
+                return Ok(MediaFeature::Plain {
+                    name,
+                    prefix,
+                    value,
+                });
+            }
+            let name = parse_range_name(&ident)?;
+            let operator = parse_operator(ast)?;
+            let value = parse_value(ast, name.value_type())?;
+            return Ok(MediaFeature::Range {
+                name,
+                start: None,
+                end: Some((operator, value)),
+            });
+        }
+
+        let start = ast.parse::<RangeValue>()?;
+        let start_operator = parse_operator(ast)?;
+        let name = parse_range_name(&ast.expect_ident()?)?;
+        let start = (start.coerce(name.value_type())?, start_operator);
+        if ast.is_exhausted() {
+            return Ok(MediaFeature::Range {
+                name,
+                start: Some(start),
+                end: None,
+            });
+        }
+        let end_operator = parse_operator(ast)?;
+        // Both comparisons must point the same way, like `a < b <= c`
+        let is_less = |operator| matches!(operator, RangeOperator::Lt | RangeOperator::Le);
+        let is_greater = |operator| matches!(operator, RangeOperator::Gt | RangeOperator::Ge);
+        if !(is_less(start_operator) && is_less(end_operator)
+            || is_greater(start_operator) && is_greater(end_operator))
+        {
+            return Err(ParseError::InvalidValue);
+        }
+        let end = parse_value(ast, name.value_type())?;
+        Ok(MediaFeature::Range {
+            name,
+            start: Some(start),
+            end: Some((end_operator, end)),
+        })
+    }
+}
+
+fn parse_name(name: &str) -> Result<MediaFeatureName, ParseError> {
+    name.parse()
+        .map_err(|_| ParseError::UnexpectedToken(Token::Ident(name.to_owned())))
+}
+
+/// The name of a feature in the range syntax, which must be a range feature
+fn parse_range_name(name: &str) -> Result<MediaFeatureName, ParseError> {
+    let name = parse_name(name)?;
+    if !name.value_type().is_range() {
+        return Err(ParseError::InvalidValue);
+    }
+    Ok(name)
+}
+
+/// `<`, `<=`, `>`, `>=` or `=`, where there can't be whitespace before `=`
+fn parse_operator(ast: &mut Ast) -> Result<RangeOperator, ParseError> {
+    let (strict, inclusive) = match ast.next_token()? {
+        Token::Delim('=') => return Ok(RangeOperator::Eq),
+        Token::Delim('<') => (RangeOperator::Lt, RangeOperator::Le),
+        Token::Delim('>') => (RangeOperator::Gt, RangeOperator::Ge),
+        token => return Err(ParseError::UnexpectedToken(token)),
+    };
+    if ast.peek_including_whitespace() == Some(&Token::Delim('=')) {
+        ast.next_token()?;
+        return Ok(inclusive);
+    }
+    Ok(strict)
+}
+
+fn parse_value(
+    ast: &mut Ast,
+    value_type: MediaFeatureType,
+) -> Result<MediaFeatureValue, ParseError> {
+    Ok(match value_type {
+        MediaFeatureType::Length => MediaFeatureValue::Length(ast.parse()?),
+        MediaFeatureType::Integer => MediaFeatureValue::Integer(ast.parse()?),
+        MediaFeatureType::Ratio => MediaFeatureValue::Ratio(ast.parse()?),
+        MediaFeatureType::Resolution => MediaFeatureValue::Resolution(ast.parse()?),
+        MediaFeatureType::Boolean => match ast.parse()? {
+            Integer(value @ (0 | 1)) => MediaFeatureValue::Integer(Integer(value)),
+            _ => return Err(ParseError::InvalidValue),
+        },
+        MediaFeatureType::Keyword(keywords) => {
+            let ident = ast.expect_ident()?;
+            if !keywords
+                .iter()
+                .any(|keyword| ident.eq_ignore_ascii_case(keyword))
+            {
+                return Err(ParseError::UnexpectedToken(Token::Ident(ident)));
+            }
+            MediaFeatureValue::Keyword(ident.to_ascii_lowercase())
+        }
+    })
+}
+
+/// A value before the name in the range syntax, whose type isn't known until
+/// the name is parsed
+struct RangeValue(MediaFeatureValue);
+impl FromTokens for RangeValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        for value_type in [
+            MediaFeatureType::Length,
+            MediaFeatureType::Resolution,
+            MediaFeatureType::Ratio,
+        ] {
+            if let Ok(value) = ast.try_parse(|ast| parse_value(ast, value_type)) {
+                return Ok(RangeValue(value));
+            }
+        }
+        Err(match ast.peek() {
+            Some(token) => ParseError::UnexpectedToken(token.clone()),
+            None => ParseError::UnexpectedEnd,
+        })
+    }
+}
+impl RangeValue {
+    /// Convert to the type of the feature, where a number can be a ratio or
+    /// an integer
+    fn coerce(self, value_type: MediaFeatureType) -> Result<MediaFeatureValue, ParseError> {
+        match (self.0, value_type) {
+            (value @ MediaFeatureValue::Length(_), MediaFeatureType::Length)
+            | (value @ MediaFeatureValue::Resolution(_), MediaFeatureType::Resolution)
+            | (value @ MediaFeatureValue::Ratio(_), MediaFeatureType::Ratio) => Ok(value),
+            (MediaFeatureValue::Ratio(ratio), MediaFeatureType::Integer)
+                if ratio.1 .0 == 1.0 && ratio.0 .0.fract() == 0.0 =>
+            {
+                Ok(MediaFeatureValue::Integer(Integer(ratio.0 .0 as i32)))
+            }
+            _ => Err(ParseError::InvalidValue),
+        }
+    }
+}
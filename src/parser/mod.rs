@@ -1,9 +1,260 @@
-pub struct Ast;
+use std::{error, fmt, string::String as StdString};
 
-pub trait FromTokens {
-    fn from_tokens(ast: &Ast) -> Self;
+mod at_rules;
+mod data_types;
+mod media_queries;
+mod properties;
+mod rules;
+mod selectors;
+mod supports;
+pub mod tokenizer;
+
+pub use rules::parse_stylesheet;
+pub use tokenizer::Token;
+
+/// A stream of tokens being parsed
+#[derive(Clone, Debug)]
+pub struct Ast {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+pub trait FromTokens: Sized {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError>;
 }
 
 pub trait ToTokens {
     fn to_tokens(&self) -> Ast;
 }
+
+/// Parse all of `input` as a `T`
+pub fn parse<T: FromTokens>(input: &str) -> Result<T, ParseError> {
+    let mut ast = Ast::new(input);
+    let value = T::from_tokens(&mut ast)?;
+    ast.expect_exhausted()?;
+    Ok(value)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    UnexpectedEnd,
+    UnexpectedToken(Token),
+    /// The tokens were of the right type, but the value isn't allowed (like a
+    /// negative duration)
+    InvalidValue,
+    /// A known property that doesn't have a parser yet, so whether its value
+    /// is valid isn't known
+    UnsupportedProperty(crate::properties::PropertyDiscriminants),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseError::UnexpectedToken(token) => write!(f, "unexpected token {:?}", token),
+            ParseError::InvalidValue => write!(f, "invalid value"),
+            ParseError::UnsupportedProperty(name) => write!(f, "no parser for {:?}", name),
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+impl Ast {
+    pub fn new(input: &str) -> Self {
+        Self {
+            tokens: tokenizer::tokenize(input),
+            position: 0,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.tokens.get(self.position) == Some(&Token::Whitespace) {
+            self.position += 1;
+        }
+    }
+
+    /// The next token that isn't whitespace, without consuming it
+    pub fn peek(&self) -> Option<&Token> {
+        self.tokens[self.position.min(self.tokens.len())..]
+            .iter()
+            .find(|token| **token != Token::Whitespace)
+    }
+
+    /// The next token (which may be whitespace), without consuming it
+    pub fn peek_including_whitespace(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    /// The next token that isn't whitespace
+    pub fn next_token(&mut self) -> Result<Token, ParseError> {
+        self.skip_whitespace();
+        self.next_token_including_whitespace()
+    }
+
+    pub fn next_token_including_whitespace(&mut self) -> Result<Token, ParseError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or(ParseError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    /// Whether only whitespace remains
+    pub fn is_exhausted(&self) -> bool {
+        self.peek().is_none()
+    }
+
+    pub fn expect_exhausted(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(ParseError::UnexpectedToken(token.clone())),
+        }
+    }
+
+    /// Run `parse`, rewinding to the current position if it fails
+    pub fn try_parse<T, F>(&mut self, parse: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&mut Ast) -> Result<T, ParseError>,
+    {
+        let position = self.position;
+        let result = parse(self);
+        if result.is_err() {
+            self.position = position;
+        }
+        result
+    }
+
+    pub fn expect_ident(&mut self) -> Result<StdString, ParseError> {
+        match self.next_token()? {
+            Token::Ident(ident) => Ok(ident),
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+
+    /// Expect an identifier matching `expected`, ignoring ASCII case
+    pub fn expect_ident_matching(&mut self, expected: &str) -> Result<(), ParseError> {
+        match self.next_token()? {
+            Token::Ident(ident) if ident.eq_ignore_ascii_case(expected) => Ok(()),
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+
+    /// Expect one of `keywords` (ignoring ASCII case), returning its value
+    pub fn expect_keyword<T: Copy>(&mut self, keywords: &[(&str, T)]) -> Result<T, ParseError> {
+        let ident = self.expect_ident()?;
+        keywords
+            .iter()
+            .find(|(keyword, _)| ident.eq_ignore_ascii_case(keyword))
+            .map(|(_, value)| *value)
+            .ok_or(ParseError::UnexpectedToken(Token::Ident(ident)))
+    }
+
+    /// Expect any function token, returning its name
+    pub fn expect_function(&mut self) -> Result<StdString, ParseError> {
+        match self.next_token()? {
+            Token::Function(name) => Ok(name),
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+
+    pub fn expect_token(&mut self, expected: &Token) -> Result<(), ParseError> {
+        match self.next_token()? {
+            ref token if token == expected => Ok(()),
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+
+    pub fn expect_comma(&mut self) -> Result<(), ParseError> {
+        self.expect_token(&Token::Comma)
+    }
+
+    /// The index of the end of the block starting at `start` (i.e. the
+    /// position of its closing token, or the end of the input)
+    fn block_end(&self, start: usize) -> usize {
+        let mut depth = 0;
+        for (i, token) in self.tokens.iter().enumerate().skip(start) {
+            if token.is_block_start() {
+                depth += 1;
+            } else if token.is_block_end() {
+                if depth == 0 {
+                    return i;
+                }
+                depth -= 1;
+            }
+        }
+        self.tokens.len()
+    }
+
+    /// The index of the next comma outside of any nested blocks, or the end
+    /// of the current block
+    fn comma_or_block_end(&self, start: usize) -> usize {
+        let mut depth = 0;
+        for (i, token) in self.tokens.iter().enumerate().skip(start) {
+            if token.is_block_start() {
+                depth += 1;
+            } else if token.is_block_end() {
+                if depth == 0 {
+                    return i;
+                }
+                depth -= 1;
+            } else if depth == 0 && *token == Token::Comma {
+                return i;
+            }
+        }
+        self.tokens.len()
+    }
+
+    /// Parse the tokens from the current position to `end` with `parse`,
+    /// which must consume all of them
+    fn parse_until<T, F>(&mut self, end: usize, parse: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&mut Ast) -> Result<T, ParseError>,
+    {
+        let mut inner = Ast {
+            tokens: self.tokens[self.position..end].to_vec(),
+            position: 0,
+        };
+        let value = parse(&mut inner)?;
+        inner.expect_exhausted()?;
+        self.position = end;
+        Ok(value)
+    }
+
+    /// Parse the contents of the block opened by the previously consumed token
+    /// (like a [`Token::Function`]) with `parse`, which must consume all of
+    /// them, then consume the closing token
+    pub fn parse_nested_block<T, F>(&mut self, parse: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&mut Ast) -> Result<T, ParseError>,
+    {
+        let end = self.block_end(self.position);
+        let value = self.parse_until(end, parse)?;
+        self.position = (end + 1).min(self.tokens.len());
+        Ok(value)
+    }
+
+    /// Parse a comma-separated list, where `parse` must consume each item
+    /// entirely
+    pub fn parse_comma_separated<T, F>(&mut self, mut parse: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&mut Ast) -> Result<T, ParseError>,
+    {
+        let mut values = Vec::new();
+        loop {
+            let end = self.comma_or_block_end(self.position);
+            values.push(self.parse_until(end, &mut parse)?);
+            match self.tokens.get(self.position) {
+                Some(Token::Comma) => self.position += 1,
+                _ => return Ok(values),
+            }
+        }
+    }
+
+    /// Parse a `T`
+    pub fn parse<T: FromTokens>(&mut self) -> Result<T, ParseError> {
+        T::from_tokens(self)
+    }
+}
@@ -0,0 +1,1015 @@
+use super::{Ast, FromTokens, ParseError, Token};
+use crate::{
+    data_types,
+    properties::{shorthand::*, *},
+};
+
+impl FromTokens for EasingFunction {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::Ident(ident) => Ok(match ident.to_ascii_lowercase().as_str() {
+                "ease" => EasingFunction::Ease,
+                "linear" => EasingFunction::Linear,
+                "ease-in" => EasingFunction::EaseIn,
+                "ease-out" => EasingFunction::EaseOut,
+                "ease-in-out" => EasingFunction::EaseInOut,
+                "step-start" => EasingFunction::Steps(1, EasingStepsJump::Start),
+                "step-end" => EasingFunction::Steps(1, EasingStepsJump::End),
+                _ => return Err(ParseError::UnexpectedToken(Token::Ident(ident))),
+            }),
+            Token::Function(name) if name.eq_ignore_ascii_case("cubic-bezier") => ast
+                .parse_nested_block(|ast| {
+                    let data_types::Number(x1) = ast.parse()?;
+                    ast.expect_comma()?;
+                    let data_types::Number(y1) = ast.parse()?;
+                    ast.expect_comma()?;
+                    let data_types::Number(x2) = ast.parse()?;
+                    ast.expect_comma()?;
+                    let data_types::Number(y2) = ast.parse()?;
+                    if !(0.0..=1.0).contains(&x1) || !(0.0..=1.0).contains(&x2) {
+                        return Err(ParseError::InvalidValue);
+                    }
+                    Ok(EasingFunction::CubicBezier(x1, y1, x2, y2))
+                }),
+            Token::Function(name) if name.eq_ignore_ascii_case("steps") => {
+                ast.parse_nested_block(|ast| {
+                    let data_types::Integer(count) = ast.parse()?;
+                    let jump = if ast.is_exhausted() {
+                        EasingStepsJump::End
+                    } else {
+                        ast.expect_comma()?;
+                        let ident = ast.expect_ident()?;
+                        match ident.to_ascii_lowercase().as_str() {
+                            "jump-start" | "start" => EasingStepsJump::Start,
+                            "jump-end" | "end" => EasingStepsJump::End,
+                            "jump-none" => EasingStepsJump::None,
+                            "jump-both" => EasingStepsJump::Both,
+                            _ => return Err(ParseError::UnexpectedToken(Token::Ident(ident))),
+                        }
+                    };
+                    // `jump-none` needs at least two steps to go anywhere
+                    let minimum = if jump == EasingStepsJump::None { 2 } else { 1 };
+                    if count < minimum {
+                        return Err(ParseError::InvalidValue);
+                    }
+                    Ok(EasingFunction::Steps(count as u32, jump))
+                })
+            }
+            // `linear([<number> && <percentage>{0,2}]#)`, which needs at
+            // least two stops
+            Token::Function(name) if name.eq_ignore_ascii_case("linear") => {
+                ast.parse_nested_block(|ast| {
+                    let stops: Vec<LinearEasingStop> = ast
+                        .parse_comma_separated(parse_linear_stop)?
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                    if stops.len() < 2 {
+                        return Err(ParseError::InvalidValue);
+                    }
+                    Ok(EasingFunction::LinearFunction(stops))
+                })
+            }
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+}
+
+/// A stop with two percentages is split into two stops
+fn parse_linear_stop(ast: &mut Ast) -> Result<Vec<LinearEasingStop>, ParseError> {
+    let mut output = None;
+    let mut inputs = Vec::new();
+    // The percentages can't be split by the number
+    let mut inputs_done = false;
+    while !ast.is_exhausted() {
+        if output.is_none() {
+            if let Ok(value) = ast.try_parse(Ast::parse) {
+                output = Some(value);
+                inputs_done = !inputs.is_empty();
+                continue;
+            }
+        }
+        if !inputs_done && inputs.len() < 2 {
+            inputs.push(ast.parse()?);
+            continue;
+        }
+        return Err(ParseError::UnexpectedToken(ast.next_token()?));
+    }
+    let output = output.ok_or(ParseError::UnexpectedEnd)?;
+    if inputs.is_empty() {
+        return Ok(vec![LinearEasingStop {
+            output,
+            input: None,
+        }]);
+    }
+    Ok(inputs
+        .into_iter()
+        .map(|input| LinearEasingStop {
+            output,
+            input: Some(input),
+        })
+        .collect())
+}
+
+//* Animations
+
+impl FromTokens for AnimationCompositionValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        ast.expect_keyword(&[
+            ("replace", AnimationCompositionValue::Replace),
+            ("add", AnimationCompositionValue::Add),
+            ("accumulate", AnimationCompositionValue::Accumulate),
+        ])
+    }
+}
+
+impl FromTokens for AnimationDirectionValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        ast.expect_keyword(&[
+            ("normal", AnimationDirectionValue::Normal),
+            ("reverse", AnimationDirectionValue::Reverse),
+            ("alternate", AnimationDirectionValue::Alternate),
+            (
+                "alternate-reverse",
+                AnimationDirectionValue::AlternateReverse,
+            ),
+        ])
+    }
+}
+
+impl FromTokens for AnimationFillModeValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        ast.expect_keyword(&[
+            ("none", AnimationFillModeValue::None),
+            ("forwards", AnimationFillModeValue::Forwards),
+            ("backwards", AnimationFillModeValue::Backwards),
+            ("both", AnimationFillModeValue::Both),
+        ])
+    }
+}
+
+impl FromTokens for AnimationIterationCountValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("infinite"))
+            .is_ok()
+        {
+            return Ok(AnimationIterationCountValue::Infinite);
+        }
+        Ok(AnimationIterationCountValue::Finite(ast.parse()?))
+    }
+}
+
+impl FromTokens for AnimationNameValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("none"))
+            .is_ok()
+        {
+            return Ok(AnimationNameValue::None);
+        }
+        if let Ok(string) = ast.try_parse(Ast::parse) {
+            return Ok(AnimationNameValue::String(string));
+        }
+        Ok(AnimationNameValue::Custom(ast.parse()?))
+    }
+}
+
+impl FromTokens for AnimationPlayStateValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        ast.expect_keyword(&[
+            ("running", AnimationPlayStateValue::Running),
+            ("paused", AnimationPlayStateValue::Paused),
+        ])
+    }
+}
+
+impl FromTokens for TimelineRangeName {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        ast.expect_keyword(&[
+            ("cover", TimelineRangeName::Cover),
+            ("contain", TimelineRangeName::Contain),
+            ("entry", TimelineRangeName::Entry),
+            ("exit", TimelineRangeName::Exit),
+            ("entry-crossing", TimelineRangeName::EntryCrossing),
+            ("exit-crossing", TimelineRangeName::ExitCrossing),
+        ])
+    }
+}
+
+impl FromTokens for AnimationRangeValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("normal"))
+            .is_ok()
+        {
+            return Ok(AnimationRangeValue::Normal);
+        }
+        if let Ok(name) = ast.try_parse(Ast::parse) {
+            return Ok(AnimationRangeValue::Named {
+                name,
+                offset: ast.try_parse(Ast::parse).ok(),
+            });
+        }
+        Ok(AnimationRangeValue::Offset(ast.parse()?))
+    }
+}
+
+impl FromTokens for ScrollTimelineScroller {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        ast.expect_keyword(&[
+            ("nearest", ScrollTimelineScroller::Nearest),
+            ("root", ScrollTimelineScroller::Root),
+            ("self", ScrollTimelineScroller::SelfElement),
+        ])
+    }
+}
+
+impl FromTokens for TimelineAxis {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        ast.expect_keyword(&[
+            ("block", TimelineAxis::Block),
+            ("inline", TimelineAxis::Inline),
+            ("x", TimelineAxis::X),
+            ("y", TimelineAxis::Y),
+        ])
+    }
+}
+
+/// `auto | <length-percentage>`, where `auto` is `None`
+fn parse_view_timeline_inset(
+    ast: &mut Ast,
+) -> Result<Option<data_types::LengthPercentage>, ParseError> {
+    if ast
+        .try_parse(|ast| ast.expect_ident_matching("auto"))
+        .is_ok()
+    {
+        return Ok(None);
+    }
+    Ok(Some(ast.parse()?))
+}
+
+impl FromTokens for AnimationTimelineValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        match ast.next_token()? {
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("auto") => {
+                Ok(AnimationTimelineValue::Auto)
+            }
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("none") => {
+                Ok(AnimationTimelineValue::None)
+            }
+            Token::Ident(ident) if ident.starts_with("--") => Ok(AnimationTimelineValue::Named(
+                data_types::DashedIdent(ident),
+            )),
+            // `scroll([<scroller> || <axis>]?)`
+            Token::Function(name) if name.eq_ignore_ascii_case("scroll") => {
+                ast.parse_nested_block(|ast| {
+                    let mut scroller = None;
+                    let mut axis = None;
+                    while !ast.is_exhausted() {
+                        if scroller.is_none() {
+                            if let Ok(value) = ast.try_parse(Ast::parse) {
+                                scroller = Some(value);
+                                continue;
+                            }
+                        }
+                        if axis.is_none() {
+                            if let Ok(value) = ast.try_parse(Ast::parse) {
+                                axis = Some(value);
+                                continue;
+                            }
+                        }
+                        return Err(ParseError::UnexpectedToken(ast.next_token()?));
+                    }
+                    Ok(AnimationTimelineValue::Scroll {
+                        scroller: scroller.unwrap_or(ScrollTimelineScroller::Nearest),
+                        axis: axis.unwrap_or(TimelineAxis::Block),
+                    })
+                })
+            }
+            // `view([<axis> || <'view-timeline-inset'>]?)`
+            Token::Function(name) if name.eq_ignore_ascii_case("view") => {
+                ast.parse_nested_block(|ast| {
+                    let mut axis = None;
+                    let mut inset = None;
+                    while !ast.is_exhausted() {
+                        if axis.is_none() {
+                            if let Ok(value) = ast.try_parse(Ast::parse) {
+                                axis = Some(value);
+                                continue;
+                            }
+                        }
+                        if inset.is_none() {
+                            let start = parse_view_timeline_inset(ast)?;
+                            let end = ast
+                                .try_parse(parse_view_timeline_inset)
+                                .unwrap_or(start.clone());
+                            inset = Some((start, end));
+                            continue;
+                        }
+                        return Err(ParseError::UnexpectedToken(ast.next_token()?));
+                    }
+                    let (inset_start, inset_end) = inset.unwrap_or((None, None));
+                    Ok(AnimationTimelineValue::View {
+                        axis: axis.unwrap_or(TimelineAxis::Block),
+                        inset_start,
+                        inset_end,
+                    })
+                })
+            }
+            token => Err(ParseError::UnexpectedToken(token)),
+        }
+    }
+}
+
+impl FromTokens for AnimationComposition {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationComposition(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationDelay {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationDelay(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationDirection {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationDirection(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationDuration {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationDuration(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationFillMode {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationFillMode(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationIterationCount {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationIterationCount(
+            ast.parse_comma_separated(Ast::parse)?,
+        ))
+    }
+}
+
+impl FromTokens for AnimationName {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationName(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationPlayState {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationPlayState(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationRangeEnd {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationRangeEnd(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationRangeStart {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationRangeStart(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationTimeline {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationTimeline(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationTimingFunction {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationTimingFunction(
+            ast.parse_comma_separated(Ast::parse)?,
+        ))
+    }
+}
+
+/// `<'animation-duration'> || <easing-function> || <'animation-delay'> ||
+/// <single-animation-iteration-count> || <single-animation-direction> ||
+/// <single-animation-fill-mode> || <single-animation-play-state> ||
+/// [ none | <keyframes-name> ]`
+///
+/// The first time is the duration. Keywords are taken by the first of these
+/// that accepts them and hasn't been set yet, with the name last (so
+/// `animation: ease ease` names the animation `ease`).
+impl FromTokens for SingleAnimation {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let mut animation = SingleAnimation {
+            name: None,
+            duration: None,
+            timing_function: None,
+            delay: None,
+            iteration_count: None,
+            direction: None,
+            fill_mode: None,
+            play_state: None,
+        };
+
+        /// Try to parse a value into an unset `slot`
+        fn fill<T: FromTokens>(ast: &mut Ast, slot: &mut Option<T>) -> bool {
+            if slot.is_some() {
+                return false;
+            }
+            match ast.try_parse(Ast::parse) {
+                Ok(value) => {
+                    *slot = Some(value);
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+
+        if ast.is_exhausted() {
+            return Err(ParseError::UnexpectedEnd);
+        }
+        while !ast.is_exhausted() {
+            if animation.delay.is_none() {
+                if let Ok(data_types::Time(value, unit)) = ast.try_parse(Ast::parse) {
+                    if animation.duration.is_none() {
+                        animation.duration = Some(
+                            data_types::PositiveTime::new(value, unit)
+                                .ok_or(ParseError::InvalidValue)?,
+                        );
+                    } else {
+                        animation.delay = Some(data_types::Time(value, unit));
+                    }
+                    continue;
+                }
+            }
+            if fill(ast, &mut animation.timing_function)
+                || fill(ast, &mut animation.iteration_count)
+                || fill(ast, &mut animation.direction)
+                || fill(ast, &mut animation.fill_mode)
+                || fill(ast, &mut animation.play_state)
+                || fill(ast, &mut animation.name)
+            {
+                continue;
+            }
+            return Err(ParseError::UnexpectedToken(ast.next_token()?));
+        }
+        Ok(animation)
+    }
+}
+
+impl FromTokens for Animation {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(Animation(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for AnimationRangeItem {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationRangeItem {
+            start: ast.parse()?,
+            end: ast.try_parse(Ast::parse).ok(),
+        })
+    }
+}
+
+impl FromTokens for AnimationRange {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(AnimationRange(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+//* Transitions
+
+impl FromTokens for TransitionBehaviorValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        ast.expect_keyword(&[
+            ("normal", TransitionBehaviorValue::Normal),
+            ("allow-discrete", TransitionBehaviorValue::AllowDiscrete),
+        ])
+    }
+}
+
+impl FromTokens for TransitionBehavior {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(TransitionBehavior(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for TransitionDelay {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(TransitionDelay(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for TransitionDuration {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(TransitionDuration(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for TransitionPropertyValue {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let data_types::CustomIdent(name) = ast.parse()?;
+        if name.eq_ignore_ascii_case("none") {
+            return Err(ParseError::InvalidValue);
+        }
+        Ok(TransitionPropertyValue::from_name(&name))
+    }
+}
+
+impl FromTokens for TransitionProperty {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("none"))
+            .is_ok()
+        {
+            return Ok(TransitionProperty::None);
+        }
+        Ok(TransitionProperty::Properties(
+            ast.parse_comma_separated(Ast::parse)?,
+        ))
+    }
+}
+
+impl FromTokens for TransitionTimingFunction {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(TransitionTimingFunction(
+            ast.parse_comma_separated(Ast::parse)?,
+        ))
+    }
+}
+
+/// `[ none | <single-transition-property> ] || <time> || <easing-function> ||
+/// <time> || <transition-behavior-value>`, where the first time is the
+/// duration
+impl FromTokens for SingleTransition {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let mut property = None;
+        let mut duration = None;
+        let mut timing_function = None;
+        let mut delay = None;
+        let mut behavior = None;
+
+        if ast.is_exhausted() {
+            return Err(ParseError::UnexpectedEnd);
+        }
+        while !ast.is_exhausted() {
+            if delay.is_none() {
+                if let Ok(data_types::Time(value, unit)) = ast.try_parse(Ast::parse) {
+                    if duration.is_none() {
+                        duration = Some(
+                            data_types::PositiveTime::new(value, unit)
+                                .ok_or(ParseError::InvalidValue)?,
+                        );
+                    } else {
+                        delay = Some(data_types::Time(value, unit));
+                    }
+                    continue;
+                }
+            }
+            // Easing and behavior keywords take precedence over property names
+            if timing_function.is_none() {
+                if let Ok(value) = ast.try_parse(Ast::parse) {
+                    timing_function = Some(value);
+                    continue;
+                }
+            }
+            if behavior.is_none() {
+                if let Ok(value) = ast.try_parse(Ast::parse) {
+                    behavior = Some(value);
+                    continue;
+                }
+            }
+            if property.is_none() {
+                if ast
+                    .try_parse(|ast| ast.expect_ident_matching("none"))
+                    .is_ok()
+                {
+                    property = Some(None);
+                    continue;
+                }
+                if let Ok(value) = ast.try_parse(Ast::parse) {
+                    property = Some(Some(value));
+                    continue;
+                }
+            }
+            return Err(ParseError::UnexpectedToken(ast.next_token()?));
+        }
+
+        Ok(SingleTransition {
+            property: property.unwrap_or(Some(TransitionPropertyValue::All)),
+            duration,
+            timing_function,
+            delay,
+            behavior,
+        })
+    }
+}
+
+impl FromTokens for Transition {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let transitions: Vec<SingleTransition> = ast.parse_comma_separated(Ast::parse)?;
+        if transitions.len() > 1
+            && transitions
+                .iter()
+                .any(|transition| transition.property.is_none())
+        {
+            return Err(ParseError::InvalidValue);
+        }
+        Ok(Transition(transitions))
+    }
+}
+
+//* Declarations
+
+/// A quoted name, or identifiers that don't start with a generic family
+/// keyword
+impl FromTokens for FamilyName {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if let Ok(name) = ast.try_parse(Ast::parse) {
+            return Ok(FamilyName::Quoted(name));
+        }
+        let first: data_types::CustomIdent = ast.parse()?;
+        const GENERIC: &[&str] = &[
+            "serif",
+            "sans-serif",
+            "monospace",
+            "cursive",
+            "fantasy",
+            "system-ui",
+            "ui-serif",
+            "ui-sans-serif",
+            "ui-monospace",
+            "ui-rounded",
+            "emoji",
+            "math",
+            "fangsong",
+        ];
+        if GENERIC
+            .iter()
+            .any(|generic| first.0.eq_ignore_ascii_case(generic))
+        {
+            return Err(ParseError::InvalidValue);
+        }
+        let mut idents = vec![first];
+        while let Ok(ident) = ast.try_parse(Ast::parse) {
+            idents.push(ident);
+        }
+        Ok(FamilyName::Unquoted(idents))
+    }
+}
+
+/// `normal | [<opentype-tag> [<integer [0,∞]> | on | off]?]#`
+impl FromTokens for FontFeatureSettings {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("normal"))
+            .is_ok()
+        {
+            return Ok(FontFeatureSettings(Vec::new()));
+        }
+        let features = ast.parse_comma_separated(|ast| {
+            let tag = ast.parse()?;
+            let value = match ast.peek() {
+                None => 1,
+                Some(Token::Ident(_)) => ast.expect_keyword(&[("on", 1), ("off", 0)])?,
+                Some(_) => {
+                    let data_types::Integer(value) = ast.parse()?;
+                    if value < 0 {
+                        return Err(ParseError::InvalidValue);
+                    }
+                    value as u32
+                }
+            };
+            Ok(FontFeatureTagValue { tag, value })
+        })?;
+        Ok(FontFeatureSettings(features))
+    }
+}
+
+/// `normal | [<opentype-tag> <number>]#`
+impl FromTokens for FontVariationSettings {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("normal"))
+            .is_ok()
+        {
+            return Ok(FontVariationSettings(Vec::new()));
+        }
+        let axes = ast.parse_comma_separated(|ast| {
+            Ok(FontVariationAxisValue {
+                tag: ast.parse()?,
+                value: ast.parse()?,
+            })
+        })?;
+        Ok(FontVariationSettings(axes))
+    }
+}
+
+impl FromTokens for FontWeightAbsolute {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if let Ok(ident) = ast.try_parse(Ast::expect_ident) {
+            return match ident.to_ascii_lowercase().as_str() {
+                "normal" => Ok(FontWeightAbsolute::Normal),
+                "bold" => Ok(FontWeightAbsolute::Bold),
+                _ => Err(ParseError::UnexpectedToken(Token::Ident(ident))),
+            };
+        }
+        let data_types::Number(weight) = ast.parse()?;
+        FontWeightAbsolute::number(weight).ok_or(ParseError::InvalidValue)
+    }
+}
+
+/// A keyword, or a percentage that isn't negative
+impl FromTokens for FontStretch {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if let Ok(percentage) = ast.try_parse(Ast::parse::<data_types::Percentage>) {
+            if percentage.0 .0 < 0.0 {
+                return Err(ParseError::InvalidValue);
+            }
+            return Ok(FontStretch::Percentage(percentage));
+        }
+        let ident = ast.expect_ident()?;
+        Ok(FontStretch::Keyword(
+            match ident.to_ascii_lowercase().as_str() {
+                "ultra-condensed" => FontStretchKeyword::UltraCondensed,
+                "extra-condensed" => FontStretchKeyword::ExtraCondensed,
+                "condensed" => FontStretchKeyword::Condensed,
+                "semi-condensed" => FontStretchKeyword::SemiCondensed,
+                "normal" => FontStretchKeyword::Normal,
+                "semi-expanded" => FontStretchKeyword::SemiExpanded,
+                "expanded" => FontStretchKeyword::Expanded,
+                "extra-expanded" => FontStretchKeyword::ExtraExpanded,
+                "ultra-expanded" => FontStretchKeyword::UltraExpanded,
+                _ => return Err(ParseError::UnexpectedToken(Token::Ident(ident))),
+            },
+        ))
+    }
+}
+
+impl Property {
+    /// Parse the value of the property called `name`
+    ///
+    /// Only the `animation-*` and `transition-*` properties have parsers so
+    /// far. Other known properties fail with
+    /// [`ParseError::UnsupportedProperty`] (rather than as an invalid value),
+    /// and unknown names with [`ParseError::UnexpectedToken`].
+    pub fn from_declaration(name: &str, ast: &mut Ast) -> Result<Self, ParseError> {
+        use PropertyDiscriminants as Name;
+        let name: Name = name
+            .to_ascii_lowercase()
+            .parse()
+            .map_err(|_| ParseError::UnexpectedToken(Token::Ident(name.to_owned())))?;
+        Ok(match name {
+            Name::Animation => Property::Animation(ast.parse()?),
+            Name::AnimationComposition => Property::AnimationComposition(ast.parse()?),
+            Name::AnimationDelay => Property::AnimationDelay(ast.parse()?),
+            Name::AnimationDirection => Property::AnimationDirection(ast.parse()?),
+            Name::AnimationDuration => Property::AnimationDuration(ast.parse()?),
+            Name::AnimationFillMode => Property::AnimationFillMode(ast.parse()?),
+            Name::AnimationIterationCount => Property::AnimationIterationCount(ast.parse()?),
+            Name::AnimationName => Property::AnimationName(ast.parse()?),
+            Name::AnimationPlayState => Property::AnimationPlayState(ast.parse()?),
+            Name::AnimationRange => Property::AnimationRange(ast.parse()?),
+            Name::AnimationRangeEnd => Property::AnimationRangeEnd(ast.parse()?),
+            Name::AnimationRangeStart => Property::AnimationRangeStart(ast.parse()?),
+            Name::AnimationTimeline => Property::AnimationTimeline(ast.parse()?),
+            Name::AnimationTimingFunction => Property::AnimationTimingFunction(ast.parse()?),
+            Name::Transition => Property::Transition(ast.parse()?),
+            Name::TransitionBehavior => Property::TransitionBehavior(ast.parse()?),
+            Name::TransitionDelay => Property::TransitionDelay(ast.parse()?),
+            Name::TransitionDuration => Property::TransitionDuration(ast.parse()?),
+            Name::TransitionProperty => Property::TransitionProperty(ast.parse()?),
+            Name::TransitionTimingFunction => Property::TransitionTimingFunction(ast.parse()?),
+            name => return Err(ParseError::UnsupportedProperty(name)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declaration(name: &str, value: &str) -> Result<Property, ParseError> {
+        let mut ast = Ast::new(value);
+        let property = Property::from_declaration(name, &mut ast)?;
+        ast.expect_exhausted()?;
+        Ok(property)
+    }
+
+    #[test]
+    fn from_declaration() {
+        assert!(declaration("transition-duration", "1s, 200ms").is_ok());
+        assert!(declaration("Animation-Name", "slide").is_ok());
+        assert_eq!(
+            declaration("transition-duration", "-1s"),
+            Err(ParseError::InvalidValue)
+        );
+        assert_eq!(
+            declaration("display", "flex"),
+            Err(ParseError::UnsupportedProperty(
+                PropertyDiscriminants::Display
+            ))
+        );
+        assert_eq!(
+            declaration("colour", "red"),
+            Err(ParseError::UnexpectedToken(Token::Ident(
+                "colour".to_owned()
+            )))
+        );
+    }
+
+    #[test]
+    fn omitted_transition_property_is_all() {
+        let transition = match declaration("transition", "1s") {
+            Ok(Property::Transition(transition)) => transition,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(transition.0[0].property, Some(TransitionPropertyValue::All));
+        let transition = match declaration("transition", "none 1s") {
+            Ok(Property::Transition(transition)) => transition,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(transition.0[0].property, None);
+    }
+
+    fn transition(value: &str) -> Result<Vec<SingleTransition>, ParseError> {
+        crate::parser::parse::<Transition>(value).map(|transition| transition.0)
+    }
+
+    fn seconds(value: f32) -> data_types::Time {
+        data_types::Time(value, data_types::TimeUnit::S)
+    }
+
+    #[test]
+    fn transition_keywords_and_property_names() {
+        // Keywords are taken as the easing function or behavior first, so a
+        // repeated keyword can only be the property name
+        let single = &transition("ease").unwrap()[0];
+        assert_eq!(single.property, Some(TransitionPropertyValue::All));
+        assert_eq!(single.timing_function, Some(EasingFunction::Ease));
+        let single = &transition("ease ease").unwrap()[0];
+        assert_eq!(
+            single.property,
+            Some(TransitionPropertyValue::Unknown(data_types::CustomIdent(
+                "ease".to_owned()
+            )))
+        );
+        assert_eq!(single.timing_function, Some(EasingFunction::Ease));
+        let single = &transition("allow-discrete allow-discrete").unwrap()[0];
+        assert_eq!(
+            single.behavior,
+            Some(TransitionBehaviorValue::AllowDiscrete)
+        );
+        assert!(matches!(
+            single.property,
+            Some(TransitionPropertyValue::Unknown(_))
+        ));
+
+        let single = &transition("linear Transform").unwrap()[0];
+        assert_eq!(
+            single.property,
+            Some(TransitionPropertyValue::Property(
+                PropertyDiscriminants::Transform
+            ))
+        );
+        assert_eq!(
+            transition("rotate transform"),
+            Err(ParseError::UnexpectedToken(Token::Ident(
+                "transform".to_owned()
+            )))
+        );
+        assert!(transition("ease linear ease").is_err());
+        assert!(transition("inherit 1s").is_err());
+    }
+
+    #[test]
+    fn transition_duration_and_delay() {
+        // The first time is the duration and the second is the delay, wherever
+        // they are
+        let single = &transition("1s opacity ease-in 2s").unwrap()[0];
+        assert_eq!(
+            single.duration,
+            Some(data_types::PositiveTime::new(1.0, data_types::TimeUnit::S).unwrap())
+        );
+        assert_eq!(single.delay, Some(seconds(2.0)));
+        let single = &transition("opacity 500ms -1s").unwrap()[0];
+        assert_eq!(
+            single.duration,
+            Some(data_types::PositiveTime::new(500.0, data_types::TimeUnit::Ms).unwrap())
+        );
+        assert_eq!(single.delay, Some(seconds(-1.0)));
+        let single = &transition("opacity").unwrap()[0];
+        assert_eq!((single.duration, single.delay), (None, None));
+
+        // Only the delay can be negative
+        assert_eq!(transition("-1s 1s"), Err(ParseError::InvalidValue));
+        assert!(transition("1s 2s 3s").is_err());
+    }
+
+    #[test]
+    fn transition_lists() {
+        let transitions = transition("opacity 1s, transform 2s linear").unwrap();
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[1].timing_function, Some(EasingFunction::Linear));
+        assert!(transition("opacity 1s, all").is_ok());
+
+        // `none` is only valid as the only transition
+        assert_eq!(transition("none").unwrap()[0].property, None);
+        assert_eq!(
+            transition("none, opacity 1s"),
+            Err(ParseError::InvalidValue)
+        );
+        assert_eq!(
+            transition("opacity 1s, none 2s"),
+            Err(ParseError::InvalidValue)
+        );
+        assert!(transition("opacity 1s,").is_err());
+        assert!(transition(", opacity 1s").is_err());
+    }
+
+    fn animation(value: &str) -> Result<Vec<SingleAnimation>, ParseError> {
+        crate::parser::parse::<Animation>(value).map(|animation| animation.0)
+    }
+
+    fn custom(name: &str) -> Option<AnimationNameValue> {
+        Some(AnimationNameValue::Custom(data_types::CustomIdent(
+            name.to_owned(),
+        )))
+    }
+
+    #[test]
+    fn animation_keywords_and_names() {
+        // A keyword is only the name once its own longhand is taken
+        let single = &animation("infinite infinite").unwrap()[0];
+        assert_eq!(
+            single.iteration_count,
+            Some(AnimationIterationCountValue::Infinite)
+        );
+        assert_eq!(single.name, custom("infinite"));
+        let single = &animation("paused reverse slide").unwrap()[0];
+        assert_eq!(single.play_state, Some(AnimationPlayStateValue::Paused));
+        assert_eq!(single.direction, Some(AnimationDirectionValue::Reverse));
+        assert_eq!(single.name, custom("slide"));
+        let single = &animation("infinite").unwrap()[0];
+        assert_eq!(single.name, None);
+        // `none` is taken by `animation-fill-mode` first, which leaves the
+        // name as its initial `none` too
+        let single = &animation("none").unwrap()[0];
+        assert_eq!(single.fill_mode, Some(AnimationFillModeValue::None));
+        assert_eq!(single.name, None);
+        assert_eq!(
+            animation("none none").unwrap()[0].name,
+            Some(AnimationNameValue::None)
+        );
+        assert!(animation("slide fade").is_err());
+        assert!(animation("infinite infinite infinite").is_err());
+    }
+
+    #[test]
+    fn animation_duration_and_delay() {
+        let single = &animation("slide 2s ease 500ms").unwrap()[0];
+        assert_eq!(
+            single.duration,
+            Some(data_types::PositiveTime::new(2.0, data_types::TimeUnit::S).unwrap())
+        );
+        assert_eq!(
+            single.delay,
+            Some(data_types::Time(500.0, data_types::TimeUnit::Ms))
+        );
+        let single = &animation("1s -1s").unwrap()[0];
+        assert_eq!(
+            single.delay,
+            Some(data_types::Time(-1.0, data_types::TimeUnit::S))
+        );
+        assert_eq!(animation("-1s 1s"), Err(ParseError::InvalidValue));
+        assert!(animation("1s 2s 3s").is_err());
+    }
+
+    #[test]
+    fn animation_lists() {
+        let animations = animation("slide 1s, none none, 2s fade").unwrap();
+        assert_eq!(animations.len(), 3);
+        assert_eq!(animations[1].name, Some(AnimationNameValue::None));
+        assert_eq!(animations[2].name, custom("fade"));
+
+        let longhands = Animation(animations).into_longhands();
+        assert_eq!(longhands.animation_count(), 3);
+        assert_eq!(
+            crate::parser::parse::<AnimationDuration>("1s, 2s").unwrap(),
+            AnimationDuration(vec![
+                data_types::PositiveTime::new(1.0, data_types::TimeUnit::S).unwrap(),
+                data_types::PositiveTime::new(2.0, data_types::TimeUnit::S).unwrap(),
+            ])
+        );
+        assert!(crate::parser::parse::<AnimationName>("slide,").is_err());
+    }
+}
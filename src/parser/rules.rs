@@ -0,0 +1,812 @@
+use std::string::String as StdString;
+
+use super::{
+    at_rules::{parse_font_face_src, parse_non_negative_percentage},
+    Ast, ParseError, Token,
+};
+use crate::{
+    animation::is_animation_property,
+    at_rules::*,
+    properties::{FamilyName, Property, PropertyDiscriminants},
+    rules::{Declaration, DeclarationValue, Rule, StyleRule},
+    selectors::{ComplexSelector, CompoundSelector, SelectorList, SubclassSelector},
+};
+
+/// Parse a stylesheet, dropping rules that are invalid or out of order (like
+/// an `@import` after a style rule) and declarations with invalid values
+///
+/// Declarations that can't be parsed but aren't known to be invalid (like
+/// those for properties without a parser) are kept as
+/// [`DeclarationValue::Unparsed`], and at-rules other than `@charset`,
+/// `@import`, `@namespace`, `@layer`, `@media`, `@supports`, `@keyframes` and
+/// `@font-face` as [`AtRule::Unknown`], so that nothing valid is lost.
+///
+/// https://drafts.csswg.org/css-syntax-3/#parse-stylesheet
+pub fn parse_stylesheet(input: &str) -> Vec<Rule> {
+    let mut ast = Ast::new(input);
+    let (_, mut rules) = parse_contents(&mut ast, Context::Stylesheet);
+
+    // `@charset` must be first, then `@import`s, then `@namespace`s (with
+    // `@layer` statements allowed before and between `@import`s)
+    let mut stage = 0;
+    let mut first = true;
+    rules.retain(|rule| {
+        let allowed = match rule {
+            Rule::At(AtRule::Charset(_)) => first,
+            Rule::At(AtRule::Import(_)) if stage <= 1 => {
+                stage = 1;
+                true
+            }
+            Rule::At(AtRule::LayerStatement(_)) if stage <= 1 => true,
+            Rule::At(AtRule::Import(_)) => false,
+            Rule::At(AtRule::Namespace(_)) if stage <= 2 => {
+                stage = 2;
+                true
+            }
+            Rule::At(AtRule::Namespace(_)) => false,
+            _ => {
+                stage = 3;
+                true
+            }
+        };
+        first = false;
+        allowed
+    });
+    rules
+}
+
+/// Where a list of rules is, which decides what it can contain
+#[derive(Clone, Copy, PartialEq)]
+enum Context {
+    /// The top level of a stylesheet, which can have `@charset`, `@import` and
+    /// `@namespace` rules
+    Stylesheet,
+    /// Inside a top-level conditional or `@layer` rule
+    Rules,
+    /// Inside a style rule (or a conditional rule nested in one), where
+    /// there can be declarations
+    Nested,
+}
+
+/// Parse the rules (and, in [`Context::Nested`], declarations) of a
+/// stylesheet or block
+fn parse_contents(ast: &mut Ast, context: Context) -> (Vec<Declaration>, Vec<Rule>) {
+    let mut declarations = Vec::new();
+    let mut rules = Vec::new();
+    while let Some(token) = ast.tokens.get(ast.position) {
+        match token {
+            Token::Whitespace | Token::Semicolon | Token::Cdo | Token::Cdc => {
+                ast.position += 1;
+                continue;
+            }
+            Token::AtKeyword(name) => {
+                let name = name.to_ascii_lowercase();
+                ast.position += 1;
+                let (prelude, block) = split_rule(ast, true);
+                if let Some(rule) = parse_at_rule(&name, prelude, block, context) {
+                    rules.push(rule);
+                }
+            }
+            Token::Ident(name) if name.starts_with("--") && context == Context::Nested => {
+                let declaration = split_custom_property(ast);
+                declarations.extend(parse_declaration(declaration));
+            }
+            _ => {
+                // In a style rule, anything without a block is a declaration
+                let (prelude, block) = split_rule(ast, context == Context::Nested);
+                match block {
+                    Some(block) => {
+                        if let Some(rule) = parse_style_rule(prelude, block, context) {
+                            rules.push(rule);
+                        }
+                    }
+                    None if context == Context::Nested => {
+                        declarations.extend(parse_declaration(prelude));
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+    (declarations, rules)
+}
+
+/// Split off the tokens of the rule starting at the current position, up to
+/// a `;` (if `semicolon`) or the end of its `{}` block
+fn split_rule(ast: &mut Ast, semicolon: bool) -> (Ast, Option<Ast>) {
+    let start = ast.position;
+    let mut depth = 0;
+    let mut end = start;
+    while let Some(token) = ast.tokens.get(end) {
+        match token {
+            Token::OpenCurly if depth == 0 => {
+                let block_end = ast.block_end(end + 1);
+                ast.position = (block_end + 1).min(ast.tokens.len());
+                return (ast.slice(start, end), Some(ast.slice(end + 1, block_end)));
+            }
+            Token::Semicolon if depth == 0 && semicolon => break,
+            token if token.is_block_start() => depth += 1,
+            token if token.is_block_end() && depth > 0 => depth -= 1,
+            _ => {}
+        }
+        end += 1;
+    }
+    ast.position = (end + 1).min(ast.tokens.len());
+    (ast.slice(start, end), None)
+}
+
+/// Split off a custom property declaration up to the next `;`, which
+/// (unlike other declarations and rules) can contain `{}` blocks
+fn split_custom_property(ast: &mut Ast) -> Ast {
+    let start = ast.position;
+    let mut depth = 0;
+    let mut end = start;
+    while let Some(token) = ast.tokens.get(end) {
+        match token {
+            Token::Semicolon if depth == 0 => break,
+            token if token.is_block_start() => depth += 1,
+            token if token.is_block_end() && depth > 0 => depth -= 1,
+            _ => {}
+        }
+        end += 1;
+    }
+    ast.position = (end + 1).min(ast.tokens.len());
+    ast.slice(start, end)
+}
+
+impl Ast {
+    fn slice(&self, start: usize, end: usize) -> Ast {
+        Ast {
+            tokens: self.tokens[start..end].to_vec(),
+            position: 0,
+        }
+    }
+}
+
+fn parse_style_rule(mut prelude: Ast, mut block: Ast, context: Context) -> Option<Rule> {
+    let selectors = match context {
+        Context::Nested => parse_all(&mut prelude, Ast::parse_nested_selector_list)?,
+        _ => parse_all(&mut prelude, Ast::parse)?,
+    };
+    let (declarations, rules) = parse_contents(&mut block, Context::Nested);
+    Some(Rule::Style(StyleRule {
+        selectors,
+        declarations,
+        rules,
+    }))
+}
+
+fn parse_at_rule(
+    name: &str,
+    mut prelude: Ast,
+    block: Option<Ast>,
+    context: Context,
+) -> Option<Rule> {
+    let prelude = &mut prelude;
+    let top_level = context == Context::Stylesheet;
+    let rule = match (name, block) {
+        ("charset", None) if top_level => AtRule::Charset(parse_all(prelude, Ast::parse)?),
+        ("import", None) if top_level => AtRule::Import(parse_all(prelude, Ast::parse)?),
+        ("namespace", None) if top_level => AtRule::Namespace(parse_all(prelude, Ast::parse)?),
+        ("layer", None) => AtRule::LayerStatement(parse_all(prelude, |ast| {
+            ast.parse_comma_separated(Ast::parse)
+        })?),
+        ("layer", Some(block)) => AtRule::Layer(LayerRule {
+            name: match prelude.is_exhausted() {
+                true => None,
+                false => Some(parse_all(prelude, Ast::parse)?),
+            },
+            rules: parse_block(block, context),
+        }),
+        ("media", Some(block)) => AtRule::Media(MediaRule {
+            queries: parse_all(prelude, Ast::parse)?,
+            rules: parse_block(block, context),
+        }),
+        ("supports", Some(block)) => AtRule::Supports(SupportsRule {
+            condition: parse_all(prelude, Ast::parse)?,
+            rules: parse_block(block, context),
+        }),
+        ("keyframes", Some(block)) => AtRule::Keyframes(Keyframes {
+            name: parse_all(prelude, Ast::parse)?,
+            keyframes: parse_keyframes(block),
+        }),
+        ("font-face", Some(block)) if context != Context::Nested && prelude.is_exhausted() => {
+            AtRule::FontFace(parse_font_face(block)?)
+        }
+        (name, block) if !KNOWN_AT_RULES.contains(&name) => AtRule::Unknown(UnknownAtRule {
+            name: name.to_owned(),
+            prelude: serialize(&prelude.tokens),
+            block: block.map(|block| serialize(&block.tokens)),
+        }),
+        _ => return None,
+    };
+    Some(Rule::At(rule))
+}
+
+/// The at-rules that are parsed, which are dropped if they're invalid rather
+/// than kept as [`AtRule::Unknown`]
+const KNOWN_AT_RULES: &[&str] = &[
+    "charset",
+    "import",
+    "namespace",
+    "layer",
+    "media",
+    "supports",
+    "keyframes",
+    "font-face",
+];
+
+/// The tokens as text, without whitespace at either end
+fn serialize(tokens: &[Token]) -> StdString {
+    let start = tokens
+        .iter()
+        .position(|token| *token != Token::Whitespace)
+        .unwrap_or(tokens.len());
+    let end = tokens
+        .iter()
+        .rposition(|token| *token != Token::Whitespace)
+        .map_or(start, |end| end + 1);
+    tokens[start..end].iter().map(Token::to_string).collect()
+}
+
+/// Parse all of `ast` with `parse`
+fn parse_all<T, F>(ast: &mut Ast, parse: F) -> Option<T>
+where
+    F: FnOnce(&mut Ast) -> Result<T, ParseError>,
+{
+    let value = parse(ast).ok()?;
+    ast.expect_exhausted().ok()?;
+    Some(value)
+}
+
+/// The rules in the block of a conditional or `@layer` rule, where
+/// declarations (when nested in a style rule) become a rule for `&`
+fn parse_block(mut block: Ast, context: Context) -> Vec<Rule> {
+    let context = match context {
+        Context::Stylesheet => Context::Rules,
+        context => context,
+    };
+    let (declarations, mut rules) = parse_contents(&mut block, context);
+    if !declarations.is_empty() {
+        let nesting = ComplexSelector {
+            first: CompoundSelector {
+                type_selector: None,
+                subclasses: vec![SubclassSelector::Nesting],
+                pseudo_elements: Vec::new(),
+            },
+            rest: Vec::new(),
+        };
+        rules.insert(
+            0,
+            Rule::Style(StyleRule {
+                selectors: SelectorList(vec![nesting]),
+                declarations,
+                rules: Vec::new(),
+            }),
+        );
+    }
+    rules
+}
+
+/// The keyframes in the block of `@keyframes`, where keyframes with an
+/// invalid selector are dropped
+fn parse_keyframes(mut block: Ast) -> Vec<Keyframe> {
+    let mut keyframes = Vec::new();
+    while let Some(token) = block.tokens.get(block.position) {
+        if matches!(token, Token::Whitespace | Token::Semicolon) {
+            block.position += 1;
+            continue;
+        }
+        let (mut prelude, body) = split_rule(&mut block, false);
+        let (selectors, mut body) = match (
+            parse_all(&mut prelude, |ast| ast.parse_comma_separated(Ast::parse)),
+            body,
+        ) {
+            (Some(selectors), Some(body)) => (selectors, body),
+            _ => continue,
+        };
+
+        let mut keyframe = Keyframe {
+            selectors,
+            declarations: Vec::new(),
+            timing_function: None,
+        };
+        while !body.is_exhausted() {
+            let (declaration, _) = split_rule(&mut body, true);
+            let value = match parse_declaration(declaration) {
+                // `!important` declarations are ignored in keyframes
+                Some(Declaration {
+                    value,
+                    important: false,
+                }) => value,
+                _ => continue,
+            };
+            let property = match &value {
+                DeclarationValue::Parsed(property) => Some(PropertyDiscriminants::from(property)),
+                DeclarationValue::Unparsed { name, .. } => name.to_ascii_lowercase().parse().ok(),
+            };
+            match value {
+                DeclarationValue::Parsed(Property::AnimationTimingFunction(function)) => {
+                    keyframe.timing_function = function.0.into_iter().next();
+                }
+                _ if property.is_some_and(is_animation_property) => {}
+                value => keyframe.declarations.push(value),
+            }
+        }
+        keyframes.push(keyframe);
+    }
+    keyframes
+}
+
+/// The descriptors in the block of `@font-face`, where invalid (and
+/// `!important`) descriptors are dropped, as is the whole rule without a
+/// valid `font-family` and `src`
+fn parse_font_face(mut block: Ast) -> Option<FontFace> {
+    fn set<T>(descriptor: &mut Option<T>, value: Option<T>) {
+        if value.is_some() {
+            *descriptor = value;
+        }
+    }
+
+    let mut font_family = None;
+    let mut src = None;
+    let mut unicode_range = None;
+    let mut face = FontFace::new(FamilyName::Unquoted(Vec::new()), Vec::new());
+    while !block.is_exhausted() {
+        let (declaration, _) = split_rule(&mut block, true);
+        let (name, mut value) = match split_declaration(declaration) {
+            Some((name, value, false)) => (name, value),
+            _ => continue,
+        };
+        let value = &mut value;
+        match name.to_ascii_lowercase().as_str() {
+            "font-family" => set(&mut font_family, parse_all(value, Ast::parse)),
+            "src" => set(&mut src, parse_all(value, parse_font_face_src)),
+            "font-style" => set(&mut face.font_style, parse_all(value, Ast::parse)),
+            "font-weight" => set(&mut face.font_weight, parse_all(value, Ast::parse)),
+            "font-stretch" => set(&mut face.font_stretch, parse_all(value, Ast::parse)),
+            "font-display" => set(&mut face.font_display, parse_all(value, Ast::parse)),
+            "unicode-range" => set(
+                &mut unicode_range,
+                parse_all(value, |ast| ast.parse_comma_separated(Ast::parse)),
+            ),
+            "font-feature-settings" => set(
+                &mut face.font_feature_settings,
+                parse_all(value, Ast::parse),
+            ),
+            "font-variation-settings" => set(
+                &mut face.font_variation_settings,
+                parse_all(value, Ast::parse),
+            ),
+            "ascent-override" => set(&mut face.ascent_override, parse_all(value, Ast::parse)),
+            "descent-override" => set(&mut face.descent_override, parse_all(value, Ast::parse)),
+            "line-gap-override" => set(&mut face.line_gap_override, parse_all(value, Ast::parse)),
+            "size-adjust" => set(
+                &mut face.size_adjust,
+                parse_all(value, parse_non_negative_percentage),
+            ),
+            _ => {}
+        }
+    }
+    Some(FontFace {
+        font_family: font_family?,
+        src: src?,
+        unicode_range: unicode_range.unwrap_or_default(),
+        ..face
+    })
+}
+
+/// `property: value`, which is kept unparsed if the property doesn't have a
+/// parser (including unknown and custom properties) or the value uses
+/// `var()`, and dropped if the value is invalid
+fn parse_declaration(ast: Ast) -> Option<Declaration> {
+    let (name, mut value, important) = split_declaration(ast)?;
+    let unparsed = DeclarationValue::Unparsed {
+        name: name.clone(),
+        value: serialize(&value.tokens),
+    };
+    let substituted = value.tokens.iter().any(|token| {
+        matches!(token, Token::Function(function)
+            if ["var", "env", "attr"].iter().any(|name| function.eq_ignore_ascii_case(name)))
+    });
+    let known = name
+        .to_ascii_lowercase()
+        .parse::<PropertyDiscriminants>()
+        .is_ok();
+    if substituted || !known {
+        return Some(Declaration {
+            value: unparsed,
+            important,
+        });
+    }
+    let value = match Property::from_declaration(&name, &mut value) {
+        Ok(property) if value.is_exhausted() => DeclarationValue::Parsed(property),
+        Err(ParseError::UnsupportedProperty(_)) => unparsed,
+        _ => return None,
+    };
+    Some(Declaration { value, important })
+}
+
+/// Split `name: value` into the name, the value and whether it's
+/// `!important`
+fn split_declaration(mut ast: Ast) -> Option<(StdString, Ast, bool)> {
+    let name = ast.expect_ident().ok()?;
+    ast.expect_token(&Token::Colon).ok()?;
+    let (value, important) = split_important(&ast.tokens[ast.position..]);
+    let value = Ast {
+        tokens: value.to_vec(),
+        position: 0,
+    };
+    Some((name, value, important))
+}
+
+/// Split `!important` off the end of a declaration's value, returning the
+/// value without trailing whitespace and whether it was important
+pub(super) fn split_important(tokens: &[Token]) -> (&[Token], bool) {
+    fn trim_end(tokens: &[Token]) -> &[Token] {
+        match tokens {
+            [rest @ .., Token::Whitespace] => trim_end(rest),
+            _ => tokens,
+        }
+    }
+    match trim_end(tokens) {
+        [rest @ .., Token::Delim('!'), Token::Ident(important)]
+        | [rest @ .., Token::Delim('!'), Token::Whitespace, Token::Ident(important)]
+            if important.eq_ignore_ascii_case("important") =>
+        {
+            (trim_end(rest), true)
+        }
+        tokens => (tokens, false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        data_types::{self, Angle, AngleUnit, CustomIdent, Number, Percentage, Url},
+        properties::{EasingFunction, FontStretch, FontStretchKeyword, TimelineRangeName},
+    };
+
+    fn keyframes(input: &str) -> Keyframes {
+        match parse_stylesheet(input).as_slice() {
+            [Rule::At(AtRule::Keyframes(keyframes))] => keyframes.clone(),
+            rules => panic!("{:?}", rules),
+        }
+    }
+
+    #[test]
+    fn keyframes_selectors() {
+        let rule =
+            keyframes("@keyframes slide { from, 50% { } to { } entry 20% { } 120% { } a { } }");
+        assert_eq!(
+            rule.name,
+            KeyframesName::Custom(CustomIdent("slide".to_owned()))
+        );
+        let selectors: Vec<_> = rule.keyframes.iter().map(|k| k.selectors.clone()).collect();
+        assert_eq!(
+            selectors,
+            vec![
+                vec![
+                    KeyframeSelector::From,
+                    KeyframeSelector::Percentage(Percentage(Number(50.0))),
+                ],
+                vec![KeyframeSelector::To],
+                vec![KeyframeSelector::Range {
+                    name: TimelineRangeName::Entry,
+                    offset: Percentage(Number(20.0)),
+                }],
+            ]
+        );
+
+        let rule = keyframes("@keyframes \"not an ident\" {}");
+        assert_eq!(rule.name.as_str(), "not an ident");
+        assert!(parse_stylesheet("@keyframes none {}").is_empty());
+    }
+
+    #[test]
+    fn keyframes_declarations() {
+        let rule = keyframes(
+            "@keyframes a {
+                50% {
+                    transition-delay: 1s;
+                    transition-duration: 2s !important;
+                    animation-name: b;
+                    animation-delay: var(--delay);
+                    animation-timing-function: linear;
+                    opacity: 0.5;
+                    opacity: 1 !important;
+                }
+            }",
+        );
+        let keyframe = &rule.keyframes[0];
+        assert_eq!(keyframe.timing_function, Some(EasingFunction::Linear));
+        assert_eq!(keyframe.declarations.len(), 2);
+        assert!(matches!(
+            keyframe.declarations[0],
+            DeclarationValue::Parsed(Property::TransitionDelay(_))
+        ));
+        assert_eq!(
+            keyframe.declarations[1],
+            DeclarationValue::Unparsed {
+                name: "opacity".to_owned(),
+                value: "0.5".to_owned(),
+            }
+        );
+    }
+
+    fn font_face(input: &str) -> Option<FontFace> {
+        match parse_stylesheet(input).as_slice() {
+            [Rule::At(AtRule::FontFace(face))] => Some(face.clone()),
+            [] => None,
+            rules => panic!("{:?}", rules),
+        }
+    }
+
+    #[test]
+    fn font_face_required_descriptors() {
+        let face = font_face("@font-face { font-family: Open Sans; src: local(Arial); }").unwrap();
+        assert_eq!(face.font_family.name(), "Open Sans");
+        assert_eq!(
+            face.src,
+            vec![FontFaceSource::Local(FamilyName::Unquoted(vec![
+                CustomIdent("Arial".to_owned())
+            ]))]
+        );
+        assert_eq!(face.font_style, None);
+        assert!(face.covers('a'));
+
+        assert!(font_face("@font-face { font-family: a; }").is_none());
+        assert!(font_face("@font-face { src: local(a); }").is_none());
+        assert!(font_face("@font-face { font-family: serif; src: local(a); }").is_none());
+        assert!(font_face("@font-face a { font-family: a; src: local(a); }").is_none());
+        assert!(font_face("@font-face { font-family: a !important; src: local(a); }").is_none());
+        // Not allowed in style rules
+        assert!(matches!(
+            parse_stylesheet("a { @font-face { font-family: a; src: local(a); } }").as_slice(),
+            [Rule::Style(rule)] if rule.rules.is_empty()
+        ));
+    }
+
+    #[test]
+    fn font_face_src() {
+        let face = font_face(
+            "@font-face {
+                font-family: \"A\";
+                src: local(\"A Regular\"),
+                    url(a.woff2) format(woff2) tech(variations, color-COLRv1),
+                    url(\"a.ttf\") format(\"truetype\"),
+                    url(a.bad) format(unknown),
+                    url(a.bad) tech(unknown),
+                    nonsense;
+            }",
+        )
+        .unwrap();
+        assert_eq!(
+            face.src,
+            vec![
+                FontFaceSource::Local(FamilyName::Quoted(data_types::String(
+                    "A Regular".to_owned()
+                ))),
+                FontFaceSource::Url {
+                    url: Url("a.woff2".to_owned()),
+                    format: Some(FontFaceFormat::Keyword(FontFormat::Woff2)),
+                    tech: vec![FontTech::Variations, FontTech::ColorColrv1],
+                },
+                FontFaceSource::Url {
+                    url: Url("a.ttf".to_owned()),
+                    format: Some(FontFaceFormat::String(data_types::String(
+                        "truetype".to_owned()
+                    ))),
+                    tech: Vec::new(),
+                },
+            ]
+        );
+        assert!(font_face("@font-face { font-family: a; src: nonsense, url(a) x; }").is_none());
+    }
+
+    #[test]
+    fn font_face_descriptors() {
+        let face = font_face(
+            "@font-face {
+                font-family: a;
+                src: url(a);
+                font-style: oblique 10deg 20deg;
+                font-weight: 300 bold;
+                font-stretch: condensed 110%;
+                font-display: swap;
+                unicode-range: U+0000-00FF, U+00E9, U+4??;
+                font-feature-settings: \"liga\" off, \"swsh\" 2, \"smcp\";
+                font-variation-settings: \"wght\" 450;
+                ascent-override: 90%;
+                descent-override: normal;
+                size-adjust: 110%;
+                unknown-descriptor: 1;
+            }",
+        )
+        .unwrap();
+        assert_eq!(
+            face.font_style,
+            Some(FontFaceStyle::Oblique(Some((
+                Angle(10.0, AngleUnit::Deg),
+                Angle(20.0, AngleUnit::Deg)
+            ))))
+        );
+        assert_eq!(
+            face.font_weight.as_ref().unwrap().range(),
+            Some((300.0, 700.0))
+        );
+        assert_eq!(
+            face.font_stretch,
+            Some(FontFaceStretch::Range(
+                FontStretch::Keyword(FontStretchKeyword::Condensed),
+                FontStretch::Percentage(Percentage(Number(110.0)))
+            ))
+        );
+        assert_eq!(face.font_display, Some(FontDisplay::Swap));
+        assert!(face.covers('\u{e9}'));
+        assert!(face.covers('\u{4ff}'));
+        assert!(!face.covers('\u{100}'));
+        let features: Vec<_> = face
+            .font_feature_settings
+            .unwrap()
+            .0
+            .iter()
+            .map(|feature| feature.value)
+            .collect();
+        assert_eq!(features, vec![0, 2, 1]);
+        assert_eq!(
+            face.font_variation_settings.unwrap().0[0].value,
+            Number(450.0)
+        );
+        assert_eq!(
+            face.ascent_override,
+            Some(FontFaceMetricOverride::Percentage(Percentage(Number(90.0))))
+        );
+        assert_eq!(face.descent_override, Some(FontFaceMetricOverride::Normal));
+        assert_eq!(face.line_gap_override, None);
+        assert_eq!(face.size_adjust, Some(Percentage(Number(110.0))));
+    }
+
+    #[test]
+    fn invalid_font_face_descriptors() {
+        let face = font_face(
+            "@font-face {
+                font-family: a;
+                src: url(a);
+                font-weight: 100;
+                font-weight: 1001;
+                font-style: oblique 100deg;
+                font-stretch: -10%;
+                font-display: sometimes;
+                unicode-range: U+110000;
+                font-feature-settings: \"toolong\";
+                size-adjust: -1%;
+            }",
+        )
+        .unwrap();
+        // Invalid descriptors are dropped without replacing valid ones
+        assert_eq!(
+            face.font_weight.as_ref().unwrap().range(),
+            Some((100.0, 100.0))
+        );
+        assert_eq!(face.font_style, None);
+        assert_eq!(face.font_stretch, None);
+        assert_eq!(face.font_display, None);
+        assert!(face.unicode_range.is_empty());
+        assert_eq!(face.font_feature_settings, None);
+        assert_eq!(face.size_adjust, None);
+    }
+
+    fn declarations(input: &str) -> Vec<Declaration> {
+        match parse_stylesheet(input).as_slice() {
+            [Rule::Style(rule)] => rule.declarations.clone(),
+            rules => panic!("{:?}", rules),
+        }
+    }
+
+    fn unparsed(name: &str, value: &str, important: bool) -> Declaration {
+        Declaration {
+            value: DeclarationValue::Unparsed {
+                name: name.to_owned(),
+                value: value.to_owned(),
+            },
+            important,
+        }
+    }
+
+    #[test]
+    fn style_declarations() {
+        let declarations = declarations(
+            "a {
+                transition-duration: 1s;
+                animation-name: b !important;
+                display:grid;
+                color: rgb(0 0 0 / 50%) ! IMPORTANT;
+                --Custom-Name:  { anything } ;
+                -webkit-appearance: none;
+                transition-delay: var(--delay, 1s);
+                transition-duration: red;
+                not a declaration;
+            }",
+        );
+        assert!(matches!(
+            declarations[0],
+            Declaration {
+                value: DeclarationValue::Parsed(Property::TransitionDuration(_)),
+                important: false,
+            }
+        ));
+        assert!(matches!(
+            declarations[1],
+            Declaration {
+                value: DeclarationValue::Parsed(Property::AnimationName(_)),
+                important: true,
+            }
+        ));
+        assert_eq!(
+            declarations[2..],
+            [
+                unparsed("display", "grid", false),
+                unparsed("color", "rgb(0 0 0 / 50%)", true),
+                unparsed("--Custom-Name", "{ anything }", false),
+                unparsed("-webkit-appearance", "none", false),
+                unparsed("transition-delay", "var(--delay, 1s)", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_declarations() {
+        let rules = parse_stylesheet("a { @media print { display: none !important } }");
+        let media = match rules.as_slice() {
+            [Rule::Style(StyleRule { rules, .. })] => match rules.as_slice() {
+                [Rule::At(AtRule::Media(media))] => media.clone(),
+                rules => panic!("{:?}", rules),
+            },
+            rules => panic!("{:?}", rules),
+        };
+        match media.rules.as_slice() {
+            [Rule::Style(nested)] => {
+                assert_eq!(nested.declarations, vec![unparsed("display", "none", true)])
+            }
+            rules => panic!("{:?}", rules),
+        }
+    }
+
+    #[test]
+    fn unknown_at_rules() {
+        let unknown = |name: &str, prelude: &str, block: Option<&str>| {
+            Rule::At(AtRule::Unknown(UnknownAtRule {
+                name: name.to_owned(),
+                prelude: prelude.to_owned(),
+                block: block.map(str::to_owned),
+            }))
+        };
+        assert_eq!(
+            parse_stylesheet(
+                "@page :first { margin: 1in; }
+                @-webkit-keyframes spin { to { transform: rotate(1turn) } }
+                @unknown-statement a  b;
+                @container (min-width: 400px) { a { display: none } }"
+            ),
+            vec![
+                unknown("page", ":first", Some("margin: 1in;")),
+                unknown(
+                    "-webkit-keyframes",
+                    "spin",
+                    Some("to { transform: rotate(1turn) }")
+                ),
+                unknown("unknown-statement", "a b", None),
+                unknown(
+                    "container",
+                    "(min-width: 400px)",
+                    Some("a { display: none }")
+                ),
+            ]
+        );
+        // Known at-rules are dropped when they're invalid
+        assert!(parse_stylesheet("@media (min-width: 1px) ;").is_empty());
+        assert!(parse_stylesheet("@keyframes none {}").is_empty());
+    }
+}
@@ -0,0 +1,655 @@
+use super::{Ast, FromTokens, ParseError, Token};
+use crate::selectors::*;
+
+impl FromTokens for SelectorList {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        Ok(SelectorList(ast.parse_comma_separated(Ast::parse)?))
+    }
+}
+
+impl FromTokens for ComplexSelector {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let first = ast.parse()?;
+        let mut rest = Vec::new();
+        while let Some(combinator) = parse_combinator(ast)? {
+            rest.push((combinator, ast.parse()?));
+        }
+        Ok(ComplexSelector { first, rest })
+    }
+}
+
+impl Ast {
+    /// Parse the selectors of a nested style rule, which are relative to the
+    /// parent rule's selectors (like `> a` or `&:hover`)
+    ///
+    /// https://drafts.csswg.org/css-nesting-1/#syntax
+    pub fn parse_nested_selector_list(&mut self) -> Result<SelectorList, ParseError> {
+        let selectors = self.parse_comma_separated(Ast::parse::<RelativeSelector>)?;
+        Ok(SelectorList(
+            selectors.iter().map(RelativeSelector::to_nested).collect(),
+        ))
+    }
+}
+
+/// Consume a combinator (and any whitespace around it), or return `None` at
+/// the end of the selector
+fn parse_combinator(ast: &mut Ast) -> Result<Option<Combinator>, ParseError> {
+    let mut whitespace = false;
+    while ast.peek_including_whitespace() == Some(&Token::Whitespace) {
+        ast.next_token_including_whitespace()?;
+        whitespace = true;
+    }
+    let column = ast.try_parse(|ast| {
+        for _ in 0..2 {
+            match ast.next_token_including_whitespace()? {
+                Token::Delim('|') => {}
+                token => return Err(ParseError::UnexpectedToken(token)),
+            }
+        }
+        Ok(())
+    });
+    if column.is_ok() {
+        return Ok(Some(Combinator::Column));
+    }
+    let combinator = match ast.peek_including_whitespace() {
+        Some(Token::Delim('>')) => Combinator::Child,
+        Some(Token::Delim('+')) => Combinator::NextSibling,
+        Some(Token::Delim('~')) => Combinator::SubsequentSibling,
+        Some(token) if whitespace && starts_compound(token) => {
+            return Ok(Some(Combinator::Descendant))
+        }
+        _ => return Ok(None),
+    };
+    ast.next_token()?;
+    Ok(Some(combinator))
+}
+
+fn starts_compound(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Ident(_)
+            | Token::Hash { is_id: true, .. }
+            | Token::Delim('*')
+            | Token::Delim('|')
+            | Token::Delim('.')
+            | Token::Delim('&')
+            | Token::OpenSquare
+            | Token::Colon
+    )
+}
+
+impl FromTokens for CompoundSelector {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        ast.skip_whitespace();
+        let mut compound = CompoundSelector {
+            type_selector: ast.try_parse(parse_type_selector).ok(),
+            ..CompoundSelector::default()
+        };
+
+        // Subclass selectors must come before pseudo-elements, which can only
+        // be followed by pseudo-classes
+        while let Some(token) = ast.peek_including_whitespace().cloned() {
+            let in_pseudo_element = !compound.pseudo_elements.is_empty();
+            match token {
+                Token::Hash { value, is_id: true } if !in_pseudo_element => {
+                    ast.next_token_including_whitespace()?;
+                    compound.subclasses.push(SubclassSelector::Id(value));
+                }
+                Token::Delim('.') if !in_pseudo_element => {
+                    ast.next_token_including_whitespace()?;
+                    match ast.next_token_including_whitespace()? {
+                        Token::Ident(class) => {
+                            compound.subclasses.push(SubclassSelector::Class(class))
+                        }
+                        token => return Err(ParseError::UnexpectedToken(token)),
+                    }
+                }
+                Token::Delim('&') if !in_pseudo_element => {
+                    ast.next_token_including_whitespace()?;
+                    compound.subclasses.push(SubclassSelector::Nesting);
+                }
+                Token::OpenSquare if !in_pseudo_element => {
+                    ast.next_token_including_whitespace()?;
+                    let attribute = ast.parse_nested_block(parse_attribute)?;
+                    compound
+                        .subclasses
+                        .push(SubclassSelector::Attribute(attribute));
+                }
+                Token::Colon => {
+                    ast.next_token_including_whitespace()?;
+                    let is_element = ast.peek_including_whitespace() == Some(&Token::Colon);
+                    if is_element {
+                        ast.next_token_including_whitespace()?;
+                    }
+                    let name = ast.next_token_including_whitespace()?;
+                    if !is_element {
+                        if let Some(element) = legacy_pseudo_element(&name) {
+                            compound.pseudo_elements.push(PseudoElementSelector {
+                                element,
+                                classes: Vec::new(),
+                            });
+                            continue;
+                        }
+                    }
+                    if is_element {
+                        let element = parse_pseudo_element(ast, name)?;
+                        compound.pseudo_elements.push(PseudoElementSelector {
+                            element,
+                            classes: Vec::new(),
+                        });
+                    } else {
+                        let class = parse_pseudo_class(ast, name)?;
+                        match compound.pseudo_elements.last_mut() {
+                            Some(pseudo_element) => pseudo_element.classes.push(class),
+                            None => compound
+                                .subclasses
+                                .push(SubclassSelector::PseudoClass(class)),
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        if compound == CompoundSelector::default() {
+            return Err(match ast.peek_including_whitespace() {
+                Some(token) => ParseError::UnexpectedToken(token.clone()),
+                None => ParseError::UnexpectedEnd,
+            });
+        }
+        Ok(compound)
+    }
+}
+
+/// An element name, or `None` for `*`
+fn parse_type_name(ast: &mut Ast) -> Result<Option<String>, ParseError> {
+    match ast.next_token_including_whitespace()? {
+        Token::Ident(name) => Ok(Some(name)),
+        Token::Delim('*') => Ok(None),
+        token => Err(ParseError::UnexpectedToken(token)),
+    }
+}
+
+fn parse_type_selector(ast: &mut Ast) -> Result<TypeSelector, ParseError> {
+    if ast.peek_including_whitespace() == Some(&Token::Delim('|')) {
+        ast.next_token_including_whitespace()?;
+        return Ok(TypeSelector {
+            namespace: Some(NamespacePrefix::None),
+            name: parse_type_name(ast)?,
+        });
+    }
+    let name = parse_type_name(ast)?;
+    let local_name = ast.try_parse(|ast| {
+        match ast.next_token_including_whitespace()? {
+            Token::Delim('|') => {}
+            token => return Err(ParseError::UnexpectedToken(token)),
+        }
+        parse_type_name(ast)
+    });
+    Ok(match local_name {
+        Ok(local_name) => TypeSelector {
+            namespace: Some(match name {
+                Some(prefix) => NamespacePrefix::Named(prefix),
+                None => NamespacePrefix::Any,
+            }),
+            name: local_name,
+        },
+        Err(_) => TypeSelector {
+            namespace: None,
+            name,
+        },
+    })
+}
+
+/// The contents of `[...]`
+fn parse_attribute(ast: &mut Ast) -> Result<AttributeSelector, ParseError> {
+    let expect_ident = |ast: &mut Ast| match ast.next_token_including_whitespace()? {
+        Token::Ident(name) => Ok(name),
+        token => Err(ParseError::UnexpectedToken(token)),
+    };
+    let expect_bar = |ast: &mut Ast| match ast.next_token_including_whitespace()? {
+        Token::Delim('|') => Ok(()),
+        token => Err(ParseError::UnexpectedToken(token)),
+    };
+
+    let (namespace, name) = match ast.next_token()? {
+        Token::Delim('*') => {
+            expect_bar(ast)?;
+            (Some(NamespacePrefix::Any), expect_ident(ast)?)
+        }
+        Token::Delim('|') => (Some(NamespacePrefix::None), expect_ident(ast)?),
+        Token::Ident(name) => match ast.try_parse(|ast| {
+            expect_bar(ast)?;
+            expect_ident(ast)
+        }) {
+            Ok(local_name) => (Some(NamespacePrefix::Named(name)), local_name),
+            Err(_) => (None, name),
+        },
+        token => return Err(ParseError::UnexpectedToken(token)),
+    };
+    if ast.is_exhausted() {
+        return Ok(AttributeSelector {
+            namespace,
+            name,
+            matcher: None,
+        });
+    }
+
+    let operator = match ast.next_token()? {
+        Token::Delim('=') => AttributeOperator::Equals,
+        Token::Delim(c @ ('~' | '|' | '^' | '$' | '*')) => {
+            match ast.next_token_including_whitespace()? {
+                Token::Delim('=') => {}
+                token => return Err(ParseError::UnexpectedToken(token)),
+            }
+            match c {
+                '~' => AttributeOperator::Includes,
+                '|' => AttributeOperator::DashMatch,
+                '^' => AttributeOperator::Prefix,
+                '$' => AttributeOperator::Suffix,
+                _ => AttributeOperator::Substring,
+            }
+        }
+        token => return Err(ParseError::UnexpectedToken(token)),
+    };
+    let value = match ast.next_token()? {
+        Token::Ident(value) | Token::String(value) => value,
+        token => return Err(ParseError::UnexpectedToken(token)),
+    };
+    let case_sensitivity = if ast.is_exhausted() {
+        None
+    } else {
+        Some(ast.expect_keyword(&[
+            ("i", AttributeCaseSensitivity::Insensitive),
+            ("s", AttributeCaseSensitivity::Sensitive),
+        ])?)
+    };
+    Ok(AttributeSelector {
+        namespace,
+        name,
+        matcher: Some(AttributeMatcher {
+            operator,
+            value,
+            case_sensitivity,
+        }),
+    })
+}
+
+/// Pseudo-elements that can be written with a single colon, like `:before`
+fn legacy_pseudo_element(name: &Token) -> Option<PseudoElement> {
+    match name {
+        Token::Ident(name) => name.parse().ok().filter(PseudoElement::is_legacy),
+        _ => None,
+    }
+}
+
+/// Parse a pseudo-class after its `:`, where `name` is the ident or function
+/// token
+fn parse_pseudo_class(ast: &mut Ast, name: Token) -> Result<PseudoClass, ParseError> {
+    let function = match &name {
+        Token::Ident(ident) if ident.eq_ignore_ascii_case("host") => {
+            return Ok(PseudoClass::Host(None))
+        }
+        Token::Ident(ident) => {
+            return ident
+                .parse()
+                .map_err(|_| ParseError::UnexpectedToken(name.clone()))
+        }
+        Token::Function(function) => function.to_ascii_lowercase(),
+        _ => return Err(ParseError::UnexpectedToken(name)),
+    };
+    ast.parse_nested_block(|ast| {
+        Ok(match function.as_str() {
+            "dir" => PseudoClass::Dir(
+                ast.expect_keyword(&[("ltr", TextDirection::Ltr), ("rtl", TextDirection::Rtl)])?,
+            ),
+            "has" => PseudoClass::Has(ast.parse_comma_separated(Ast::parse)?),
+            "host" => PseudoClass::Host(Some(Box::new(ast.parse()?))),
+            "host-context" => PseudoClass::HostContext(Box::new(ast.parse()?)),
+            "is" => PseudoClass::Is(parse_forgiving_selector_list(ast)?),
+            "lang" => {
+                PseudoClass::Lang(ast.parse_comma_separated(|ast| match ast.next_token()? {
+                    Token::Ident(range) | Token::String(range) => Ok(range),
+                    token => Err(ParseError::UnexpectedToken(token)),
+                })?)
+            }
+            "not" => PseudoClass::Not(ast.parse()?),
+            "nth-child" => PseudoClass::NthChild {
+                nth: ast.parse()?,
+                of: parse_nth_of(ast)?,
+            },
+            "nth-last-child" => PseudoClass::NthLastChild {
+                nth: ast.parse()?,
+                of: parse_nth_of(ast)?,
+            },
+            "nth-last-of-type" => PseudoClass::NthLastOfType(ast.parse()?),
+            "nth-of-type" => PseudoClass::NthOfType(ast.parse()?),
+            "where" => PseudoClass::Where(parse_forgiving_selector_list(ast)?),
+            _ => return Err(ParseError::UnexpectedToken(name.clone())),
+        })
+    })
+}
+
+/// A selector list where invalid selectors are dropped instead of
+/// invalidating the whole list
+///
+/// https://drafts.csswg.org/selectors-4/#forgiving-selector
+fn parse_forgiving_selector_list(ast: &mut Ast) -> Result<SelectorList, ParseError> {
+    let selectors = ast.parse_comma_separated(|ast| {
+        let selector = ast
+            .try_parse(|ast| {
+                let selector = ast.parse::<ComplexSelector>()?;
+                ast.expect_exhausted()?;
+                Ok(selector)
+            })
+            .ok();
+        ast.position = ast.tokens.len();
+        Ok(selector)
+    })?;
+    Ok(SelectorList(selectors.into_iter().flatten().collect()))
+}
+
+/// The optional `of S` after the `An+B` of `:nth-child()`
+fn parse_nth_of(ast: &mut Ast) -> Result<Option<SelectorList>, ParseError> {
+    if ast.is_exhausted() {
+        return Ok(None);
+    }
+    ast.expect_ident_matching("of")?;
+    Ok(Some(ast.parse()?))
+}
+
+impl FromTokens for RelativeSelector {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        ast.skip_whitespace();
+        let combinator = match ast.peek_including_whitespace() {
+            Some(Token::Delim('>' | '+' | '~')) => parse_combinator(ast)?,
+            Some(Token::Delim('|')) => ast
+                .try_parse(|ast| match parse_combinator(ast)? {
+                    Some(Combinator::Column) => Ok(Combinator::Column),
+                    _ => Err(ParseError::InvalidValue),
+                })
+                .ok(),
+            _ => None,
+        };
+        Ok(RelativeSelector {
+            combinator: combinator.unwrap_or(Combinator::Descendant),
+            selector: ast.parse()?,
+        })
+    }
+}
+
+/// What follows the `n` in an `An+B` identifier or dimension unit
+enum NTail {
+    /// Nothing, so `B` can follow as separate tokens
+    None,
+    /// `n-`, which must be followed by a signless integer
+    Dash,
+    /// `n-B`, like in `2n-1`
+    B(i32),
+}
+
+/// Split an `An+B` identifier or unit like `-n-1` into the sign of `A` and
+/// what follows the `n`
+fn split_n(ident: &str) -> Option<(i32, NTail)> {
+    let ident = ident.to_ascii_lowercase();
+    let (sign, rest) = match ident.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, ident.as_str()),
+    };
+    let tail = match rest.strip_prefix('n')? {
+        "" => NTail::None,
+        "-" => NTail::Dash,
+        digits if digits.len() > 1 && digits[1..].bytes().all(|b| b.is_ascii_digit()) => {
+            // Saturating, like the integers of number tokens
+            NTail::B(digits.parse::<f64>().ok()? as i32)
+        }
+        _ => return None,
+    };
+    Some((sign, tail))
+}
+
+/// https://drafts.csswg.org/css-syntax-3/#anb-microsyntax
+impl FromTokens for AnPlusB {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        let token = ast.next_token()?;
+        let (a, tail) = match &token {
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("odd") => return Ok(AnPlusB::ODD),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("even") => return Ok(AnPlusB::EVEN),
+            Token::Number {
+                value,
+                is_integer: true,
+                ..
+            } => {
+                return Ok(AnPlusB {
+                    a: 0,
+                    b: *value as i32,
+                })
+            }
+            Token::Dimension {
+                value,
+                is_integer: true,
+                unit,
+                ..
+            } => match split_n(unit) {
+                Some((1, tail)) => (*value as i32, tail),
+                _ => return Err(ParseError::UnexpectedToken(token)),
+            },
+            Token::Ident(ident) => split_n(ident).ok_or(ParseError::UnexpectedToken(token))?,
+            // `+n`, where the `+` can't be followed by whitespace
+            Token::Delim('+') => match ast.next_token_including_whitespace()? {
+                Token::Ident(ident) if !ident.starts_with('-') => {
+                    split_n(&ident).ok_or(ParseError::UnexpectedToken(Token::Ident(ident)))?
+                }
+                token => return Err(ParseError::UnexpectedToken(token)),
+            },
+            _ => return Err(ParseError::UnexpectedToken(token)),
+        };
+
+        let signless_integer = |ast: &mut Ast| match ast.next_token()? {
+            Token::Number {
+                value,
+                is_integer: true,
+                has_sign: false,
+                ..
+            } => Ok(value as i32),
+            token => Err(ParseError::UnexpectedToken(token)),
+        };
+        let b = match tail {
+            NTail::B(b) => b,
+            NTail::Dash => -signless_integer(ast)?,
+            NTail::None => match ast.peek() {
+                Some(Token::Number {
+                    value,
+                    is_integer: true,
+                    has_sign: true,
+                    ..
+                }) => {
+                    let b = *value as i32;
+                    ast.next_token()?;
+                    b
+                }
+                Some(Token::Delim(sign @ ('+' | '-'))) => {
+                    let sign = if *sign == '-' { -1 } else { 1 };
+                    ast.next_token()?;
+                    sign * signless_integer(ast)?
+                }
+                _ => 0,
+            },
+        };
+        Ok(AnPlusB { a, b })
+    }
+}
+
+/// Parse a pseudo-element after its `::`, where `name` is the ident or
+/// function token
+fn parse_pseudo_element(ast: &mut Ast, name: Token) -> Result<PseudoElement, ParseError> {
+    match &name {
+        Token::Ident(ident) => ident
+            .parse()
+            .map_err(|_| ParseError::UnexpectedToken(name.clone())),
+        Token::Function(function) if function.eq_ignore_ascii_case("highlight") => {
+            ast.parse_nested_block(|ast| Ok(PseudoElement::Highlight(ast.expect_ident()?)))
+        }
+        Token::Function(function) if function.eq_ignore_ascii_case("part") => ast
+            .parse_nested_block(|ast| {
+                let mut names = vec![ast.expect_ident()?];
+                while !ast.is_exhausted() {
+                    names.push(ast.expect_ident()?);
+                }
+                Ok(PseudoElement::Part(names))
+            }),
+        Token::Function(function) if function.eq_ignore_ascii_case("slotted") => {
+            ast.parse_nested_block(|ast| Ok(PseudoElement::Slotted(Box::new(ast.parse()?))))
+        }
+        _ => Err(ParseError::UnexpectedToken(name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn an_plus_b(input: &str) -> Option<(i32, i32)> {
+        parse::<AnPlusB>(input).ok().map(|AnPlusB { a, b }| (a, b))
+    }
+
+    #[test]
+    fn an_plus_b_syntax() {
+        assert_eq!(an_plus_b("odd"), Some((2, 1)));
+        assert_eq!(an_plus_b("EVEN"), Some((2, 0)));
+        assert_eq!(an_plus_b("5"), Some((0, 5)));
+        assert_eq!(an_plus_b("-5"), Some((0, -5)));
+        assert_eq!(an_plus_b("n"), Some((1, 0)));
+        assert_eq!(an_plus_b("+n"), Some((1, 0)));
+        assert_eq!(an_plus_b("-n+3"), Some((-1, 3)));
+        assert_eq!(an_plus_b("2n+1"), Some((2, 1)));
+        assert_eq!(an_plus_b("2n + 1"), Some((2, 1)));
+        assert_eq!(an_plus_b("2n- 1"), Some((2, -1)));
+        assert_eq!(an_plus_b("2n -1"), Some((2, -1)));
+        assert_eq!(an_plus_b("3n-2"), Some((3, -2)));
+        assert_eq!(an_plus_b("-n-1"), Some((-1, -1)));
+        assert_eq!(an_plus_b("+ n"), None);
+        assert_eq!(an_plus_b("2n + -1"), None);
+        assert_eq!(an_plus_b("1.5n"), None);
+        assert_eq!(an_plus_b("2m"), None);
+    }
+
+    #[test]
+    fn an_plus_b_saturates() {
+        assert_eq!(an_plus_b("-99999999999"), Some((0, i32::MIN)));
+        assert_eq!(an_plus_b("n-99999999999"), Some((1, i32::MIN)));
+        assert_eq!(an_plus_b("99999999999n"), Some((i32::MAX, 0)));
+    }
+
+    fn round_trip(input: &str) -> Result<String, ParseError> {
+        parse::<SelectorList>(input).map(|selectors| selectors.to_string())
+    }
+
+    #[test]
+    fn attribute_selectors() {
+        assert_eq!(round_trip("[href]").unwrap(), "[href]");
+        assert_eq!(round_trip("[ lang |= en ]").unwrap(), "[lang|=\"en\"]");
+        assert_eq!(round_trip("[type='a' i]").unwrap(), "[type=\"a\" i]");
+        assert_eq!(round_trip("[type=a S]").unwrap(), "[type=\"a\" s]");
+        assert_eq!(
+            round_trip("[class~=x],[a^=b],[a$=b],[a*=b]").unwrap(),
+            "[class~=\"x\"], [a^=\"b\"], [a$=\"b\"], [a*=\"b\"]"
+        );
+        let attribute = match &parse::<CompoundSelector>("[type=a i]").unwrap().subclasses[..] {
+            [SubclassSelector::Attribute(attribute)] => attribute.clone(),
+            subclasses => panic!("{:?}", subclasses),
+        };
+        assert_eq!(
+            attribute.matcher.unwrap().case_sensitivity,
+            Some(AttributeCaseSensitivity::Insensitive)
+        );
+
+        assert!(round_trip("[type=a x]").is_err());
+        assert!(round_trip("[type=a i i]").is_err());
+        assert!(round_trip("[type=]").is_err());
+        assert!(round_trip("[type = = a]").is_err());
+        assert!(round_trip("[type ~ = a]").is_err());
+        assert!(round_trip("[]").is_err());
+    }
+
+    #[test]
+    fn namespaced_selectors() {
+        assert_eq!(round_trip("svg|a").unwrap(), "svg|a");
+        assert_eq!(round_trip("*|a").unwrap(), "*|a");
+        assert_eq!(round_trip("|a").unwrap(), "|a");
+        assert_eq!(round_trip("*|*").unwrap(), "*|*");
+        assert_eq!(round_trip("svg|*.x").unwrap(), "svg|*.x");
+        assert_eq!(
+            parse::<CompoundSelector>("|a").unwrap().type_selector,
+            Some(TypeSelector {
+                namespace: Some(NamespacePrefix::None),
+                name: Some("a".to_owned()),
+            })
+        );
+        assert_eq!(
+            round_trip("[xlink|href], [*|href], [|href]").unwrap(),
+            "[xlink|href], [*|href], [|href]"
+        );
+        assert!(round_trip("svg|").is_err());
+        assert!(round_trip("svg| a").is_err());
+        assert!(round_trip("[*href]").is_err());
+    }
+
+    #[test]
+    fn combinators() {
+        assert_eq!(round_trip("a>b+c~d   e").unwrap(), "a > b + c ~ d e");
+        assert_eq!(
+            round_trip("col.selected||td").unwrap(),
+            "col.selected || td"
+        );
+        assert_eq!(round_trip("a || b").unwrap(), "a || b");
+        let selector = parse::<ComplexSelector>("a || b").unwrap();
+        assert_eq!(selector.rest[0].0, Combinator::Column);
+        // A namespace bar isn't a column combinator
+        assert_eq!(round_trip("a |b").unwrap(), "a |b");
+        assert!(round_trip("a | | b").is_err());
+    }
+
+    #[test]
+    fn pseudo_elements() {
+        // Legacy pseudo-elements can be written with a single colon
+        assert_eq!(round_trip("p:before").unwrap(), "p::before");
+        assert_eq!(round_trip("p:First-Line").unwrap(), "p::first-line");
+        assert!(round_trip("p:marker").is_err());
+        assert_eq!(round_trip("p::marker").unwrap(), "p::marker");
+
+        // Pseudo-classes after a pseudo-element apply to it
+        let compound = parse::<CompoundSelector>("a:focus::before:hover").unwrap();
+        assert_eq!(compound.subclasses.len(), 1);
+        assert_eq!(compound.pseudo_elements[0].classes.len(), 1);
+        assert_eq!(compound.to_string(), "a:focus::before:hover");
+        assert_eq!(
+            round_trip("::part(a b):active").unwrap(),
+            "::part(a b):active"
+        );
+        assert!(round_trip("a::before.x").is_err());
+        assert!(round_trip("a::before#x").is_err());
+        assert!(round_trip("a::before[x]").is_err());
+        assert!(round_trip("a::before span").is_ok());
+    }
+
+    #[test]
+    fn invalid_selector_lists() {
+        assert!(round_trip("").is_err());
+        assert!(round_trip("a >").is_err());
+        assert!(round_trip("a > > b").is_err());
+        assert!(round_trip("a +").is_err());
+        assert!(round_trip("> a").is_err());
+        assert!(round_trip("a,").is_err());
+        assert!(round_trip(", a").is_err());
+        assert!(round_trip("a,,b").is_err());
+        assert!(round_trip("a, , b").is_err());
+        assert!(round_trip(".").is_err());
+        assert!(round_trip("a:unknown").is_err());
+        assert!(round_trip("a::unknown").is_err());
+        // But invalid selectors are dropped from forgiving lists
+        assert_eq!(round_trip(":is(a, >, b)").unwrap(), ":is(a, b)");
+        assert!(round_trip(":not(a, >)").is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use super::{rules::split_important, Ast, FromTokens, ParseError, Token};
+use crate::supports::SupportsCondition;
+
+/// `<supports-condition>`, where `and` and `or` can't be mixed without
+/// parentheses
+impl FromTokens for SupportsCondition {
+    fn from_tokens(ast: &mut Ast) -> Result<Self, ParseError> {
+        if ast
+            .try_parse(|ast| ast.expect_ident_matching("not"))
+            .is_ok()
+        {
+            return Ok(SupportsCondition::Not(Box::new(parse_in_parens(ast)?)));
+        }
+        let first = parse_in_parens(ast)?;
+        let keyword = match ast.peek() {
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("and") => "and",
+            Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case("or") => "or",
+            _ => return Ok(first),
+        };
+        let mut conditions = vec![first];
+        while ast
+            .try_parse(|ast| ast.expect_ident_matching(keyword))
+            .is_ok()
+        {
+            conditions.push(parse_in_parens(ast)?);
+        }
+        Ok(match keyword {
+            "and" => SupportsCondition::And(conditions),
+            _ => SupportsCondition::Or(conditions),
+        })
+    }
+}
+
+/// `<supports-in-parens>` or `<supports-feature>`, where anything in
+/// parentheses (or a function) that doesn't match the grammar is unknown
+/// rather than invalid
+fn parse_in_parens(ast: &mut Ast) -> Result<SupportsCondition, ParseError> {
+    match ast.next_token()? {
+        Token::OpenParen => ast.parse_nested_block(|ast| {
+            if let Ok(condition) = ast.try_parse(|ast| {
+                let condition = ast.parse()?;
+                ast.expect_exhausted()?;
+                Ok(condition)
+            }) {
+                return Ok(condition);
+            }
+            if let Ok(declaration) = ast.try_parse(parse_declaration) {
+                return Ok(declaration);
+            }
+            ast.position = ast.tokens.len();
+            Ok(SupportsCondition::GeneralEnclosed)
+        }),
+        Token::Function(name) => ast.parse_nested_block(|ast| {
+            let feature = ast.try_parse(|ast| {
+                let feature = match name.to_ascii_lowercase().as_str() {
+                    "selector" => SupportsCondition::Selector(ast.parse()?),
+                    "font-tech" => SupportsCondition::FontTech(parse_keyword(ast)?),
+                    "font-format" => SupportsCondition::FontFormat(parse_keyword(ast)?),
+                    _ => return Err(ParseError::UnexpectedToken(Token::Function(name))),
+                };
+                ast.expect_exhausted()?;
+                Ok(feature)
+            });
+            ast.position = ast.tokens.len();
+            Ok(feature.unwrap_or(SupportsCondition::GeneralEnclosed))
+        }),
+        token => Err(ParseError::UnexpectedToken(token)),
+    }
+}
+
+/// The contents of `(property: value)`, with the value serialized as written
+pub(super) fn parse_declaration(ast: &mut Ast) -> Result<SupportsCondition, ParseError> {
+    let property = ast.expect_ident()?;
+    ast.expect_token(&Token::Colon)?;
+    ast.skip_whitespace();
+    // `!important` doesn't change whether a declaration is supported
+    let (value, _) = split_important(&ast.tokens[ast.position..]);
+    let value = value.iter().map(Token::to_string).collect();
+    ast.position = ast.tokens.len();
+    Ok(SupportsCondition::Declaration { property, value })
+}
+
+/// An identifier parsed (ignoring ASCII case) as a `T`
+pub(super) fn parse_keyword<T: std::str::FromStr>(ast: &mut Ast) -> Result<T, ParseError> {
+    let ident = ast.expect_ident()?;
+    ident
+        .parse()
+        .map_err(|_| ParseError::UnexpectedToken(Token::Ident(ident)))
+}
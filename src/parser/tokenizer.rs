@@ -0,0 +1,538 @@
+//! Tokenization as described in https://drafts.csswg.org/css-syntax-3/#tokenization
+
+use std::{
+    fmt::{self, Write},
+    string::String as StdString,
+};
+
+use crate::selectors::{write_identifier, write_string};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Ident(StdString),
+    /// The name of a function, whose arguments follow until the matching
+    /// `CloseParen`
+    Function(StdString),
+    AtKeyword(StdString),
+    Hash {
+        value: StdString,
+        /// Whether the value would be a valid identifier (and so can be an id
+        /// selector)
+        is_id: bool,
+    },
+    String(StdString),
+    BadString,
+    Url(StdString),
+    BadUrl,
+    Delim(char),
+    Number {
+        value: f32,
+        is_integer: bool,
+        /// Whether the number was written with an explicit `+` or `-`
+        has_sign: bool,
+        /// The number as written, which `unicode-range` needs since hex digits
+        /// like `00E9` would otherwise be read as an exponent
+        representation: StdString,
+    },
+    Percentage(f32),
+    Dimension {
+        value: f32,
+        is_integer: bool,
+        has_sign: bool,
+        /// The number (without the unit) as written
+        representation: StdString,
+        unit: StdString,
+    },
+    Whitespace,
+    /// `<!--`
+    Cdo,
+    /// `-->`
+    Cdc,
+    Colon,
+    Semicolon,
+    Comma,
+    OpenSquare,
+    CloseSquare,
+    OpenParen,
+    CloseParen,
+    OpenCurly,
+    CloseCurly,
+}
+
+impl Token {
+    /// Whether this token opens a block (a function's arguments, or a
+    /// parenthesized, square or curly bracketed block)
+    pub fn is_block_start(&self) -> bool {
+        matches!(
+            self,
+            Token::Function(_) | Token::OpenParen | Token::OpenSquare | Token::OpenCurly
+        )
+    }
+
+    pub fn is_block_end(&self) -> bool {
+        matches!(
+            self,
+            Token::CloseParen | Token::CloseSquare | Token::CloseCurly
+        )
+    }
+}
+
+/// Serialize the token so that tokenizing the result gives the same token
+/// back, which for a sequence needs the [`Token::Whitespace`] tokens between
+/// them to be kept
+///
+/// https://drafts.csswg.org/css-syntax-3/#serialization
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(name) => write_identifier(f, name),
+            Token::Function(name) => {
+                write_identifier(f, name)?;
+                f.write_char('(')
+            }
+            Token::AtKeyword(name) => {
+                f.write_char('@')?;
+                write_identifier(f, name)
+            }
+            Token::Hash { value, is_id } => {
+                f.write_char('#')?;
+                match is_id {
+                    true => write_identifier(f, value),
+                    false => f.write_str(value),
+                }
+            }
+            Token::String(string) => write_string(f, string),
+            Token::BadString => f.write_str("\"\n"),
+            Token::Url(url) => {
+                f.write_str("url(")?;
+                for c in url.chars() {
+                    match c {
+                        '"' | '\'' | '(' | ')' | '\\' => write!(f, "\\{}", c)?,
+                        c if is_whitespace(c) || is_non_printable(c) => {
+                            write!(f, "\\{:x} ", c as u32)?
+                        }
+                        c => f.write_char(c)?,
+                    }
+                }
+                f.write_char(')')
+            }
+            Token::BadUrl => f.write_str("url(\\\n)"),
+            // A backslash is only a delimiter when it isn't an escape
+            Token::Delim('\\') => f.write_str("\\\n"),
+            Token::Delim(c) => f.write_char(*c),
+            Token::Number {
+                value,
+                is_integer,
+                has_sign,
+                ..
+            } => write_number(f, *value, *is_integer, *has_sign),
+            Token::Percentage(value) => {
+                write!(f, "{}", value)?;
+                f.write_char('%')
+            }
+            Token::Dimension {
+                value,
+                is_integer,
+                has_sign,
+                unit,
+                ..
+            } => {
+                write_number(f, *value, *is_integer, *has_sign)?;
+                write_identifier(f, unit)
+            }
+            Token::Whitespace => f.write_char(' '),
+            Token::Cdo => f.write_str("<!--"),
+            Token::Cdc => f.write_str("-->"),
+            Token::Colon => f.write_char(':'),
+            Token::Semicolon => f.write_char(';'),
+            Token::Comma => f.write_char(','),
+            Token::OpenSquare => f.write_char('['),
+            Token::CloseSquare => f.write_char(']'),
+            Token::OpenParen => f.write_char('('),
+            Token::CloseParen => f.write_char(')'),
+            Token::OpenCurly => f.write_char('{'),
+            Token::CloseCurly => f.write_char('}'),
+        }
+    }
+}
+
+fn write_number(
+    f: &mut fmt::Formatter<'_>,
+    value: f32,
+    is_integer: bool,
+    has_sign: bool,
+) -> fmt::Result {
+    if has_sign && value.is_sign_positive() {
+        f.write_char('+')?;
+    }
+    write!(f, "{}", value)?;
+    // Keep numbers like `1.0` (or `1e3`) from becoming integers
+    if !is_integer && value.fract() == 0.0 {
+        f.write_str(".0")?;
+    }
+    Ok(())
+}
+
+/// Split `input` into tokens, dropping comments
+pub fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokenizer = Tokenizer::new(input);
+    let mut tokens = Vec::new();
+    while let Some(token) = tokenizer.next_token() {
+        tokens.push(token);
+    }
+    tokens
+}
+
+struct Tokenizer {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Tokenizer {
+    fn new(input: &str) -> Self {
+        // https://drafts.csswg.org/css-syntax-3/#input-preprocessing
+        let input = input
+            .replace("\r\n", "\n")
+            .replace(['\r', '\u{c}'], "\n")
+            .replace('\0', "\u{fffd}");
+        Self {
+            chars: input.chars().collect(),
+            position: 0,
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.peek(0);
+        self.position += 1;
+        c
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        self.consume_comments();
+        let c = self.peek(0)?;
+        let token = match c {
+            c if is_whitespace(c) => {
+                while self.peek(0).is_some_and(is_whitespace) {
+                    self.position += 1;
+                }
+                Token::Whitespace
+            }
+            '"' | '\'' => {
+                self.position += 1;
+                self.consume_string(c)
+            }
+            '#' if self.peek(1).is_some_and(is_name) || self.is_valid_escape(1) => {
+                self.position += 1;
+                let is_id = self.starts_ident(0);
+                Token::Hash {
+                    value: self.consume_name(),
+                    is_id,
+                }
+            }
+            '+' | '-' | '.' if self.starts_number(0) => self.consume_numeric(),
+            '-' if self.peek(1) == Some('-') && self.peek(2) == Some('>') => {
+                self.position += 3;
+                Token::Cdc
+            }
+            '-' if self.starts_ident(0) => self.consume_ident_like(),
+            '<' if self.peek(1) == Some('!')
+                && self.peek(2) == Some('-')
+                && self.peek(3) == Some('-') =>
+            {
+                self.position += 4;
+                Token::Cdo
+            }
+            '@' if self.starts_ident(1) => {
+                self.position += 1;
+                Token::AtKeyword(self.consume_name())
+            }
+            '\\' if self.is_valid_escape(0) => self.consume_ident_like(),
+            c if c.is_ascii_digit() => self.consume_numeric(),
+            c if is_name_start(c) => self.consume_ident_like(),
+            c => {
+                self.position += 1;
+                match c {
+                    '(' => Token::OpenParen,
+                    ')' => Token::CloseParen,
+                    '[' => Token::OpenSquare,
+                    ']' => Token::CloseSquare,
+                    '{' => Token::OpenCurly,
+                    '}' => Token::CloseCurly,
+                    ',' => Token::Comma,
+                    ':' => Token::Colon,
+                    ';' => Token::Semicolon,
+                    c => Token::Delim(c),
+                }
+            }
+        };
+        Some(token)
+    }
+
+    fn consume_comments(&mut self) {
+        while self.peek(0) == Some('/') && self.peek(1) == Some('*') {
+            self.position += 2;
+            while self.position < self.chars.len() {
+                if self.peek(0) == Some('*') && self.peek(1) == Some('/') {
+                    self.position += 2;
+                    break;
+                }
+                self.position += 1;
+            }
+        }
+    }
+
+    /// https://drafts.csswg.org/css-syntax-3/#starts-with-a-valid-escape
+    fn is_valid_escape(&self, offset: usize) -> bool {
+        self.peek(offset) == Some('\\') && self.peek(offset + 1).is_some_and(|c| c != '\n')
+    }
+
+    /// https://drafts.csswg.org/css-syntax-3/#would-start-an-identifier
+    fn starts_ident(&self, offset: usize) -> bool {
+        match self.peek(offset) {
+            Some('-') => {
+                self.peek(offset + 1)
+                    .is_some_and(|c| is_name_start(c) || c == '-')
+                    || self.is_valid_escape(offset + 1)
+            }
+            Some('\\') => self.is_valid_escape(offset),
+            Some(c) => is_name_start(c),
+            None => false,
+        }
+    }
+
+    /// https://drafts.csswg.org/css-syntax-3/#starts-with-a-number
+    fn starts_number(&self, offset: usize) -> bool {
+        let digit = |offset| self.peek(offset).is_some_and(|c| c.is_ascii_digit());
+        match self.peek(offset) {
+            Some('+') | Some('-') => {
+                digit(offset + 1) || (self.peek(offset + 1) == Some('.') && digit(offset + 2))
+            }
+            Some('.') => digit(offset + 1),
+            Some(c) => c.is_ascii_digit(),
+            None => false,
+        }
+    }
+
+    /// Consume an escape, after the `\`
+    fn consume_escape(&mut self) -> char {
+        match self.next_char() {
+            Some(c) if c.is_ascii_hexdigit() => {
+                let mut hex = StdString::from(c);
+                while hex.len() < 6 && self.peek(0).is_some_and(|c| c.is_ascii_hexdigit()) {
+                    hex.push(self.next_char().unwrap());
+                }
+                if self.peek(0).is_some_and(is_whitespace) {
+                    self.position += 1;
+                }
+                u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|&code| code != 0)
+                    .and_then(char::from_u32)
+                    .unwrap_or('\u{fffd}')
+            }
+            Some(c) => c,
+            None => '\u{fffd}',
+        }
+    }
+
+    fn consume_name(&mut self) -> StdString {
+        let mut name = StdString::new();
+        loop {
+            match self.peek(0) {
+                Some(c) if is_name(c) => {
+                    self.position += 1;
+                    name.push(c);
+                }
+                Some('\\') if self.is_valid_escape(0) => {
+                    self.position += 1;
+                    name.push(self.consume_escape());
+                }
+                _ => return name,
+            }
+        }
+    }
+
+    /// https://drafts.csswg.org/css-syntax-3/#consume-number
+    fn consume_number(&mut self) -> (f32, bool, bool, StdString) {
+        let digit = |tokenizer: &Self, offset| {
+            tokenizer
+                .peek(offset)
+                .is_some_and(|c: char| c.is_ascii_digit())
+        };
+
+        let mut repr = StdString::new();
+        let mut is_integer = true;
+        let has_sign = matches!(self.peek(0), Some('+') | Some('-'));
+        if has_sign {
+            repr.push(self.next_char().unwrap());
+        }
+        while digit(self, 0) {
+            repr.push(self.next_char().unwrap());
+        }
+        if self.peek(0) == Some('.') && digit(self, 1) {
+            is_integer = false;
+            repr.push(self.next_char().unwrap());
+            while digit(self, 0) {
+                repr.push(self.next_char().unwrap());
+            }
+        }
+        if matches!(self.peek(0), Some('e') | Some('E'))
+            && (digit(self, 1) || (matches!(self.peek(1), Some('+') | Some('-')) && digit(self, 2)))
+        {
+            is_integer = false;
+            repr.push(self.next_char().unwrap());
+            if !digit(self, 0) {
+                repr.push(self.next_char().unwrap());
+            }
+            while digit(self, 0) {
+                repr.push(self.next_char().unwrap());
+            }
+        }
+
+        let value = repr.parse::<f64>().unwrap_or(0.0) as f32;
+        (value, is_integer, has_sign, repr)
+    }
+
+    fn consume_numeric(&mut self) -> Token {
+        let (value, is_integer, has_sign, representation) = self.consume_number();
+        if self.starts_ident(0) {
+            Token::Dimension {
+                value,
+                is_integer,
+                has_sign,
+                representation,
+                unit: self.consume_name(),
+            }
+        } else if self.peek(0) == Some('%') {
+            self.position += 1;
+            Token::Percentage(value)
+        } else {
+            Token::Number {
+                value,
+                is_integer,
+                has_sign,
+                representation,
+            }
+        }
+    }
+
+    fn consume_ident_like(&mut self) -> Token {
+        let name = self.consume_name();
+        if self.peek(0) != Some('(') {
+            return Token::Ident(name);
+        }
+        self.position += 1;
+        if !name.eq_ignore_ascii_case("url") {
+            return Token::Function(name);
+        }
+
+        // A quoted url is a regular function, an unquoted one is a single token
+        let mut offset = 0;
+        while self.peek(offset).is_some_and(is_whitespace) {
+            offset += 1;
+        }
+        match self.peek(offset) {
+            Some('"') | Some('\'') => Token::Function(name),
+            _ => {
+                self.position += offset;
+                self.consume_url()
+            }
+        }
+    }
+
+    /// https://drafts.csswg.org/css-syntax-3/#consume-url-token
+    fn consume_url(&mut self) -> Token {
+        let mut url = StdString::new();
+        loop {
+            match self.next_char() {
+                Some(')') | None => return Token::Url(url),
+                Some(c) if is_whitespace(c) => {
+                    while self.peek(0).is_some_and(is_whitespace) {
+                        self.position += 1;
+                    }
+                    return match self.peek(0) {
+                        Some(')') | None => {
+                            self.position += 1;
+                            Token::Url(url)
+                        }
+                        _ => self.consume_bad_url(),
+                    };
+                }
+                Some('"') | Some('\'') | Some('(') => return self.consume_bad_url(),
+                Some(c) if is_non_printable(c) => return self.consume_bad_url(),
+                Some('\\') => {
+                    if self.peek(0).is_some_and(|c| c != '\n') {
+                        url.push(self.consume_escape());
+                    } else {
+                        return self.consume_bad_url();
+                    }
+                }
+                Some(c) => url.push(c),
+            }
+        }
+    }
+
+    fn consume_bad_url(&mut self) -> Token {
+        loop {
+            match self.peek(0) {
+                Some(')') | None => {
+                    self.position += 1;
+                    return Token::BadUrl;
+                }
+                Some('\\') if self.is_valid_escape(0) => {
+                    self.position += 1;
+                    self.consume_escape();
+                }
+                Some(_) => self.position += 1,
+            }
+        }
+    }
+
+    /// https://drafts.csswg.org/css-syntax-3/#consume-string-token
+    fn consume_string(&mut self, quote: char) -> Token {
+        let mut string = StdString::new();
+        loop {
+            match self.peek(0) {
+                None => return Token::String(string),
+                Some(c) if c == quote => {
+                    self.position += 1;
+                    return Token::String(string);
+                }
+                // Newlines can't be in strings, and aren't consumed
+                Some('\n') => return Token::BadString,
+                Some('\\') => {
+                    self.position += 1;
+                    match self.peek(0) {
+                        None => {}
+                        Some('\n') => self.position += 1,
+                        Some(_) => string.push(self.consume_escape()),
+                    }
+                }
+                Some(c) => {
+                    self.position += 1;
+                    string.push(c);
+                }
+            }
+        }
+    }
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n')
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_name(c: char) -> bool {
+    is_name_start(c) || c.is_ascii_digit() || c == '-'
+}
+
+fn is_non_printable(c: char) -> bool {
+    matches!(c, '\0'..='\u{8}' | '\u{b}' | '\u{e}'..='\u{1f}' | '\u{7f}')
+}
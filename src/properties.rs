@@ -1,7 +1,14 @@
-use crate::data_types;
-use strum::EnumDiscriminants;
+use std::{fmt, string::String as StdString};
 
-#[derive(EnumDiscriminants)]
+use strum::{EnumDiscriminants, EnumString};
+
+use crate::{
+    at_rules::{FeatureValueBlock, FontFeatureValues},
+    data_types,
+};
+
+#[derive(Clone, Debug, PartialEq, EnumDiscriminants)]
+#[strum_discriminants(derive(EnumString, Hash), strum(serialize_all = "kebab-case"))]
 pub enum Property {
     AccentColor(AccentColor),
     AlignContent {
@@ -18,23 +25,191 @@ pub enum Property {
     },
     All(shorthand::All),
     Animation(shorthand::Animation),
+    AnimationComposition(AnimationComposition),
     AnimationDelay(AnimationDelay),
     AnimationDirection(AnimationDirection),
     AnimationDuration(AnimationDuration),
     AnimationFillMode(AnimationFillMode),
+    AnimationIterationCount(AnimationIterationCount),
     AnimationName(AnimationName),
     AnimationPlayState(AnimationPlayState),
+    AnimationRange(shorthand::AnimationRange),
+    AnimationRangeEnd(AnimationRangeEnd),
+    AnimationRangeStart(AnimationRangeStart),
+    AnimationTimeline(AnimationTimeline),
     AnimationTimingFunction(AnimationTimingFunction),
     Appearance(Appearance),
     AspectRatio(AspectRatio),
+    BackfaceVisibility(BackfaceVisibility),
+    BlockSize(BlockSize),
+    Bottom(Bottom),
+    BoxSizing(BoxSizing),
+    Clear(Clear),
+    ColumnGap(ColumnGap),
+    Direction(Direction),
+    Display(Display),
+    Flex(shorthand::Flex),
+    FlexBasis(FlexBasis),
+    FlexDirection(FlexDirection),
+    FlexFlow(shorthand::FlexFlow),
+    FlexGrow(FlexGrow),
+    FlexShrink(FlexShrink),
+    FlexWrap(FlexWrap),
+    Float(Float),
+    Font(shorthand::Font),
+    FontFamily(FontFamily),
+    FontFeatureSettings(FontFeatureSettings),
+    FontKerning(FontKerning),
+    FontLanguageOverride(FontLanguageOverride),
+    FontOpticalSizing(FontOpticalSizing),
+    FontPalette(FontPalette),
+    FontSize(FontSize),
+    FontSizeAdjust(FontSizeAdjust),
+    FontStretch(FontStretch),
+    FontStyle(FontStyle),
+    FontVariant(shorthand::FontVariant),
+    FontVariantAlternates(FontVariantAlternates),
+    FontVariantCaps(FontVariantCaps),
+    FontVariantEastAsian(FontVariantEastAsian),
+    FontVariantEmoji(FontVariantEmoji),
+    FontVariantLigatures(FontVariantLigatures),
+    FontVariantNumeric(FontVariantNumeric),
+    FontVariantPosition(FontVariantPosition),
+    FontVariationSettings(FontVariationSettings),
+    FontWeight(FontWeight),
+    Gap(shorthand::Gap),
+    Grid(shorthand::Grid),
+    GridArea(shorthand::GridArea),
+    GridAutoColumns(GridAutoColumns),
+    GridAutoFlow(GridAutoFlow),
+    GridAutoRows(GridAutoRows),
+    GridColumn(shorthand::GridColumn),
+    GridColumnEnd(GridColumnEnd),
+    GridColumnStart(GridColumnStart),
+    GridRow(shorthand::GridRow),
+    GridRowEnd(GridRowEnd),
+    GridRowStart(GridRowStart),
+    GridTemplate(shorthand::GridTemplate),
+    GridTemplateAreas(GridTemplateAreas),
+    GridTemplateColumns(GridTemplateColumns),
+    GridTemplateRows(GridTemplateRows),
+    Height(Height),
+    Hyphens(Hyphens),
+    InlineSize(InlineSize),
+    Inset(shorthand::Inset),
+    InsetBlock(shorthand::InsetBlock),
+    InsetBlockEnd(InsetBlockEnd),
+    InsetBlockStart(InsetBlockStart),
+    InsetInline(shorthand::InsetInline),
+    InsetInlineEnd(InsetInlineEnd),
+    InsetInlineStart(InsetInlineStart),
+    JustifyContent {
+        value: JustifyContent,
+        safety: Option<bool>,
+    },
+    JustifyItems {
+        value: JustifyItems,
+        safety: Option<bool>,
+    },
+    JustifySelf {
+        value: JustifySelf,
+        safety: Option<bool>,
+    },
+    Left(Left),
+    LetterSpacing(LetterSpacing),
+    LineHeight(LineHeight),
+    Margin(shorthand::Margin),
+    MarginBlock(shorthand::MarginBlock),
+    MarginBlockEnd(MarginBlockEnd),
+    MarginBlockStart(MarginBlockStart),
+    MarginBottom(MarginBottom),
+    MarginInline(shorthand::MarginInline),
+    MarginInlineEnd(MarginInlineEnd),
+    MarginInlineStart(MarginInlineStart),
+    MarginLeft(MarginLeft),
+    MarginRight(MarginRight),
+    MarginTop(MarginTop),
+    MaxBlockSize(MaxBlockSize),
+    MaxHeight(MaxHeight),
+    MaxInlineSize(MaxInlineSize),
+    MaxWidth(MaxWidth),
+    MinBlockSize(MinBlockSize),
+    MinHeight(MinHeight),
+    MinInlineSize(MinInlineSize),
+    MinWidth(MinWidth),
+    Order(Order),
+    Overflow(shorthand::Overflow),
+    OverflowBlock(OverflowBlock),
+    OverflowClipMargin(OverflowClipMargin),
+    OverflowInline(OverflowInline),
+    OverflowWrap(OverflowWrap),
+    OverflowX(OverflowX),
+    OverflowY(OverflowY),
+    Padding(shorthand::Padding),
+    PaddingBlock(shorthand::PaddingBlock),
+    PaddingBlockEnd(PaddingBlockEnd),
+    PaddingBlockStart(PaddingBlockStart),
+    PaddingBottom(PaddingBottom),
+    PaddingInline(shorthand::PaddingInline),
+    PaddingInlineEnd(PaddingInlineEnd),
+    PaddingInlineStart(PaddingInlineStart),
+    PaddingLeft(PaddingLeft),
+    PaddingRight(PaddingRight),
+    PaddingTop(PaddingTop),
+    Perspective(Perspective),
+    PerspectiveOrigin(PerspectiveOrigin),
+    Position(Position),
+    Right(Right),
+    Rotate(Rotate),
+    RowGap(RowGap),
+    Scale(Scale),
+    TabSize(TabSize),
+    TextAlign(TextAlign),
+    TextAlignLast(TextAlignLast),
+    TextDecoration(shorthand::TextDecoration),
+    TextDecorationColor(TextDecorationColor),
+    TextDecorationLine(TextDecorationLine),
+    TextDecorationStyle(TextDecorationStyle),
+    TextDecorationThickness(TextDecorationThickness),
+    TextIndent(TextIndent),
+    TextOverflow(TextOverflow),
+    TextShadow(TextShadow),
+    TextTransform(TextTransform),
+    TextUnderlineOffset(TextUnderlineOffset),
+    TextWrap(shorthand::TextWrap),
+    TextWrapMode(TextWrapMode),
+    TextWrapStyle(TextWrapStyle),
+    Top(Top),
+    Transform(Transform),
+    TransformBox(TransformBox),
+    TransformOrigin(TransformOrigin),
+    TransformStyle(TransformStyle),
+    Transition(shorthand::Transition),
+    TransitionBehavior(TransitionBehavior),
+    TransitionDelay(TransitionDelay),
+    TransitionDuration(TransitionDuration),
+    TransitionProperty(TransitionProperty),
+    TransitionTimingFunction(TransitionTimingFunction),
+    Translate(Translate),
+    UnicodeBidi(UnicodeBidi),
+    Visibility(Visibility),
+    WhiteSpace(shorthand::WhiteSpace),
+    WhiteSpaceCollapse(WhiteSpaceCollapse),
+    Width(Width),
+    WordBreak(WordBreak),
+    WordSpacing(WordSpacing),
+    WritingMode(WritingMode),
+    ZIndex(ZIndex),
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum AccentColor {
     Auto,
     Color(data_types::Color),
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/align-content
+#[derive(Clone, Debug, PartialEq)]
 pub enum AlignContent {
     Start,
     End,
@@ -53,6 +228,7 @@ pub enum AlignContent {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/align-items
+#[derive(Clone, Debug, PartialEq)]
 pub enum AlignItems {
     Normal,
     FlexStart,
@@ -69,6 +245,7 @@ pub enum AlignItems {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/align-self
+#[derive(Clone, Debug, PartialEq)]
 pub enum AlignSelf {
     Auto,
     Normal,
@@ -85,51 +262,266 @@ pub enum AlignSelf {
     Stretch,
 }
 
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-composition
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationComposition(pub Vec<AnimationCompositionValue>);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationCompositionValue {
+    Replace,
+    Add,
+    Accumulate,
+}
+
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-delay
-pub struct AnimationDelay(data_types::Time);
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationDelay(pub Vec<data_types::Time>);
+
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-direction
-pub enum AnimationDirection {
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationDirection(pub Vec<AnimationDirectionValue>);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationDirectionValue {
     Normal,
     Reverse,
     Alternate,
     AlternateReverse,
 }
+
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-duration
-pub struct AnimationDuration(data_types::PositiveTime);
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationDuration(pub Vec<data_types::PositiveTime>);
+
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-fill-mode
-pub enum AnimationFillMode {
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationFillMode(pub Vec<AnimationFillModeValue>);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationFillModeValue {
     None,
     Forwards,
     Backwards,
     Both,
 }
+
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-iteration-count
-pub enum AnimationIterationCount {
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationIterationCount(pub Vec<AnimationIterationCountValue>);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnimationIterationCountValue {
     Infinite,
     Finite(data_types::PositiveNumber), // Default 1
 }
+
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-name
-pub enum AnimationName {
+///
+/// This list coordinates the other `animation-*` longhands (see
+/// [`AnimationLonghands`]).
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationName(pub Vec<AnimationNameValue>);
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationNameValue {
     None,
     Custom(data_types::CustomIdent),
+    String(data_types::String),
 }
+
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-play-state
-pub enum AnimationPlayState {
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationPlayState(pub Vec<AnimationPlayStateValue>);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationPlayStateValue {
     Running,
     Paused,
 }
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-range-end
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationRangeEnd(pub Vec<AnimationRangeValue>);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-range-start
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationRangeStart(pub Vec<AnimationRangeValue>);
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationRangeValue {
+    Normal,
+    /// An offset into the whole timeline
+    Offset(data_types::LengthPercentage),
+    /// An offset into a named range. An omitted offset is 0% for the start of
+    /// a range and 100% for the end.
+    Named {
+        name: TimelineRangeName,
+        offset: Option<data_types::LengthPercentage>,
+    },
+}
+/// https://drafts.csswg.org/scroll-animations-1/#named-ranges
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimelineRangeName {
+    Cover,
+    Contain,
+    Entry,
+    Exit,
+    EntryCrossing,
+    ExitCrossing,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-timeline
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationTimeline(pub Vec<AnimationTimelineValue>);
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationTimelineValue {
+    /// The document timeline
+    Auto,
+    None,
+    /// A named scroll or view progress timeline
+    Named(data_types::DashedIdent),
+    /// `scroll(<scroller>? <axis>?)`
+    Scroll {
+        scroller: ScrollTimelineScroller,
+        axis: TimelineAxis,
+    },
+    /// `view(<axis>? <'view-timeline-inset'>?)`, where a `None` inset is
+    /// `auto` and an omitted end inset copies the start
+    View {
+        axis: TimelineAxis,
+        inset_start: Option<data_types::LengthPercentage>,
+        inset_end: Option<data_types::LengthPercentage>,
+    },
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollTimelineScroller {
+    Nearest,
+    Root,
+    SelfElement,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimelineAxis {
+    Block,
+    Inline,
+    X,
+    Y,
+}
+
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-timing-function
-pub enum AnimationTimingFunction {
-    Easing(EasingFunction), // TODO
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationTimingFunction(pub Vec<EasingFunction>);
+
+/// The `animation-*` longhands, which describe one animation per item of
+/// `animation-name`
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationLonghands {
+    pub name: AnimationName,
+    pub duration: AnimationDuration,
+    pub timing_function: AnimationTimingFunction,
+    pub delay: AnimationDelay,
+    pub iteration_count: AnimationIterationCount,
+    pub direction: AnimationDirection,
+    pub fill_mode: AnimationFillMode,
+    pub play_state: AnimationPlayState,
+    pub composition: AnimationComposition,
+    pub timeline: AnimationTimeline,
+    pub range_start: AnimationRangeStart,
+    pub range_end: AnimationRangeEnd,
+}
+impl AnimationLonghands {
+    /// The number of animations, which is the length of `animation-name`
+    pub fn animation_count(&self) -> usize {
+        self.name.0.len()
+    }
+
+    /// Repeat or truncate every list to the length of `animation-name` (see
+    /// [`coordinate_list`])
+    pub fn coordinate(&self) -> Self {
+        let length = self.animation_count();
+        Self {
+            name: self.name.clone(),
+            duration: AnimationDuration(coordinate_list(&self.duration.0, length)),
+            timing_function: AnimationTimingFunction(coordinate_list(
+                &self.timing_function.0,
+                length,
+            )),
+            delay: AnimationDelay(coordinate_list(&self.delay.0, length)),
+            iteration_count: AnimationIterationCount(coordinate_list(
+                &self.iteration_count.0,
+                length,
+            )),
+            direction: AnimationDirection(coordinate_list(&self.direction.0, length)),
+            fill_mode: AnimationFillMode(coordinate_list(&self.fill_mode.0, length)),
+            play_state: AnimationPlayState(coordinate_list(&self.play_state.0, length)),
+            composition: AnimationComposition(coordinate_list(&self.composition.0, length)),
+            timeline: AnimationTimeline(coordinate_list(&self.timeline.0, length)),
+            range_start: AnimationRangeStart(coordinate_list(&self.range_start.0, length)),
+            range_end: AnimationRangeEnd(coordinate_list(&self.range_end.0, length)),
+        }
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/easing-function
+#[derive(Clone, Debug, PartialEq)]
+pub enum EasingFunction {
     Ease,
     Linear,
     EaseIn,
     EaseOut,
     EaseInOut,
     CubicBezier(f32, f32, f32, f32),
+    /// `steps()`, which needs at least one step (or two for `jump-none`).
+    /// Fewer steps are evaluated as the minimum.
     Steps(u32, EasingStepsJump),
+    /// `linear(<linear-stop>#)`, with each stop of two input percentages
+    /// split into two stops
+    LinearFunction(Vec<LinearEasingStop>),
+}
+impl EasingFunction {
+    /// The output progress for an input progress `t` (usually from 0 to 1,
+    /// but inputs outside of that are extrapolated)
+    ///
+    /// https://drafts.csswg.org/css-easing-2/#easing-functions
+    pub fn evaluate(&self, t: f64) -> f64 {
+        self.evaluate_with_before_flag(t, false)
+    }
+
+    /// [`EasingFunction::evaluate`], where `before_flag` is set when the
+    /// animation is in its before phase. This only affects `steps()`, whose
+    /// output at a step boundary is then the step before it.
+    ///
+    /// https://drafts.csswg.org/css-easing-2/#before-flag
+    pub fn evaluate_with_before_flag(&self, t: f64, before_flag: bool) -> f64 {
+        match self {
+            EasingFunction::Linear => t,
+            EasingFunction::Ease => cubic_bezier(0.25, 0.1, 0.25, 1.0, t),
+            EasingFunction::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            EasingFunction::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            EasingFunction::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            EasingFunction::CubicBezier(x1, y1, x2, y2) => {
+                cubic_bezier(*x1 as f64, *y1 as f64, *x2 as f64, *y2 as f64, t)
+            }
+            EasingFunction::Steps(steps, jump) => {
+                // https://drafts.csswg.org/css-easing-2/#step-easing-algo
+                let minimum = if *jump == EasingStepsJump::None { 2 } else { 1 };
+                let steps = (*steps).max(minimum) as f64;
+                let mut step = (t * steps).floor();
+                if matches!(jump, EasingStepsJump::Start | EasingStepsJump::Both) {
+                    step += 1.0;
+                }
+                if before_flag && (t * steps).fract() == 0.0 {
+                    step -= 1.0;
+                }
+                if t >= 0.0 && step < 0.0 {
+                    step = 0.0;
+                }
+                let jumps = match jump {
+                    EasingStepsJump::Start | EasingStepsJump::End => steps,
+                    EasingStepsJump::None => steps - 1.0,
+                    EasingStepsJump::Both => steps + 1.0,
+                };
+                if t <= 1.0 && step > jumps {
+                    step = jumps;
+                }
+                step / jumps
+            }
+            EasingFunction::LinearFunction(stops) => linear_function(stops, t),
+        }
+    }
 }
-pub enum EasingFunction {}
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum EasingStepsJump {
     // TODO: move to easings?
     Start,
@@ -137,8 +529,163 @@ pub enum EasingStepsJump {
     None,
     Both,
 }
+/// A single point of [`EasingFunction::LinearFunction`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearEasingStop {
+    pub output: data_types::Number,
+    /// An omitted input is spaced evenly between its neighbours (or 0% for
+    /// the first stop and 100% for the last)
+    pub input: Option<data_types::Percentage>,
+}
+
+/// Solve `cubic-bezier(x1, y1, x2, y2)` for the input progress `x`, where
+/// `x1` and `x2` are from 0 to 1
+///
+/// https://drafts.csswg.org/css-easing-2/#cubic-bezier-algo
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+    // Outside of 0 to 1, extend the curve along its tangent at that end
+    if x < 0.0 {
+        let gradient = if x1 > 0.0 {
+            y1 / x1
+        } else if x2 > 0.0 {
+            y2 / x2
+        } else {
+            0.0
+        };
+        return gradient * x;
+    }
+    if x > 1.0 {
+        let gradient = if x2 < 1.0 {
+            (y2 - 1.0) / (x2 - 1.0)
+        } else if x1 < 1.0 {
+            (y1 - 1.0) / (x1 - 1.0)
+        } else {
+            0.0
+        };
+        return 1.0 + gradient * (x - 1.0);
+    }
+
+    // The coefficients of the polynomial form of each coordinate, with the end
+    // points fixed at (0, 0) and (1, 1)
+    let (cx, cy) = (3.0 * x1, 3.0 * y1);
+    let (bx, by) = (3.0 * (x2 - x1) - cx, 3.0 * (y2 - y1) - cy);
+    let (ax, ay) = (1.0 - cx - bx, 1.0 - cy - by);
+    let sample_x = |t: f64| ((ax * t + bx) * t + cx) * t;
+    let sample_y = |t: f64| ((ay * t + by) * t + cy) * t;
+    let sample_dx = |t: f64| (3.0 * ax * t + 2.0 * bx) * t + cx;
+
+    const EPSILON: f64 = 1e-7;
+
+    // Newton's method converges quickly for most curves...
+    let mut t = x;
+    for _ in 0..8 {
+        let error = sample_x(t) - x;
+        if error.abs() < EPSILON {
+            return sample_y(t);
+        }
+        let derivative = sample_dx(t);
+        if derivative.abs() < EPSILON {
+            break;
+        }
+        t -= error / derivative;
+    }
+
+    // ...but fall back to bisection, which always does (since x is monotonic
+    // when x1 and x2 are from 0 to 1)
+    let (mut low, mut high) = (0.0, 1.0);
+    t = x;
+    while high - low > EPSILON {
+        let value = sample_x(t);
+        if (value - x).abs() < EPSILON {
+            break;
+        }
+        if value < x {
+            low = t;
+        } else {
+            high = t;
+        }
+        t = (low + high) / 2.0;
+    }
+    sample_y(t)
+}
+
+/// https://drafts.csswg.org/css-easing-2/#linear-easing-function-output
+fn linear_function(stops: &[LinearEasingStop], x: f64) -> f64 {
+    let points = linear_function_points(stops);
+    match points.len() {
+        0 => return x,
+        1 => return points[0].1,
+        _ => {}
+    }
+
+    // Interpolate between the last point at or before the input and the one
+    // after it (extrapolating from the first or last pair)
+    let mut a = points
+        .iter()
+        .rposition(|(input, _)| *input <= x)
+        .unwrap_or(0);
+    if a == points.len() - 1 {
+        a -= 1;
+    }
+    let (a_input, a_output) = points[a];
+    let (b_input, b_output) = points[a + 1];
+    if a_input == b_input {
+        return b_output;
+    }
+    let progress = (x - a_input) / (b_input - a_input);
+    a_output + (b_output - a_output) * progress
+}
+
+/// The `(input, output)` points of `linear()`, with every input resolved
+///
+/// https://drafts.csswg.org/css-easing-2/#linear-canonicalization
+fn linear_function_points(stops: &[LinearEasingStop]) -> Vec<(f64, f64)> {
+    let mut inputs: Vec<Option<f64>> = stops
+        .iter()
+        .map(|stop| stop.input.map(|input| input.0 .0 as f64 / 100.0))
+        .collect();
+    if let Some(first) = inputs.first_mut() {
+        first.get_or_insert(0.0);
+    }
+    if inputs.len() > 1 {
+        if let Some(last) = inputs.last_mut() {
+            last.get_or_insert(1.0);
+        }
+    }
+
+    // Inputs can't go backwards
+    let mut largest = f64::NEG_INFINITY;
+    for input in inputs.iter_mut().flatten() {
+        largest = largest.max(*input);
+        *input = largest;
+    }
+
+    // Space runs of missing inputs evenly between their neighbours
+    let mut i = 0;
+    while i < inputs.len() {
+        if inputs[i].is_some() {
+            i += 1;
+            continue;
+        }
+        let start = i - 1;
+        let end = (i..inputs.len()).find(|&j| inputs[j].is_some()).unwrap();
+        let (from, to) = (inputs[start].unwrap(), inputs[end].unwrap());
+        for (j, input) in inputs.iter_mut().enumerate().take(end).skip(i) {
+            let progress = (j - start) as f64 / (end - start) as f64;
+            *input = Some(from + (to - from) * progress);
+        }
+        i = end;
+    }
+
+    inputs
+        .into_iter()
+        .zip(stops)
+        .map(|(input, stop)| (input.unwrap(), stop.output.0 as f64))
+        .collect()
+}
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/appearance
+#[derive(Clone, Debug, PartialEq)]
 pub enum Appearance {
     None,
     Auto,
@@ -160,29 +707,35 @@ pub enum Appearance {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/aspect-ratio
+#[derive(Clone, Debug, PartialEq)]
 pub enum AspectRatio {
     Auto, // initial/inherit/rever/unset?
     Ratio(data_types::Ratio),
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/backdrop-filter
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackdropFilter {
     None,
     FilterFunctionList(Vec<FilterFunctionOrSvg>),
 }
+#[derive(Clone, Debug, PartialEq)]
 pub enum FilterFunctionOrSvg {
     Filter(data_types::FilterFunction),
     Svg(data_types::Url),
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/backface-visibility
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackfaceVisibility {
     Visible,
     Hidden,
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-attachment
-pub struct BackgroundAttachment(Vec<BackgroundLayerAttachment>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundAttachment(pub Vec<BackgroundLayerAttachment>);
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackgroundLayerAttachment {
     // TODO: maybe more global <attachment>
     Fixed,
@@ -191,11 +744,15 @@ pub enum BackgroundLayerAttachment {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-blend-mode
-pub struct BackgroundBlendMode(Vec<BackgroundLayerBlendMode>);
-pub struct BackgroundLayerBlendMode(data_types::BlendMode);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundBlendMode(pub Vec<BackgroundLayerBlendMode>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundLayerBlendMode(pub data_types::BlendMode);
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-clip
-pub struct BackgroundClip(Vec<BackgroundLayerClip>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundClip(pub Vec<BackgroundLayerClip>);
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackgroundLayerClip {
     BorderBox,
     PaddingBox,
@@ -204,7 +761,9 @@ pub enum BackgroundLayerClip {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-color
-pub struct BackgroundColor(Vec<BackgroundLayerColor>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundColor(pub Vec<BackgroundLayerColor>);
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackgroundLayerColor {
     // TODO: or inherit/initial/revert/unset
     Color(data_types::Color),
@@ -213,14 +772,18 @@ pub enum BackgroundLayerColor {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-image
-pub struct BackgroundImage(Vec<BackgroundLayerImage>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundImage(pub Vec<BackgroundLayerImage>);
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackgroundLayerImage {
     None,
     Image(data_types::Image),
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-origin
-pub struct BackgroundOrigin(Vec<BackgroundLayerOrigin>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundOrigin(pub Vec<BackgroundLayerOrigin>);
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackgroundLayerOrigin {
     /// TODO: combine box types?
     BorderBox,
@@ -229,23 +792,32 @@ pub enum BackgroundLayerOrigin {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-position
-pub struct BackgroundPosition(Vec<BackgroundLayerPosition>);
-pub struct BackgroundLayerPosition(data_types::Position);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundPosition(pub Vec<BackgroundLayerPosition>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundLayerPosition(pub data_types::Position);
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-position-x
-pub struct BackgroundPositionX(Vec<BackgroundLayerPositionX>);
-pub struct BackgroundLayerPositionX(data_types::PositionX);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundPositionX(pub Vec<BackgroundLayerPositionX>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundLayerPositionX(pub data_types::PositionX);
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-position-y
-pub struct BackgroundPositionY(Vec<BackgroundLayerPositionY>);
-pub struct BackgroundLayerPositionY(data_types::PositionY);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundPositionY(pub Vec<BackgroundLayerPositionY>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundLayerPositionY(pub data_types::PositionY);
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-repeat
-pub struct BackgroundRepeat(Vec<BackgroundLayerRepeat>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundRepeat(pub Vec<BackgroundLayerRepeat>);
+#[derive(Clone, Debug, PartialEq)]
 pub struct BackgroundLayerRepeat {
     pub x: BackgroundRepeatStyle,
     pub y: BackgroundRepeatStyle,
 } // TODO: shorthands
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackgroundRepeatStyle {
     Repeat,
     Space,
@@ -254,7 +826,9 @@ pub enum BackgroundRepeatStyle {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/background-size
-pub struct BackgroundSize(Vec<BackgroundLayerSize>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackgroundSize(pub Vec<BackgroundLayerSize>);
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackgroundLayerSize {
     Contain,
     Cover,
@@ -266,107 +840,144 @@ pub enum BackgroundLayerSize {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/block-size
-pub struct BlockSize(data_types::WidthValue);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockSize(pub data_types::WidthValue);
 
 //* Border Block
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-color
-pub struct BorderBlockColor(data_types::Color);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBlockColor(pub data_types::Color);
 
 //* Border Block Start
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-start-color
-pub struct BorderBlockStartColor(data_types::Color);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBlockStartColor(pub data_types::Color);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-start-style
-pub struct BorderBlockStartStyle(data_types::LineStyle);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBlockStartStyle(pub data_types::LineStyle);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-start-width
-pub struct BorderBlockStartWidth(data_types::LineWidth);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBlockStartWidth(pub data_types::LineWidth);
 
 //* Border Block End
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-end-color
-pub struct BorderBlockEndColor(data_types::Color);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBlockEndColor(pub data_types::Color);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-end-style
-pub struct BorderBlockEndStyle(data_types::LineStyle);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBlockEndStyle(pub data_types::LineStyle);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-end-width
-pub struct BorderBlockEndWidth(data_types::LineWidth);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBlockEndWidth(pub data_types::LineWidth);
 
 //* Border Bottom
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-bottom-color
-pub struct BorderBottomColor(data_types::Color);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBottomColor(pub data_types::Color);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-bottom-style
-pub struct BorderBottomStyle(data_types::LineStyle);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBottomStyle(pub data_types::LineStyle);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-bottom-width
-pub struct BorderBottomWidth(data_types::LineWidth);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBottomWidth(pub data_types::LineWidth);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-bottom-left-radius
-pub struct BorderBottomLeftRadius(data_types::RadiusCorner);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBottomLeftRadius(pub data_types::RadiusCorner);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-bottom-right-radius
-pub struct BorderBottomRightRadius(data_types::RadiusCorner);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderBottomRightRadius(pub data_types::RadiusCorner);
 
 //* Border Left
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-left-color
-pub struct BorderLeftColor(data_types::Color);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderLeftColor(pub data_types::Color);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-left-style
-pub struct BorderLeftStyle(data_types::LineStyle);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderLeftStyle(pub data_types::LineStyle);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-left-width
-pub struct BorderLeftWidth(data_types::LineWidth);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderLeftWidth(pub data_types::LineWidth);
 
 //* Border Right
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-right-color
-pub struct BorderRightColor(data_types::Color);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderRightColor(pub data_types::Color);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-right-style
-pub struct BorderRightStyle(data_types::LineStyle);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderRightStyle(pub data_types::LineStyle);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-right-width
-pub struct BorderRightWidth(data_types::LineWidth);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderRightWidth(pub data_types::LineWidth);
 
 //* Border Top
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-top-color
-pub struct BorderTopColor(data_types::Color);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderTopColor(pub data_types::Color);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-top-style
-pub struct BorderTopStyle(data_types::LineStyle);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderTopStyle(pub data_types::LineStyle);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-top-width
-pub struct BorderTopWidth(data_types::LineWidth);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderTopWidth(pub data_types::LineWidth);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-top-left-radius
-pub struct BorderTopLeftRadius(data_types::RadiusCorner);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderTopLeftRadius(pub data_types::RadiusCorner);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-top-right-radius
-pub struct BorderTopRightRadius(data_types::RadiusCorner);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderTopRightRadius(pub data_types::RadiusCorner);
 
 //* Border Start
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-start-start-radius
-pub struct BorderStartStartRadius(data_types::RadiusCorner);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderStartStartRadius(pub data_types::RadiusCorner);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-start-end-radius
-pub struct BorderStartEndRadius(data_types::RadiusCorner);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderStartEndRadius(pub data_types::RadiusCorner);
 
 //* Border End
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-end-start-radius
-pub struct BorderEndStartRadius(data_types::RadiusCorner);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderEndStartRadius(pub data_types::RadiusCorner);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-end-end-radius
-pub struct BorderEndEndRadius(data_types::RadiusCorner);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderEndEndRadius(pub data_types::RadiusCorner);
 
 //* Border Inline
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-width
-pub struct BorderInlineWidth(data_types::LineWidth);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderInlineWidth(pub data_types::LineWidth);
 
 //* Border Inline Start
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-start-color
-pub struct BorderInlineStartColor(data_types::Color);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderInlineStartColor(pub data_types::Color);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-start-style
-pub struct BorderInlineStartStyle(data_types::LineStyle);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderInlineStartStyle(pub data_types::LineStyle);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-start-width
-pub struct BorderInlineStartWidth(data_types::LineWidth);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderInlineStartWidth(pub data_types::LineWidth);
 
 //* Border Inline End
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-end-color
-pub struct BorderInlineEndColor(data_types::Color);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderInlineEndColor(pub data_types::Color);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-end-style
-pub struct BorderInlineEndStyle(data_types::LineStyle);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderInlineEndStyle(pub data_types::LineStyle);
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-end-width
-pub struct BorderInlineEndWidth(data_types::LineWidth);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderInlineEndWidth(pub data_types::LineWidth);
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-collapse
+#[derive(Clone, Debug, PartialEq)]
 pub enum BorderCollapse {
     Collapse,
     Separate,
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-spacing
+#[derive(Clone, Debug, PartialEq)]
 pub struct BorderSpacing {
     // TODO: only when BorderCollpase is separate
     pub x: data_types::Length,
@@ -374,6 +985,7 @@ pub struct BorderSpacing {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-image-outset
+#[derive(Clone, Debug, PartialEq)]
 pub struct BorderImageOutset {
     pub top: data_types::LengthNumber,
     pub left: data_types::LengthNumber,
@@ -382,11 +994,13 @@ pub struct BorderImageOutset {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-image-repeat
+#[derive(Clone, Debug, PartialEq)]
 pub struct BorderImageRepeat {
     pub x: BorderImageRepeatValue,
     pub y: BorderImageRepeatValue,
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub enum BorderImageRepeatValue {
     Stretch,
     Repeat,
@@ -395,6 +1009,7 @@ pub enum BorderImageRepeatValue {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-image-slice
+#[derive(Clone, Debug, PartialEq)]
 pub struct BorderImageSlice {
     pub x1: data_types::NumberPercentage,
     pub x2: data_types::NumberPercentage,
@@ -405,15 +1020,18 @@ pub struct BorderImageSlice {
 }
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-image-source
-pub struct BorderImageSource(Option<data_types::Image>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorderImageSource(pub Option<data_types::Image>);
 
 /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-image-width
+#[derive(Clone, Debug, PartialEq)]
 pub struct BorderImageWidth {
     pub top: BorderImageWidthValue,
     pub left: BorderImageWidthValue,
     pub bottom: BorderImageWidthValue,
     pub right: BorderImageWidthValue,
 }
+#[derive(Clone, Debug, PartialEq)]
 pub enum BorderImageWidthValue {
     Auto,
     Length(data_types::Length),
@@ -421,125 +1039,2021 @@ pub enum BorderImageWidthValue {
     Number(data_types::Number),
 }
 
-pub mod shorthand {
-    use super::*;
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/box-sizing
+#[derive(Clone, Debug, PartialEq)]
+pub enum BoxSizing {
+    ContentBox,
+    BorderBox,
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/all
-    pub enum All {
-        Initial,
-        Inherit,
-        Unset,
-        Revert,
-    }
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/clear
+#[derive(Clone, Debug, PartialEq)]
+pub enum Clear {
+    None,
+    Left,
+    Right,
+    Both,
+    InlineStart,
+    InlineEnd,
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation
-    pub struct Animation {
-        // TODO: all option?
-        pub name: Option<AnimationName>,
-        pub duration: Option<AnimationDuration>,
-        pub timing_function: Option<AnimationTimingFunction>,
-        pub delay: Option<AnimationDelay>,
-        pub direction: Option<AnimationDirection>,
-        pub iteration_count: Option<AnimationIterationCount>,
-        pub fill_mode: Option<AnimationFillMode>,
-        pub play_state: Option<AnimationPlayState>,
-    }
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/column-gap
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnGap {
+    Normal,
+    LengthPercentage(data_types::LengthPercentage),
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/background
-    pub struct Background(Vec<BackgroundLayer>);
-    pub struct BackgroundLayer {
-        // TODO: at least one?
-        pub attachment: Option<BackgroundLayerAttachment>,
-        pub clip: Option<BackgroundLayerClip>,
-        pub color: Option<BackgroundLayerColor>,
-        pub image: Option<BackgroundLayerImage>,
-        pub origin: Option<BackgroundLayerOrigin>,
-        pub position: Option<BackgroundLayerPosition>,
-        pub repeat: Option<BackgroundLayerRepeat>,
-        pub size: Option<BackgroundLayerSize>,
-    }
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/direction
+#[derive(Clone, Debug, PartialEq)]
+pub enum Direction {
+    Ltr,
+    Rtl,
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border
-    pub struct Border {
-        pub color: Option<data_types::Color>,
-        pub style: Option<data_types::LineStyle>,
-        pub width: Option<data_types::LineWidth>,
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/display
+///
+/// Values are kept as specified; use [`Display::normalize`] to get the full
+/// two-value form.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Display {
+    /// `<display-outside> || <display-inside>`, with at least one present
+    Pair {
+        outside: Option<DisplayOutside>,
+        inside: Option<DisplayInside>,
+    },
+    /// `<display-outside>? && [ flow | flow-root ]? && list-item`
+    ListItem {
+        outside: Option<DisplayOutside>,
+        inside: Option<DisplayListItemInside>,
+    },
+    Internal(DisplayInternal),
+    Box(DisplayBox),
+    Legacy(DisplayLegacy),
+}
+impl Display {
+    pub const BLOCK: Display = Display::Pair {
+        outside: Some(DisplayOutside::Block),
+        inside: None,
+    };
+    pub const INLINE: Display = Display::Pair {
+        outside: Some(DisplayOutside::Inline),
+        inside: None,
+    };
+    pub const FLEX: Display = Display::Pair {
+        outside: None,
+        inside: Some(DisplayInside::Flex),
+    };
+    pub const GRID: Display = Display::Pair {
+        outside: None,
+        inside: Some(DisplayInside::Grid),
+    };
+    pub const NONE: Display = Display::Box(DisplayBox::None);
+
+    /// Expand to the full two-value (or three-value, for list items) form,
+    /// filling in omitted outer and inner display types and mapping legacy
+    /// keywords.
+    pub fn normalize(&self) -> NormalizedDisplay {
+        let outer = |outside, inside, list_item| {
+            NormalizedDisplay::Outer(DisplayType {
+                outside,
+                inside,
+                list_item,
+            })
+        };
+        match *self {
+            Display::Pair { outside, inside } => {
+                let inside = inside.unwrap_or(DisplayInside::Flow);
+                // `ruby` is the only inner display type defaulting to inline
+                let outside = outside.unwrap_or(match inside {
+                    DisplayInside::Ruby => DisplayOutside::Inline,
+                    _ => DisplayOutside::Block,
+                });
+                outer(outside, inside, false)
+            }
+            Display::ListItem { outside, inside } => outer(
+                outside.unwrap_or(DisplayOutside::Block),
+                match inside {
+                    Some(DisplayListItemInside::FlowRoot) => DisplayInside::FlowRoot,
+                    Some(DisplayListItemInside::Flow) | None => DisplayInside::Flow,
+                },
+                true,
+            ),
+            Display::Internal(internal) => NormalizedDisplay::Internal(internal),
+            Display::Box(display_box) => NormalizedDisplay::Box(display_box),
+            Display::Legacy(legacy) => outer(
+                DisplayOutside::Inline,
+                match legacy {
+                    DisplayLegacy::InlineBlock => DisplayInside::FlowRoot,
+                    DisplayLegacy::InlineTable => DisplayInside::Table,
+                    DisplayLegacy::InlineFlex => DisplayInside::Flex,
+                    DisplayLegacy::InlineGrid => DisplayInside::Grid,
+                },
+                false,
+            ),
+        }
     }
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-style
-    pub struct BorderStyle {
-        pub top: data_types::LineStyle,
-        pub left: data_types::LineStyle,
-        pub bottom: data_types::LineStyle,
-        pub right: data_types::LineStyle,
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/display-outside
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayOutside {
+    Block,
+    Inline,
+    RunIn,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/display-inside
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayInside {
+    Flow,
+    FlowRoot,
+    Table,
+    Flex,
+    Grid,
+    Ruby,
+}
+
+/// The inner display types allowed alongside `list-item`
+///
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/display-listitem
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayListItemInside {
+    Flow,
+    FlowRoot,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/display-internal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayInternal {
+    TableRowGroup,
+    TableHeaderGroup,
+    TableFooterGroup,
+    TableRow,
+    TableCell,
+    TableColumnGroup,
+    TableColumn,
+    TableCaption,
+    RubyBase,
+    RubyText,
+    RubyBaseContainer,
+    RubyTextContainer,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/display-box
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayBox {
+    Contents,
+    None,
+}
+
+/// Precomposed legacy keywords that are aliases of an inline two-value form
+///
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/display-legacy
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisplayLegacy {
+    /// `inline flow-root`
+    InlineBlock,
+    /// `inline table`
+    InlineTable,
+    /// `inline flex`
+    InlineFlex,
+    /// `inline grid`
+    InlineGrid,
+}
+
+/// A [`Display`] with every omitted or aliased part made explicit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalizedDisplay {
+    Outer(DisplayType),
+    Internal(DisplayInternal),
+    Box(DisplayBox),
+}
+impl NormalizedDisplay {
+    /// The shortest specified form with the same meaning, preferring single
+    /// (and legacy) keywords, e.g. `inline flow-root` becomes `inline-block`.
+    pub fn to_shortest(self) -> Display {
+        let display_type = match self {
+            NormalizedDisplay::Outer(display_type) => display_type,
+            NormalizedDisplay::Internal(internal) => return Display::Internal(internal),
+            NormalizedDisplay::Box(display_box) => return Display::Box(display_box),
+        };
+        let DisplayType {
+            outside,
+            inside,
+            list_item,
+        } = display_type;
+
+        if list_item {
+            return Display::ListItem {
+                outside: (outside != DisplayOutside::Block).then_some(outside),
+                inside: match inside {
+                    DisplayInside::FlowRoot => Some(DisplayListItemInside::FlowRoot),
+                    // Other inner types can't be combined with `list-item`
+                    _ => None,
+                },
+            };
+        }
+
+        let legacy = match (outside, inside) {
+            (DisplayOutside::Inline, DisplayInside::FlowRoot) => Some(DisplayLegacy::InlineBlock),
+            (DisplayOutside::Inline, DisplayInside::Table) => Some(DisplayLegacy::InlineTable),
+            (DisplayOutside::Inline, DisplayInside::Flex) => Some(DisplayLegacy::InlineFlex),
+            (DisplayOutside::Inline, DisplayInside::Grid) => Some(DisplayLegacy::InlineGrid),
+            _ => None,
+        };
+        if let Some(legacy) = legacy {
+            return Display::Legacy(legacy);
+        }
+
+        match (outside, inside) {
+            (outside, DisplayInside::Flow) => Display::Pair {
+                outside: Some(outside),
+                inside: None,
+            },
+            (DisplayOutside::Inline, DisplayInside::Ruby)
+            | (DisplayOutside::Block, DisplayInside::FlowRoot)
+            | (DisplayOutside::Block, DisplayInside::Table)
+            | (DisplayOutside::Block, DisplayInside::Flex)
+            | (DisplayOutside::Block, DisplayInside::Grid) => Display::Pair {
+                outside: None,
+                inside: Some(inside),
+            },
+            (outside, inside) => Display::Pair {
+                outside: Some(outside),
+                inside: Some(inside),
+            },
+        }
     }
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-color
-    pub struct BorderColor {
-        pub top: data_types::Color,
-        pub left: data_types::Color,
-        pub bottom: data_types::Color,
-        pub right: data_types::Color,
+/// A fully specified outer and inner display type
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DisplayType {
+    pub outside: DisplayOutside,
+    pub inside: DisplayInside,
+    pub list_item: bool,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/flex-basis
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlexBasis {
+    /// Size automatically based on the flex item's content
+    Content,
+    /// Same values as `width` (including `auto`)
+    Width(data_types::WidthValue),
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/flex-direction
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlexDirection {
+    Row,
+    RowReverse,
+    Column,
+    ColumnReverse,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/flex-grow
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlexGrow(pub data_types::PositiveNumber); // Default 0
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/flex-shrink
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlexShrink(pub data_types::PositiveNumber); // Default 1
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/flex-wrap
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlexWrap {
+    NoWrap,
+    Wrap,
+    WrapReverse,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/float
+#[derive(Clone, Debug, PartialEq)]
+pub enum Float {
+    None,
+    Left,
+    Right,
+    InlineStart,
+    InlineEnd,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-family
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontFamily(pub Vec<FontFamilyValue>);
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontFamilyValue {
+    Named(FamilyName),
+    Generic(GenericFamily),
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-family#family-name
+#[derive(Clone, Debug, PartialEq)]
+pub enum FamilyName {
+    Quoted(data_types::String),
+    /// A sequence of identifiers, which may not start with a generic family
+    /// keyword or a CSS-wide keyword
+    Unquoted(Vec<data_types::CustomIdent>),
+}
+impl FamilyName {
+    /// The family name, with unquoted identifiers joined by single spaces
+    pub fn name(&self) -> StdString {
+        match self {
+            FamilyName::Quoted(name) => name.0.clone(),
+            FamilyName::Unquoted(idents) => idents
+                .iter()
+                .map(|ident| ident.0.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
     }
-    
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-width
-    pub struct BorderWidth {
-        pub top: data_types::LineWidth,
-        pub left: data_types::LineWidth,
-        pub bottom: data_types::LineWidth,
-        pub right: data_types::LineWidth,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-family#generic-name
+#[derive(Clone, Debug, PartialEq)]
+pub enum GenericFamily {
+    Serif,
+    SansSerif,
+    Monospace,
+    Cursive,
+    Fantasy,
+    SystemUi,
+    UiSerif,
+    UiSansSerif,
+    UiMonospace,
+    UiRounded,
+    Emoji,
+    Math,
+    Fangsong,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-feature-settings
+///
+/// An empty list is `normal`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontFeatureSettings(pub Vec<FontFeatureTagValue>);
+/// A feature tag, with a value of `1` for `on` (the default) and `0` for `off`
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontFeatureTagValue {
+    pub tag: data_types::OpenTypeTag,
+    pub value: u32,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-kerning
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontKerning {
+    Auto,
+    Normal,
+    None,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-language-override
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontLanguageOverride {
+    Normal,
+    /// An OpenType language system tag
+    String(data_types::String),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-optical-sizing
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontOpticalSizing {
+    Auto,
+    None,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-palette
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontPalette {
+    Normal,
+    Light,
+    Dark,
+    /// A palette defined by `@font-palette-values`
+    Custom(data_types::DashedIdent),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-size
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontSize {
+    Absolute(AbsoluteSize),
+    Relative(RelativeSize),
+    LengthPercentage(data_types::LengthPercentage), // TODO: positive
+    Math,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/absolute-size
+#[derive(Clone, Debug, PartialEq)]
+pub enum AbsoluteSize {
+    XxSmall,
+    XSmall,
+    Small,
+    Medium,
+    Large,
+    XLarge,
+    XxLarge,
+    XxxLarge,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/relative-size
+#[derive(Clone, Debug, PartialEq)]
+pub enum RelativeSize {
+    Smaller,
+    Larger,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-size-adjust
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontSizeAdjust {
+    None,
+    Value {
+        /// Defaults to `ex-height`
+        metric: Option<FontSizeAdjustMetric>,
+        /// `None` is `from-font`
+        value: Option<data_types::PositiveNumber>,
+    },
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontSizeAdjustMetric {
+    ExHeight,
+    CapHeight,
+    ChWidth,
+    IcWidth,
+    IcHeight,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-stretch
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontStretch {
+    Keyword(FontStretchKeyword),
+    Percentage(data_types::Percentage), // TODO: positive
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontStretchKeyword {
+    UltraCondensed,
+    ExtraCondensed,
+    Condensed,
+    SemiCondensed,
+    Normal,
+    SemiExpanded,
+    Expanded,
+    ExtraExpanded,
+    UltraExpanded,
+}
+impl FontStretchKeyword {
+    /// The width percentage this keyword maps to
+    pub fn percentage(&self) -> f32 {
+        match self {
+            FontStretchKeyword::UltraCondensed => 50.0,
+            FontStretchKeyword::ExtraCondensed => 62.5,
+            FontStretchKeyword::Condensed => 75.0,
+            FontStretchKeyword::SemiCondensed => 87.5,
+            FontStretchKeyword::Normal => 100.0,
+            FontStretchKeyword::SemiExpanded => 112.5,
+            FontStretchKeyword::Expanded => 125.0,
+            FontStretchKeyword::ExtraExpanded => 150.0,
+            FontStretchKeyword::UltraExpanded => 200.0,
+        }
     }
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-radius
-    pub struct BorderRadius {
-        // TODO
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-style
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontStyle {
+    Normal,
+    Italic,
+    /// An optional angle between -90deg and 90deg (default 14deg)
+    Oblique(Option<data_types::Angle>),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-variant-alternates
+///
+/// The default (all empty) value is `normal`. Identifiers refer to values
+/// defined with `@font-feature-values`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FontVariantAlternates {
+    pub stylistic: Option<data_types::CustomIdent>,
+    pub historical_forms: bool,
+    pub styleset: Vec<data_types::CustomIdent>,
+    pub character_variant: Vec<data_types::CustomIdent>,
+    pub swash: Option<data_types::CustomIdent>,
+    pub ornaments: Option<data_types::CustomIdent>,
+    pub annotation: Option<data_types::CustomIdent>,
+}
+impl FontVariantAlternates {
+    /// The OpenType features to enable for text in `family`, resolving names
+    /// with the `@font-feature-values` rules (names that aren't defined for
+    /// the family are ignored)
+    ///
+    /// https://drafts.csswg.org/css-fonts-4/#font-variant-alternates-prop
+    pub fn feature_settings(
+        &self,
+        family: &FamilyName,
+        rules: &[FontFeatureValues],
+    ) -> Vec<FontFeatureTagValue> {
+        let mut features = Vec::new();
+        let mut push = |tag: StdString, value| {
+            if let Some(tag) = data_types::OpenTypeTag::new(&tag) {
+                features.push(FontFeatureTagValue { tag, value });
+            }
+        };
+        let lookup = |block, name| FontFeatureValues::lookup(rules, family, block, name);
+
+        if let Some(&[value]) = self
+            .stylistic
+            .as_ref()
+            .and_then(|name| lookup(FeatureValueBlock::Stylistic, name))
+        {
+            push("salt".to_owned(), value);
+        }
+        if self.historical_forms {
+            push("hist".to_owned(), 1);
+        }
+        for name in &self.styleset {
+            for &set in lookup(FeatureValueBlock::Styleset, name).unwrap_or_default() {
+                if (1..=20).contains(&set) {
+                    push(format!("ss{:02}", set), 1);
+                }
+            }
+        }
+        for name in &self.character_variant {
+            let (variant, value) = match lookup(FeatureValueBlock::CharacterVariant, name) {
+                Some(&[variant]) => (variant, 1),
+                Some(&[variant, value]) => (variant, value),
+                _ => continue,
+            };
+            if (1..=99).contains(&variant) {
+                push(format!("cv{:02}", variant), value);
+            }
+        }
+        if let Some(&[value]) = self
+            .swash
+            .as_ref()
+            .and_then(|name| lookup(FeatureValueBlock::Swash, name))
+        {
+            push("swsh".to_owned(), value);
+            push("cswh".to_owned(), value);
+        }
+        if let Some(&[value]) = self
+            .ornaments
+            .as_ref()
+            .and_then(|name| lookup(FeatureValueBlock::Ornaments, name))
+        {
+            push("ornm".to_owned(), value);
+        }
+        if let Some(&[value]) = self
+            .annotation
+            .as_ref()
+            .and_then(|name| lookup(FeatureValueBlock::Annotation, name))
+        {
+            push("nalt".to_owned(), value);
+        }
+        features
     }
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block
-    pub struct BorderBlock(Border);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-variant-caps
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontVariantCaps {
+    Normal,
+    SmallCaps,
+    AllSmallCaps,
+    PetiteCaps,
+    AllPetiteCaps,
+    Unicase,
+    TitlingCaps,
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-end
-    pub struct BorderBlockEnd(Border);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-variant-east-asian
+///
+/// The default (all empty) value is `normal`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FontVariantEastAsian {
+    pub variant: Option<FontVariantEastAsianVariant>,
+    pub width: Option<FontVariantEastAsianWidth>,
+    pub ruby: bool,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontVariantEastAsianVariant {
+    Jis78,
+    Jis83,
+    Jis90,
+    Jis04,
+    Simplified,
+    Traditional,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontVariantEastAsianWidth {
+    FullWidth,
+    ProportionalWidth,
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-start
-    pub struct BorderBlockStart(Border);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-variant-emoji
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontVariantEmoji {
+    Normal,
+    Text,
+    Emoji,
+    Unicode,
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-bottom
-    pub struct BorderBottom(Border);
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-left
-    pub struct BorderLeft(Border);
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-right
-    pub struct BorderRight(Border);
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-top
-    pub struct BorderTop(Border);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-variant-ligatures
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontVariantLigatures {
+    Normal,
+    None,
+    /// Each ligature type is enabled (`Some(true)`), disabled with its `no-`
+    /// keyword (`Some(false)`) or left as normal
+    Values {
+        common: Option<bool>,
+        discretionary: Option<bool>,
+        historical: Option<bool>,
+        contextual: Option<bool>,
+    },
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-style
-    pub struct BorderBlockStyle(BorderStyle);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-variant-numeric
+///
+/// The default (all empty) value is `normal`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FontVariantNumeric {
+    pub figure: Option<FontVariantNumericFigure>,
+    pub spacing: Option<FontVariantNumericSpacing>,
+    pub fraction: Option<FontVariantNumericFraction>,
+    pub ordinal: bool,
+    pub slashed_zero: bool,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontVariantNumericFigure {
+    LiningNums,
+    OldstyleNums,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontVariantNumericSpacing {
+    ProportionalNums,
+    TabularNums,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontVariantNumericFraction {
+    DiagonalFractions,
+    StackedFractions,
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-style
-    pub struct BorderInlineStyle(BorderStyle);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-variant-position
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontVariantPosition {
+    Normal,
+    Sub,
+    Super,
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline
-    pub struct BorderInline(Border);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-variation-settings
+///
+/// An empty list is `normal`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontVariationSettings(pub Vec<FontVariationAxisValue>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontVariationAxisValue {
+    pub tag: data_types::OpenTypeTag,
+    pub value: data_types::Number,
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-end
-    pub struct BorderInlineEnd(Border);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/font-weight
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontWeight {
+    Absolute(FontWeightAbsolute),
+    /// One weight class bolder than the inherited weight
+    Bolder,
+    /// One weight class lighter than the inherited weight
+    Lighter,
+}
+impl FontWeight {
+    /// The numeric weight, given the inherited (parent) weight.
+    ///
+    /// https://drafts.csswg.org/css-fonts-4/#relative-weights
+    pub fn resolve(&self, inherited: f32) -> f32 {
+        match self {
+            FontWeight::Absolute(weight) => weight.value(),
+            FontWeight::Bolder if inherited < 350.0 => 400.0,
+            FontWeight::Bolder if inherited < 550.0 => 700.0,
+            FontWeight::Bolder => 900f32.max(inherited),
+            FontWeight::Lighter if inherited < 100.0 => inherited,
+            FontWeight::Lighter if inherited < 550.0 => 100.0,
+            FontWeight::Lighter if inherited < 750.0 => 400.0,
+            FontWeight::Lighter => 700.0,
+        }
+    }
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontWeightAbsolute {
+    /// Same as `400`
+    Normal,
+    /// Same as `700`
+    Bold,
+    /// A weight between 1 and 1000 (inclusive)
+    Number(data_types::Number),
+}
+impl FontWeightAbsolute {
+    /// Returns `None` if `weight` is outside of 1 to 1000
+    pub fn number(weight: f32) -> Option<Self> {
+        (1.0..=1000.0)
+            .contains(&weight)
+            .then_some(FontWeightAbsolute::Number(data_types::Number(weight)))
+    }
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-start
-    pub struct BorderInlineStart(Border);
+    pub fn value(&self) -> f32 {
+        match self {
+            FontWeightAbsolute::Normal => 400.0,
+            FontWeightAbsolute::Bold => 700.0,
+            FontWeightAbsolute::Number(weight) => weight.0,
+        }
+    }
+}
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-color
-    pub struct BorderInlineColor(BorderColor);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-auto-columns
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridAutoColumns(pub Vec<GridTrackSize>);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-auto-rows
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridAutoRows(pub Vec<GridTrackSize>);
 
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-width
-    pub struct BorderBlockWidth {
-        pub bottom: data_types::LineWidth,
-        pub left: data_types::LineWidth,
-        pub right: data_types::LineWidth,
-        pub top: data_types::LineWidth,
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-auto-flow
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridAutoFlow {
+    pub direction: GridAutoFlowDirection,
+    pub dense: bool,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridAutoFlowDirection {
+    Row,
+    Column,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-column-end
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridColumnEnd(pub GridLine);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-column-start
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridColumnStart(pub GridLine);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-row-end
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridRowEnd(pub GridLine);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-row-start
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridRowStart(pub GridLine);
+
+/// A grid placement, as used by `grid-row-start` and friends
+///
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-row-start#values
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridLine {
+    Auto,
+    /// A named area (using its implicit `-start`/`-end` line) or named line
+    Ident(data_types::CustomIdent),
+    /// The nth line (counting from the end if negative, never 0), optionally
+    /// counting only lines with the given name
+    Line {
+        index: data_types::Integer,
+        name: Option<data_types::CustomIdent>,
+    },
+    /// Span a number of tracks (default 1), or until the next line with the
+    /// given name. At least one of `count` or `name` must be present.
+    Span {
+        count: Option<data_types::Integer>,
+        name: Option<data_types::CustomIdent>,
+    },
+}
+impl GridLine {
+    /// The value used for an omitted end line (or column line in
+    /// `grid-area`): the same ident if this line is a [`GridLine::Ident`],
+    /// otherwise `auto`.
+    pub fn implied_end(&self) -> GridLine {
+        match self {
+            GridLine::Ident(ident) => GridLine::Ident(data_types::CustomIdent(ident.0.clone())),
+            _ => GridLine::Auto,
+        }
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-template-areas
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridTemplateAreas {
+    None,
+    Areas(GridAreas),
+}
+
+/// A validated set of rows from `grid-template-areas`, in which every named
+/// area forms a single filled-in rectangle
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridAreas {
+    cells: Vec<Vec<Option<StdString>>>,
+    areas: Vec<GridNamedArea>,
+}
+impl GridAreas {
+    /// Build from the strings of `grid-template-areas`, one per row.
+    ///
+    /// Each string is tokenized into named cells (runs of ident code points)
+    /// and null cells (runs of `.`), separated by whitespace.
+    pub fn new<I, S>(rows: I) -> Result<Self, GridError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut cells: Vec<Vec<_>> = Vec::new();
+        for row in rows {
+            let row = tokenize_grid_area_row(row.as_ref())?;
+            if let Some(first) = cells.first() {
+                if first.len() != row.len() {
+                    return Err(GridError::AreaRowLength {
+                        row: cells.len(),
+                        expected: first.len(),
+                        found: row.len(),
+                    });
+                }
+            }
+            cells.push(row);
+        }
+        if cells.is_empty() || cells[0].is_empty() {
+            return Err(GridError::Empty);
+        }
+
+        let mut areas: Vec<GridNamedArea> = Vec::new();
+        for (r, row) in cells.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                let name = match cell {
+                    Some(name) => name,
+                    None => continue,
+                };
+                let (r, c) = (r as u32 + 1, c as u32 + 1);
+                match areas.iter_mut().find(|area| &area.name == name) {
+                    Some(area) => {
+                        area.row_start = area.row_start.min(r);
+                        area.row_end = area.row_end.max(r + 1);
+                        area.column_start = area.column_start.min(c);
+                        area.column_end = area.column_end.max(c + 1);
+                    }
+                    None => areas.push(GridNamedArea {
+                        name: name.clone(),
+                        row_start: r,
+                        row_end: r + 1,
+                        column_start: c,
+                        column_end: c + 1,
+                    }),
+                }
+            }
+        }
+
+        // Every cell inside an area's bounding box must belong to that area
+        for area in &areas {
+            let rectangular = (area.row_start..area.row_end).all(|r| {
+                (area.column_start..area.column_end)
+                    .all(|c| cells[r as usize - 1][c as usize - 1].as_ref() == Some(&area.name))
+            });
+            if !rectangular {
+                return Err(GridError::NonRectangularArea(area.name.clone()));
+            }
+        }
+
+        Ok(Self { cells, areas })
+    }
+
+    /// The cells of each row, with `None` for null (`.`) cells
+    pub fn cells(&self) -> &[Vec<Option<StdString>>] {
+        &self.cells
+    }
+    pub fn rows(&self) -> usize {
+        self.cells.len()
+    }
+    pub fn columns(&self) -> usize {
+        self.cells[0].len()
+    }
+    /// The named areas, in order of first appearance
+    pub fn areas(&self) -> &[GridNamedArea] {
+        &self.areas
+    }
+    pub fn area(&self, name: &str) -> Option<&GridNamedArea> {
+        self.areas.iter().find(|area| area.name == name)
+    }
+}
+
+/// A named area of [`GridAreas`], bounded by (1-based) grid lines
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridNamedArea {
+    pub name: StdString,
+    pub row_start: u32,
+    pub row_end: u32,
+    pub column_start: u32,
+    pub column_end: u32,
+}
+
+fn tokenize_grid_area_row(row: &str) -> Result<Vec<Option<StdString>>, GridError> {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii();
+
+    let mut cells = Vec::new();
+    let mut chars = row.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '.' {
+            while chars.next_if_eq(&'.').is_some() {}
+            cells.push(None);
+        } else if is_name(c) {
+            let mut name = StdString::new();
+            while let Some(c) = chars.next_if(|&c| is_name(c)) {
+                name.push(c);
+            }
+            cells.push(Some(name));
+        } else {
+            return Err(GridError::InvalidAreaToken(c));
+        }
+    }
+    Ok(cells)
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-template-columns
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridTemplateColumns(pub GridTemplateTracks);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-template-rows
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridTemplateRows(pub GridTemplateTracks);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridTemplateTracks {
+    None,
+    /// A track list, which may contain one `auto-fill`/`auto-fit` repeat
+    TrackList(GridTrackList),
+    /// Adopt the tracks of the parent grid, optionally naming its lines
+    Subgrid(Vec<GridLineNames>),
+}
+
+/// A validated `[<line-names>? [<track-size> | <track-repeat>]]+
+/// <line-names>?`
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridTrackList {
+    tracks: Vec<(GridLineNames, GridTrack)>,
+    trailing_names: GridLineNames,
+}
+impl GridTrackList {
+    /// Check the constraints the grammar can't express with types: `minmax()`
+    /// minimums can't be flexible, repeats can't be empty or repeat zero
+    /// times, and at most one `auto-fill`/`auto-fit` repeat is allowed, in
+    /// which case every track in the list must have a fixed size.
+    pub fn new(
+        tracks: Vec<(GridLineNames, GridTrack)>,
+        trailing_names: GridLineNames,
+    ) -> Result<Self, GridError> {
+        if tracks.is_empty() {
+            return Err(GridError::Empty);
+        }
+
+        let mut auto_repeats = 0;
+        let mut all_fixed = true;
+        for (_, track) in &tracks {
+            match track {
+                GridTrack::Size(size) => {
+                    size.validate()?;
+                    all_fixed &= size.is_fixed();
+                }
+                GridTrack::Repeat(repeat) => {
+                    if repeat.tracks.is_empty() {
+                        return Err(GridError::Empty);
+                    }
+                    if let GridRepeatCount::Count(0) = repeat.count {
+                        return Err(GridError::ZeroRepetitions);
+                    }
+                    if repeat.count.is_auto() {
+                        auto_repeats += 1;
+                    }
+                    for (_, size) in &repeat.tracks {
+                        size.validate()?;
+                        all_fixed &= size.is_fixed();
+                    }
+                }
+            }
+        }
+
+        match auto_repeats {
+            0 => {}
+            1 if all_fixed => {}
+            1 => return Err(GridError::AutoRepeatWithIntrinsicSize),
+            _ => return Err(GridError::MultipleAutoRepeats),
+        }
+        Ok(Self {
+            tracks,
+            trailing_names,
+        })
+    }
+
+    /// The tracks, each with the names of the line before it
+    pub fn tracks(&self) -> &[(GridLineNames, GridTrack)] {
+        &self.tracks
+    }
+    /// The names of the line after the last track
+    pub fn trailing_names(&self) -> &GridLineNames {
+        &self.trailing_names
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/custom-ident#grid_line_names
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridLineNames(pub Vec<data_types::CustomIdent>);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridTrack {
+    Size(GridTrackSize),
+    Repeat(GridTrackRepeat),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/repeat()
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridTrackRepeat {
+    pub count: GridRepeatCount,
+    pub tracks: Vec<(GridLineNames, GridTrackSize)>,
+    pub trailing_names: GridLineNames,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridRepeatCount {
+    Count(u32),
+    /// Repeat as many times as will fit the container
+    AutoFill,
+    /// Like [`GridRepeatCount::AutoFill`], but empty repeated tracks collapse
+    AutoFit,
+}
+impl GridRepeatCount {
+    pub fn is_auto(&self) -> bool {
+        matches!(self, GridRepeatCount::AutoFill | GridRepeatCount::AutoFit)
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-template-columns#values
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridTrackSize {
+    Breadth(GridTrackBreadth),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/minmax()
+    ///
+    /// The minimum may not be flexible.
+    MinMax(GridTrackBreadth, GridTrackBreadth),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/fit-content()
+    FitContent(data_types::LengthPercentage),
+}
+impl GridTrackSize {
+    /// Whether this is a `<fixed-size>`, i.e. at least one of its bounds is a
+    /// length or percentage and neither is flexible where that's disallowed
+    pub fn is_fixed(&self) -> bool {
+        match self {
+            GridTrackSize::Breadth(breadth) => breadth.is_fixed(),
+            GridTrackSize::MinMax(min, max) => {
+                min.is_fixed() || (!min.is_flexible() && max.is_fixed())
+            }
+            GridTrackSize::FitContent(_) => false,
+        }
+    }
+
+    fn validate(&self) -> Result<(), GridError> {
+        match self {
+            GridTrackSize::MinMax(min, _) if min.is_flexible() => Err(GridError::FlexibleMinimum),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridTrackBreadth {
+    LengthPercentage(data_types::LengthPercentage),
+    Flex(data_types::Flex),
+    MinContent,
+    MaxContent,
+    Auto,
+}
+impl GridTrackBreadth {
+    pub fn is_fixed(&self) -> bool {
+        matches!(self, GridTrackBreadth::LengthPercentage(_))
+    }
+    pub fn is_flexible(&self) -> bool {
+        matches!(self, GridTrackBreadth::Flex(_))
+    }
+}
+
+/// Reasons a grid template is invalid
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridError {
+    /// No tracks, rows or columns were given
+    Empty,
+    /// `repeat()` with a count of 0
+    ZeroRepetitions,
+    /// `minmax()` with a flexible (`fr`) minimum
+    FlexibleMinimum,
+    /// More than one `auto-fill`/`auto-fit` repeat in a track list
+    MultipleAutoRepeats,
+    /// A track list with an `auto-fill`/`auto-fit` repeat contains a track
+    /// without a fixed size
+    AutoRepeatWithIntrinsicSize,
+    /// A `grid-template-areas` row has a different number of cells than the
+    /// first row
+    AreaRowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A named area whose cells don't form a rectangle
+    NonRectangularArea(StdString),
+    /// A character that is neither an ident code point, `.` nor whitespace
+    InvalidAreaToken(char),
+}
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "grid template is empty"),
+            GridError::ZeroRepetitions => write!(f, "repeat() count must be positive"),
+            GridError::FlexibleMinimum => write!(f, "minmax() minimum cannot be flexible"),
+            GridError::MultipleAutoRepeats => {
+                write!(f, "only one auto-fill/auto-fit repeat() is allowed")
+            }
+            GridError::AutoRepeatWithIntrinsicSize => write!(
+                f,
+                "track lists with auto-fill/auto-fit repeat() must only contain fixed sizes"
+            ),
+            GridError::AreaRowLength {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "grid area row {} has {} columns, expected {}",
+                row, found, expected
+            ),
+            GridError::NonRectangularArea(name) => {
+                write!(f, "grid area '{}' is not rectangular", name)
+            }
+            GridError::InvalidAreaToken(c) => write!(f, "invalid character '{}' in grid area", c),
+        }
+    }
+}
+impl std::error::Error for GridError {}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/height
+#[derive(Clone, Debug, PartialEq)]
+pub struct Height(pub data_types::WidthValue);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/inline-size
+#[derive(Clone, Debug, PartialEq)]
+pub struct InlineSize(pub data_types::WidthValue);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/hyphens
+#[derive(Clone, Debug, PartialEq)]
+pub enum Hyphens {
+    None,
+    Manual,
+    Auto,
+}
+
+//* Inset
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/top
+#[derive(Clone, Debug, PartialEq)]
+pub struct Top(pub InsetValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/right
+#[derive(Clone, Debug, PartialEq)]
+pub struct Right(pub InsetValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/bottom
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bottom(pub InsetValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/left
+#[derive(Clone, Debug, PartialEq)]
+pub struct Left(pub InsetValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/inset-block-start
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsetBlockStart(pub InsetValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/inset-block-end
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsetBlockEnd(pub InsetValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/inset-inline-start
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsetInlineStart(pub InsetValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/inset-inline-end
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsetInlineEnd(pub InsetValue);
+#[derive(Clone, Debug, PartialEq)]
+pub enum InsetValue {
+    Auto,
+    LengthPercentage(data_types::LengthPercentage),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/justify-content
+///
+/// Baseline values of [`AlignContent`] are not valid here.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JustifyContent {
+    Align(AlignContent),
+    Left,
+    Right,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/justify-items
+#[derive(Clone, Debug, PartialEq)]
+pub enum JustifyItems {
+    Align(AlignItems),
+    Left,
+    Right,
+    /// `legacy`, optionally paired with a position that is inherited by
+    /// descendants
+    Legacy(Option<JustifyItemsLegacy>),
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum JustifyItemsLegacy {
+    Left,
+    Right,
+    Center,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/justify-self
+#[derive(Clone, Debug, PartialEq)]
+pub enum JustifySelf {
+    Align(AlignSelf),
+    Left,
+    Right,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/letter-spacing
+#[derive(Clone, Debug, PartialEq)]
+pub enum LetterSpacing {
+    Normal,
+    LengthPercentage(data_types::LengthPercentage),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/line-height
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineHeight {
+    Normal,
+    /// A multiple of the element's font size
+    Number(data_types::PositiveNumber),
+    LengthPercentage(data_types::LengthPercentage), // TODO: positive
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/margin-top
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarginTop(pub MarginValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/margin-right
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarginRight(pub MarginValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/margin-bottom
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarginBottom(pub MarginValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/margin-left
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarginLeft(pub MarginValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/margin-block-start
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarginBlockStart(pub MarginValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/margin-block-end
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarginBlockEnd(pub MarginValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/margin-inline-start
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarginInlineStart(pub MarginValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/margin-inline-end
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarginInlineEnd(pub MarginValue);
+#[derive(Clone, Debug, PartialEq)]
+pub enum MarginValue {
+    Auto,
+    LengthPercentage(data_types::LengthPercentage),
+}
+
+//* Max/Min Sizes
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/max-block-size
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaxBlockSize(pub data_types::MaxSizeValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/max-height
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaxHeight(pub data_types::MaxSizeValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/max-inline-size
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaxInlineSize(pub data_types::MaxSizeValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/max-width
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaxWidth(pub data_types::MaxSizeValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/min-block-size
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinBlockSize(pub data_types::WidthValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/min-height
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinHeight(pub data_types::WidthValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/min-inline-size
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinInlineSize(pub data_types::WidthValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/min-width
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinWidth(pub data_types::WidthValue);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/order
+#[derive(Clone, Debug, PartialEq)]
+pub struct Order(pub data_types::Integer); // Default 0
+
+//* Overflow
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/overflow-block
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverflowBlock(pub OverflowValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/overflow-inline
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverflowInline(pub OverflowValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/overflow-x
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverflowX(pub OverflowValue);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/overflow-y
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverflowY(pub OverflowValue);
+#[derive(Clone, Debug, PartialEq)]
+pub enum OverflowValue {
+    Visible,
+    Hidden,
+    Clip,
+    Scroll,
+    Auto,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/overflow-clip-margin
+///
+/// At least one of `visual_box` and `margin` must be present.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OverflowClipMargin {
+    pub visual_box: Option<VisualBox>,
+    pub margin: Option<data_types::PositiveLength>,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum VisualBox {
+    ContentBox,
+    PaddingBox,
+    BorderBox,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/overflow-wrap
+#[derive(Clone, Debug, PartialEq)]
+pub enum OverflowWrap {
+    Normal,
+    BreakWord,
+    Anywhere,
+}
+
+//* Padding
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/padding-top
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaddingTop(pub data_types::LengthPercentage); // TODO: positive
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/padding-right
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaddingRight(pub data_types::LengthPercentage);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/padding-bottom
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaddingBottom(pub data_types::LengthPercentage);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/padding-left
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaddingLeft(pub data_types::LengthPercentage);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/padding-block-start
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaddingBlockStart(pub data_types::LengthPercentage);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/padding-block-end
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaddingBlockEnd(pub data_types::LengthPercentage);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/padding-inline-start
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaddingInlineStart(pub data_types::LengthPercentage);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/padding-inline-end
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaddingInlineEnd(pub data_types::LengthPercentage);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/perspective
+///
+/// `None` is the `none` keyword.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Perspective(pub Option<data_types::PositiveLength>);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/perspective-origin
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerspectiveOrigin(pub data_types::Position);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/position
+#[derive(Clone, Debug, PartialEq)]
+pub enum Position {
+    Static,
+    Relative,
+    Absolute,
+    Fixed,
+    Sticky,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/rotate
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rotate {
+    None,
+    /// Rotate around the z axis
+    Angle(data_types::Angle),
+    Axis {
+        axis: RotateAxis,
+        angle: data_types::Angle,
+    },
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum RotateAxis {
+    X,
+    Y,
+    Z,
+    Vector(data_types::Number, data_types::Number, data_types::Number),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/row-gap
+#[derive(Clone, Debug, PartialEq)]
+pub enum RowGap {
+    Normal,
+    LengthPercentage(data_types::LengthPercentage),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/scale
+#[derive(Clone, Debug, PartialEq)]
+pub enum Scale {
+    None,
+    /// `y` defaults to `x` and `z` defaults to 1
+    Value {
+        x: data_types::NumberPercentage,
+        y: Option<data_types::NumberPercentage>,
+        z: Option<data_types::NumberPercentage>,
+    },
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/tab-size
+#[derive(Clone, Debug, PartialEq)]
+pub enum TabSize {
+    /// A multiple of the width of the space character
+    Number(data_types::PositiveNumber),
+    Length(data_types::PositiveLength),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-align
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextAlign {
+    Start,
+    End,
+    Left,
+    Right,
+    Center,
+    Justify,
+    JustifyAll,
+    MatchParent,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-align-last
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextAlignLast {
+    Auto,
+    Start,
+    End,
+    Left,
+    Right,
+    Center,
+    Justify,
+    MatchParent,
+}
+
+//* Text Decoration
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-decoration-color
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextDecorationColor(pub data_types::Color);
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-decoration-line
+///
+/// The default (all `false`) value is `none`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextDecorationLine {
+    pub underline: bool,
+    pub overline: bool,
+    pub line_through: bool,
+    pub blink: bool,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-decoration-style
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextDecorationStyle {
+    Solid,
+    Double,
+    Dotted,
+    Dashed,
+    Wavy,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-decoration-thickness
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextDecorationThickness {
+    Auto,
+    FromFont,
+    LengthPercentage(data_types::LengthPercentage),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-indent
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextIndent {
+    pub value: data_types::LengthPercentage,
+    /// Indent every line except the first
+    pub hanging: bool,
+    /// Also indent lines after forced line breaks
+    pub each_line: bool,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-overflow
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextOverflow {
+    /// A single value, applying to the end of the line
+    End(TextOverflowValue),
+    /// Separate values for the line-left and line-right ends
+    Sides {
+        left: TextOverflowValue,
+        right: TextOverflowValue,
+    },
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextOverflowValue {
+    Clip,
+    Ellipsis,
+    String(data_types::String),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-shadow
+///
+/// An empty list is `none`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextShadow(pub Vec<TextShadowValue>);
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextShadowValue {
+    pub offset_x: data_types::Length,
+    pub offset_y: data_types::Length,
+    pub blur_radius: Option<data_types::Length>, // TODO: positive
+    /// Defaults to `currentcolor`
+    pub color: Option<data_types::Color>,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-transform
+///
+/// The default (all empty) value is `none`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextTransform {
+    pub case: Option<TextTransformCase>,
+    pub full_width: bool,
+    pub full_size_kana: bool,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextTransformCase {
+    Capitalize,
+    Uppercase,
+    Lowercase,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-underline-offset
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextUnderlineOffset {
+    Auto,
+    LengthPercentage(data_types::LengthPercentage),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-wrap-mode
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextWrapMode {
+    Wrap,
+    NoWrap,
+}
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/text-wrap-style
+#[derive(Clone, Debug, PartialEq)]
+pub enum TextWrapStyle {
+    Auto,
+    Balance,
+    Stable,
+    Pretty,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/transform
+///
+/// An empty list is `none`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transform(pub Vec<data_types::TransformFunction>);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-box
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransformBox {
+    ContentBox,
+    BorderBox,
+    FillBox,
+    StrokeBox,
+    ViewBox,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-origin
+///
+/// Keyword offsets (like `left 10px`) aren't valid here.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransformOrigin {
+    pub x: data_types::PositionX,
+    pub y: data_types::PositionY,
+    pub z: data_types::Length,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/transform-style
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransformStyle {
+    Flat,
+    Preserve3d,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/transition-behavior
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionBehavior(pub Vec<TransitionBehaviorValue>);
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionBehaviorValue {
+    Normal,
+    AllowDiscrete,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/transition-delay
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionDelay(pub Vec<data_types::Time>);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/transition-duration
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionDuration(pub Vec<data_types::PositiveTime>);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/transition-property
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransitionProperty {
+    None,
+    Properties(Vec<TransitionPropertyValue>),
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransitionPropertyValue {
+    All,
+    Property(PropertyDiscriminants),
+    /// A name that isn't a known property (including custom properties),
+    /// which still takes up its place in the list
+    Unknown(data_types::CustomIdent),
+}
+impl TransitionPropertyValue {
+    /// Resolve a property name, ignoring ASCII case
+    pub fn from_name(name: &str) -> Self {
+        if name.eq_ignore_ascii_case("all") {
+            return TransitionPropertyValue::All;
+        }
+        match name.to_ascii_lowercase().parse() {
+            Ok(property) => TransitionPropertyValue::Property(property),
+            Err(_) => TransitionPropertyValue::Unknown(data_types::CustomIdent(name.into())),
+        }
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/transition-timing-function
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionTimingFunction(pub Vec<EasingFunction>);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/translate
+#[derive(Clone, Debug, PartialEq)]
+pub enum Translate {
+    None,
+    /// `y` and `z` default to 0
+    Value {
+        x: data_types::LengthPercentage,
+        y: Option<data_types::LengthPercentage>,
+        z: Option<data_types::Length>,
+    },
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/unicode-bidi
+#[derive(Clone, Debug, PartialEq)]
+pub enum UnicodeBidi {
+    Normal,
+    Embed,
+    Isolate,
+    BidiOverride,
+    IsolateOverride,
+    Plaintext,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/visibility
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Visibility {
+    Visible,
+    Hidden,
+    Collapse,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/white-space-collapse
+#[derive(Clone, Debug, PartialEq)]
+pub enum WhiteSpaceCollapse {
+    Collapse,
+    Discard,
+    Preserve,
+    PreserveBreaks,
+    PreserveSpaces,
+    BreakSpaces,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/width
+#[derive(Clone, Debug, PartialEq)]
+pub struct Width(pub data_types::WidthValue);
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/word-break
+#[derive(Clone, Debug, PartialEq)]
+pub enum WordBreak {
+    Normal,
+    BreakAll,
+    KeepAll,
+    AutoPhrase,
+    /// Deprecated, same as `word-break: normal` with `overflow-wrap: anywhere`
+    BreakWord,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/word-spacing
+#[derive(Clone, Debug, PartialEq)]
+pub enum WordSpacing {
+    Normal,
+    LengthPercentage(data_types::LengthPercentage),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/writing-mode
+#[derive(Clone, Debug, PartialEq)]
+pub enum WritingMode {
+    HorizontalTb,
+    VerticalRl,
+    VerticalLr,
+    SidewaysRl,
+    SidewaysLr,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/z-index
+#[derive(Clone, Debug, PartialEq)]
+pub enum ZIndex {
+    Auto,
+    Integer(data_types::Integer),
+}
+
+/// Repeat or truncate the values of a list-valued property to `length`, to
+/// line up with the list that coordinates it (like `transition-property` for
+/// the other `transition-*` longhands)
+///
+/// https://drafts.csswg.org/css-values-4/#linked-properties
+pub fn coordinate_list<T: Clone>(values: &[T], length: usize) -> Vec<T> {
+    values.iter().cycle().take(length).cloned().collect()
+}
+
+pub mod shorthand {
+    use super::*;
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/all
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum All {
+        Initial,
+        Inherit,
+        Unset,
+        Revert,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Animation(pub Vec<SingleAnimation>);
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct SingleAnimation {
+        pub name: Option<AnimationNameValue>,
+        pub duration: Option<data_types::PositiveTime>,
+        pub timing_function: Option<EasingFunction>,
+        pub delay: Option<data_types::Time>,
+        pub iteration_count: Option<AnimationIterationCountValue>,
+        pub direction: Option<AnimationDirectionValue>,
+        pub fill_mode: Option<AnimationFillModeValue>,
+        pub play_state: Option<AnimationPlayStateValue>,
+    }
+    impl Animation {
+        /// Longhands that can't be set by `animation`, but are reset to their
+        /// initial values by it
+        pub const RESET_ONLY: &'static [PropertyDiscriminants] = &[
+            PropertyDiscriminants::AnimationComposition,
+            PropertyDiscriminants::AnimationRangeEnd,
+            PropertyDiscriminants::AnimationRangeStart,
+            PropertyDiscriminants::AnimationTimeline,
+        ];
+
+        /// Omitted values (and the reset-only longhands) take their initial
+        /// values
+        pub fn into_longhands(self) -> AnimationLonghands {
+            let length = self.0.len();
+            let mut longhands = AnimationLonghands {
+                name: AnimationName(Vec::with_capacity(length)),
+                duration: AnimationDuration(Vec::with_capacity(length)),
+                timing_function: AnimationTimingFunction(Vec::with_capacity(length)),
+                delay: AnimationDelay(Vec::with_capacity(length)),
+                iteration_count: AnimationIterationCount(Vec::with_capacity(length)),
+                direction: AnimationDirection(Vec::with_capacity(length)),
+                fill_mode: AnimationFillMode(Vec::with_capacity(length)),
+                play_state: AnimationPlayState(Vec::with_capacity(length)),
+                composition: AnimationComposition(vec![AnimationCompositionValue::Replace; length]),
+                timeline: AnimationTimeline(vec![AnimationTimelineValue::Auto; length]),
+                range_start: AnimationRangeStart(vec![AnimationRangeValue::Normal; length]),
+                range_end: AnimationRangeEnd(vec![AnimationRangeValue::Normal; length]),
+            };
+            for animation in self.0 {
+                longhands
+                    .name
+                    .0
+                    .push(animation.name.unwrap_or(AnimationNameValue::None));
+                longhands
+                    .duration
+                    .0
+                    .push(animation.duration.unwrap_or_else(|| {
+                        data_types::PositiveTime::new(0.0, data_types::TimeUnit::S).unwrap()
+                    }));
+                longhands
+                    .timing_function
+                    .0
+                    .push(animation.timing_function.unwrap_or(EasingFunction::Ease));
+                longhands.delay.0.push(
+                    animation
+                        .delay
+                        .unwrap_or(data_types::Time(0.0, data_types::TimeUnit::S)),
+                );
+                longhands
+                    .iteration_count
+                    .0
+                    .push(animation.iteration_count.unwrap_or(
+                        AnimationIterationCountValue::Finite(data_types::PositiveNumber::ONE),
+                    ));
+                longhands.direction.0.push(
+                    animation
+                        .direction
+                        .unwrap_or(AnimationDirectionValue::Normal),
+                );
+                longhands
+                    .fill_mode
+                    .0
+                    .push(animation.fill_mode.unwrap_or(AnimationFillModeValue::None));
+                longhands.play_state.0.push(
+                    animation
+                        .play_state
+                        .unwrap_or(AnimationPlayStateValue::Running),
+                );
+            }
+            longhands
+        }
+
+        /// The animations described by a set of longhands, coordinated by
+        /// `animation-name` (see [`AnimationLonghands::coordinate`]). The
+        /// reset-only longhands are dropped.
+        pub fn from_longhands(longhands: &AnimationLonghands) -> Self {
+            let longhands = longhands.coordinate();
+            Animation(
+                (0..longhands.animation_count())
+                    .map(|i| SingleAnimation {
+                        name: longhands.name.0.get(i).cloned(),
+                        duration: longhands.duration.0.get(i).cloned(),
+                        timing_function: longhands.timing_function.0.get(i).cloned(),
+                        delay: longhands.delay.0.get(i).cloned(),
+                        iteration_count: longhands.iteration_count.0.get(i).cloned(),
+                        direction: longhands.direction.0.get(i).cloned(),
+                        fill_mode: longhands.fill_mode.0.get(i).cloned(),
+                        play_state: longhands.play_state.0.get(i).cloned(),
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/animation-range
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct AnimationRange(pub Vec<AnimationRangeItem>);
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct AnimationRangeItem {
+        pub start: AnimationRangeValue,
+        pub end: Option<AnimationRangeValue>,
+    }
+    impl AnimationRange {
+        /// An omitted end is the end of the start's named range (if it has
+        /// one), otherwise `normal`
+        pub fn into_longhands(self) -> (AnimationRangeStart, AnimationRangeEnd) {
+            let (starts, ends) = self
+                .0
+                .into_iter()
+                .map(|AnimationRangeItem { start, end }| {
+                    let end = end.unwrap_or(match start {
+                        AnimationRangeValue::Named { name, .. } => AnimationRangeValue::Named {
+                            name,
+                            offset: Some(data_types::LengthPercentage::Percentage(
+                                data_types::Percentage(data_types::Number(100.0)),
+                            )),
+                        },
+                        _ => AnimationRangeValue::Normal,
+                    });
+                    (start, end)
+                })
+                .unzip();
+            (AnimationRangeStart(starts), AnimationRangeEnd(ends))
+        }
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/background
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Background(pub Vec<BackgroundLayer>);
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BackgroundLayer {
+        // TODO: at least one?
+        pub attachment: Option<BackgroundLayerAttachment>,
+        pub clip: Option<BackgroundLayerClip>,
+        pub color: Option<BackgroundLayerColor>,
+        pub image: Option<BackgroundLayerImage>,
+        pub origin: Option<BackgroundLayerOrigin>,
+        pub position: Option<BackgroundLayerPosition>,
+        pub repeat: Option<BackgroundLayerRepeat>,
+        pub size: Option<BackgroundLayerSize>,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Border {
+        pub color: Option<data_types::Color>,
+        pub style: Option<data_types::LineStyle>,
+        pub width: Option<data_types::LineWidth>,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-style
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderStyle {
+        pub top: data_types::LineStyle,
+        pub left: data_types::LineStyle,
+        pub bottom: data_types::LineStyle,
+        pub right: data_types::LineStyle,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-color
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderColor {
+        pub top: data_types::Color,
+        pub left: data_types::Color,
+        pub bottom: data_types::Color,
+        pub right: data_types::Color,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-width
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderWidth {
+        pub top: data_types::LineWidth,
+        pub left: data_types::LineWidth,
+        pub bottom: data_types::LineWidth,
+        pub right: data_types::LineWidth,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-radius
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderRadius {
+        // TODO
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderBlock(pub Border);
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-end
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderBlockEnd(pub Border);
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-start
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderBlockStart(pub Border);
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-bottom
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderBottom(pub Border);
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-left
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderLeft(pub Border);
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-right
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderRight(pub Border);
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-top
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderTop(pub Border);
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-style
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderBlockStyle(pub BorderStyle);
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-style
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderInlineStyle(pub BorderStyle);
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderInline(pub Border);
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-end
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderInlineEnd(pub Border);
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-start
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderInlineStart(pub Border);
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-inline-color
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderInlineColor(pub BorderColor);
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-block-width
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct BorderBlockWidth {
+        pub bottom: data_types::LineWidth,
+        pub left: data_types::LineWidth,
+        pub right: data_types::LineWidth,
+        pub top: data_types::LineWidth,
     }
 
     /// https://developer.mozilla.org/en-US/docs/Web/CSS/border-image
+    #[derive(Clone, Debug, PartialEq)]
     pub struct BorderImage {
         pub outset: BorderImageOutset,
         pub repeat: BorderImageRepeat,
@@ -547,4 +3061,1197 @@ pub mod shorthand {
         pub source: BorderImageSource,
         pub width: BorderImageWidth,
     }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/flex
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Flex {
+        /// Equivalent to `0 0 auto`
+        None,
+        /// Equivalent to `1 1 auto`
+        Auto,
+        /// `<'flex-grow'> <'flex-shrink'>? || <'flex-basis'>`
+        ///
+        /// Omitted values are not the longhand initial values: see
+        /// [`Flex::into_longhands`].
+        Values {
+            grow: Option<FlexGrow>,
+            shrink: Option<FlexShrink>,
+            basis: Option<FlexBasis>,
+        },
+    }
+    impl Flex {
+        /// `flex: <number>`, equivalent to `<number> 1 0`
+        pub fn number(grow: FlexGrow) -> Self {
+            Flex::Values {
+                grow: Some(grow),
+                shrink: None,
+                basis: None,
+            }
+        }
+
+        /// Expand into the `flex-grow`, `flex-shrink` and `flex-basis`
+        /// longhands.
+        ///
+        /// An omitted grow or shrink factor becomes `1`, and an omitted basis
+        /// becomes `0` (rather than the `auto` initial value of `flex-basis`).
+        pub fn into_longhands(self) -> (FlexGrow, FlexShrink, FlexBasis) {
+            use data_types::{Number, Percentage, PositiveNumber, WidthValue};
+            match self {
+                Flex::None => (
+                    FlexGrow(PositiveNumber::ZERO),
+                    FlexShrink(PositiveNumber::ZERO),
+                    FlexBasis::Width(WidthValue::Auto),
+                ),
+                Flex::Auto => (
+                    FlexGrow(PositiveNumber::ONE),
+                    FlexShrink(PositiveNumber::ONE),
+                    FlexBasis::Width(WidthValue::Auto),
+                ),
+                Flex::Values {
+                    grow,
+                    shrink,
+                    basis,
+                } => (
+                    grow.unwrap_or(FlexGrow(PositiveNumber::ONE)),
+                    shrink.unwrap_or(FlexShrink(PositiveNumber::ONE)),
+                    basis.unwrap_or(FlexBasis::Width(WidthValue::Percentage(Percentage(
+                        Number(0.0),
+                    )))),
+                ),
+            }
+        }
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/flex-flow
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct FlexFlow {
+        pub direction: Option<FlexDirection>,
+        pub wrap: Option<FlexWrap>,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/font
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Font {
+        Values {
+            style: Option<FontStyle>,
+            /// The CSS 2.1 `small-caps` variant (rather than `normal`)
+            small_caps: bool,
+            weight: Option<FontWeight>,
+            /// Only keywords are allowed in the shorthand
+            stretch: Option<FontStretchKeyword>,
+            size: FontSize,
+            line_height: Option<LineHeight>,
+            family: FontFamily,
+        },
+        /// Use the font of a system UI element
+        System(SystemFont),
+    }
+    impl Font {
+        /// Longhands that can't be set by `font`, but are reset to their
+        /// initial values by it
+        pub const RESET_ONLY: &'static [PropertyDiscriminants] = &[
+            PropertyDiscriminants::FontFeatureSettings,
+            PropertyDiscriminants::FontKerning,
+            PropertyDiscriminants::FontLanguageOverride,
+            PropertyDiscriminants::FontOpticalSizing,
+            PropertyDiscriminants::FontPalette,
+            PropertyDiscriminants::FontSizeAdjust,
+            PropertyDiscriminants::FontVariantAlternates,
+            PropertyDiscriminants::FontVariantEastAsian,
+            PropertyDiscriminants::FontVariantEmoji,
+            PropertyDiscriminants::FontVariantLigatures,
+            PropertyDiscriminants::FontVariantNumeric,
+            PropertyDiscriminants::FontVariantPosition,
+            PropertyDiscriminants::FontVariationSettings,
+        ];
+    }
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/font#values
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum SystemFont {
+        Caption,
+        Icon,
+        Menu,
+        MessageBox,
+        SmallCaption,
+        StatusBar,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/font-variant
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum FontVariant {
+        /// Resets every `font-variant-*` longhand
+        Normal,
+        /// Sets `font-variant-ligatures` to `none` and resets the others
+        None,
+        Values {
+            ligatures: Option<FontVariantLigatures>,
+            caps: Option<FontVariantCaps>,
+            alternates: Option<FontVariantAlternates>,
+            numeric: Option<FontVariantNumeric>,
+            east_asian: Option<FontVariantEastAsian>,
+            position: Option<FontVariantPosition>,
+            emoji: Option<FontVariantEmoji>,
+        },
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/gap
+    ///
+    /// A single value sets both the row and column gap.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Gap {
+        pub row: RowGap,
+        pub column: ColumnGap,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/grid
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Grid {
+        Template(GridTemplate),
+        /// `<'grid-template-rows'> / auto-flow dense? <'grid-auto-columns'>?`
+        AutoFlowColumns {
+            rows: GridTemplateRows,
+            dense: bool,
+            auto_columns: Option<GridAutoColumns>,
+        },
+        /// `auto-flow dense? <'grid-auto-rows'>? / <'grid-template-columns'>`
+        AutoFlowRows {
+            dense: bool,
+            auto_rows: Option<GridAutoRows>,
+            columns: GridTemplateColumns,
+        },
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-area
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct GridArea {
+        pub row_start: GridLine,
+        pub column_start: Option<GridLine>,
+        pub row_end: Option<GridLine>,
+        pub column_end: Option<GridLine>,
+    }
+    impl GridArea {
+        /// Expand into `(grid-row-start, grid-column-start, grid-row-end,
+        /// grid-column-end)`, filling omitted lines per
+        /// [`GridLine::implied_end`]
+        pub fn into_longhands(self) -> (GridRowStart, GridColumnStart, GridRowEnd, GridColumnEnd) {
+            let GridArea {
+                row_start,
+                column_start,
+                row_end,
+                column_end,
+            } = self;
+            let column_start = column_start.unwrap_or_else(|| row_start.implied_end());
+            let row_end = row_end.unwrap_or_else(|| row_start.implied_end());
+            let column_end = column_end.unwrap_or_else(|| column_start.implied_end());
+            (
+                GridRowStart(row_start),
+                GridColumnStart(column_start),
+                GridRowEnd(row_end),
+                GridColumnEnd(column_end),
+            )
+        }
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-column
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct GridColumn {
+        pub start: GridLine,
+        pub end: Option<GridLine>,
+    }
+    impl GridColumn {
+        pub fn into_longhands(self) -> (GridColumnStart, GridColumnEnd) {
+            let (start, end) = (self.start, self.end);
+            let end = end.unwrap_or_else(|| start.implied_end());
+            (GridColumnStart(start), GridColumnEnd(end))
+        }
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-row
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct GridRow {
+        pub start: GridLine,
+        pub end: Option<GridLine>,
+    }
+    impl GridRow {
+        pub fn into_longhands(self) -> (GridRowStart, GridRowEnd) {
+            let (start, end) = (self.start, self.end);
+            let end = end.unwrap_or_else(|| start.implied_end());
+            (GridRowStart(start), GridRowEnd(end))
+        }
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/grid-template
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum GridTemplate {
+        None,
+        /// `<'grid-template-rows'> / <'grid-template-columns'>`
+        RowsColumns {
+            rows: GridTemplateRows,
+            columns: GridTemplateColumns,
+        },
+        /// `[<line-names>? <string> <track-size>? <line-names>?]+ [/
+        /// <explicit-track-list>]?`
+        ///
+        /// The column track list may not contain `auto-fill`/`auto-fit`
+        /// repeats.
+        Areas {
+            rows: Vec<GridTemplateAreaRow>,
+            columns: Option<GridTrackList>,
+        },
+    }
+    impl GridTemplate {
+        /// The `grid-template-areas` set by this shorthand
+        pub fn template_areas(&self) -> Result<GridTemplateAreas, GridError> {
+            match self {
+                GridTemplate::Areas { rows, .. } => Ok(GridTemplateAreas::Areas(GridAreas::new(
+                    rows.iter().map(|row| row.areas.0.as_str()),
+                )?)),
+                _ => Ok(GridTemplateAreas::None),
+            }
+        }
+    }
+    /// A single row of [`GridTemplate::Areas`]
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct GridTemplateAreaRow {
+        pub leading_names: GridLineNames,
+        pub areas: data_types::String,
+        pub size: Option<GridTrackSize>,
+        pub trailing_names: GridLineNames,
+    }
+
+    /// Expand the 1 to 4 values of a box shorthand (like `margin`) into
+    /// `(top, right, bottom, left)`. An omitted right copies the top, an
+    /// omitted bottom copies the top and an omitted left copies the right.
+    ///
+    /// Returns `None` if a value is given without the ones before it (like a
+    /// bottom without a right), which the grammar doesn't allow.
+    pub fn expand_box_values<T: Clone>(
+        top: T,
+        right: Option<T>,
+        bottom: Option<T>,
+        left: Option<T>,
+    ) -> Option<(T, T, T, T)> {
+        if (right.is_none() && bottom.is_some()) || (bottom.is_none() && left.is_some()) {
+            return None;
+        }
+        let right = right.unwrap_or_else(|| top.clone());
+        let bottom = bottom.unwrap_or_else(|| top.clone());
+        let left = left.unwrap_or_else(|| right.clone());
+        Some((top, right, bottom, left))
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/inset
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Inset {
+        pub top: InsetValue,
+        pub right: InsetValue,
+        pub bottom: InsetValue,
+        pub left: InsetValue,
+    }
+    impl Inset {
+        /// `inset: <top> [<right> [<bottom> [<left>]]]`, or `None` if a value
+        /// is given without the ones before it
+        pub fn from_values(
+            top: InsetValue,
+            right: Option<InsetValue>,
+            bottom: Option<InsetValue>,
+            left: Option<InsetValue>,
+        ) -> Option<Self> {
+            let (top, right, bottom, left) = expand_box_values(top, right, bottom, left)?;
+            Some(Self {
+                top,
+                right,
+                bottom,
+                left,
+            })
+        }
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/inset-block
+    ///
+    /// A single value sets both start and end.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct InsetBlock {
+        pub start: InsetValue,
+        pub end: InsetValue,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/inset-inline
+    ///
+    /// A single value sets both start and end.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct InsetInline {
+        pub start: InsetValue,
+        pub end: InsetValue,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/margin
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Margin {
+        pub top: MarginValue,
+        pub right: MarginValue,
+        pub bottom: MarginValue,
+        pub left: MarginValue,
+    }
+    impl Margin {
+        /// `margin: <top> [<right> [<bottom> [<left>]]]`, or `None` if a value
+        /// is given without the ones before it
+        pub fn from_values(
+            top: MarginValue,
+            right: Option<MarginValue>,
+            bottom: Option<MarginValue>,
+            left: Option<MarginValue>,
+        ) -> Option<Self> {
+            let (top, right, bottom, left) = expand_box_values(top, right, bottom, left)?;
+            Some(Self {
+                top,
+                right,
+                bottom,
+                left,
+            })
+        }
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/margin-block
+    ///
+    /// A single value sets both start and end.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct MarginBlock {
+        pub start: MarginValue,
+        pub end: MarginValue,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/margin-inline
+    ///
+    /// A single value sets both start and end.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct MarginInline {
+        pub start: MarginValue,
+        pub end: MarginValue,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/overflow
+    ///
+    /// A single value sets both axes.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Overflow {
+        pub x: OverflowValue,
+        pub y: OverflowValue,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/padding
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Padding {
+        pub top: data_types::LengthPercentage,
+        pub right: data_types::LengthPercentage,
+        pub bottom: data_types::LengthPercentage,
+        pub left: data_types::LengthPercentage,
+    }
+    impl Padding {
+        /// `padding: <top> [<right> [<bottom> [<left>]]]`, or `None` if a value
+        /// is given without the ones before it
+        pub fn from_values(
+            top: data_types::LengthPercentage,
+            right: Option<data_types::LengthPercentage>,
+            bottom: Option<data_types::LengthPercentage>,
+            left: Option<data_types::LengthPercentage>,
+        ) -> Option<Self> {
+            let (top, right, bottom, left) = expand_box_values(top, right, bottom, left)?;
+            Some(Self {
+                top,
+                right,
+                bottom,
+                left,
+            })
+        }
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/padding-block
+    ///
+    /// A single value sets both start and end.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct PaddingBlock {
+        pub start: data_types::LengthPercentage,
+        pub end: data_types::LengthPercentage,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/padding-inline
+    ///
+    /// A single value sets both start and end.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct PaddingInline {
+        pub start: data_types::LengthPercentage,
+        pub end: data_types::LengthPercentage,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/text-decoration
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct TextDecoration {
+        pub line: Option<TextDecorationLine>,
+        pub style: Option<TextDecorationStyle>,
+        pub color: Option<data_types::Color>,
+        pub thickness: Option<TextDecorationThickness>,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/text-wrap
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct TextWrap {
+        pub mode: Option<TextWrapMode>,
+        pub style: Option<TextWrapStyle>,
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/transition
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Transition(pub Vec<SingleTransition>);
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct SingleTransition {
+        /// `None` is the `none` keyword, which is only valid in a single
+        /// transition. An omitted property isn't `None` but
+        /// `Some(TransitionPropertyValue::All)`, its initial value.
+        pub property: Option<TransitionPropertyValue>,
+        pub duration: Option<data_types::PositiveTime>,
+        pub timing_function: Option<EasingFunction>,
+        pub delay: Option<data_types::Time>,
+        pub behavior: Option<TransitionBehaviorValue>,
+    }
+    impl Transition {
+        /// Omitted values take the initial value of their longhand
+        /// (`all 0s ease 0s normal`)
+        pub fn into_longhands(
+            self,
+        ) -> (
+            TransitionProperty,
+            TransitionDuration,
+            TransitionTimingFunction,
+            TransitionDelay,
+            TransitionBehavior,
+        ) {
+            let mut properties = Some(Vec::new());
+            let mut durations = Vec::new();
+            let mut timing_functions = Vec::new();
+            let mut delays = Vec::new();
+            let mut behaviors = Vec::new();
+            for transition in self.0 {
+                match (transition.property, &mut properties) {
+                    (Some(property), Some(properties)) => properties.push(property),
+                    _ => properties = None,
+                }
+                durations.push(transition.duration.unwrap_or_else(|| {
+                    data_types::PositiveTime::new(0.0, data_types::TimeUnit::S).unwrap()
+                }));
+                timing_functions.push(transition.timing_function.unwrap_or(EasingFunction::Ease));
+                delays.push(
+                    transition
+                        .delay
+                        .unwrap_or(data_types::Time(0.0, data_types::TimeUnit::S)),
+                );
+                behaviors.push(
+                    transition
+                        .behavior
+                        .unwrap_or(TransitionBehaviorValue::Normal),
+                );
+            }
+            (
+                properties.map_or(TransitionProperty::None, TransitionProperty::Properties),
+                TransitionDuration(durations),
+                TransitionTimingFunction(timing_functions),
+                TransitionDelay(delays),
+                TransitionBehavior(behaviors),
+            )
+        }
+
+        /// The transitions described by a set of longhands. Their lists are
+        /// coordinated by `transition-property`, so the other lists are
+        /// repeated or truncated to its length (see [`coordinate_list`]).
+        pub fn from_longhands(
+            property: &TransitionProperty,
+            duration: &TransitionDuration,
+            timing_function: &TransitionTimingFunction,
+            delay: &TransitionDelay,
+            behavior: &TransitionBehavior,
+        ) -> Self {
+            let properties = match property {
+                TransitionProperty::None => vec![None],
+                TransitionProperty::Properties(properties) => {
+                    properties.iter().cloned().map(Some).collect()
+                }
+            };
+            let length = properties.len();
+            let durations = coordinate_list(&duration.0, length);
+            let timing_functions = coordinate_list(&timing_function.0, length);
+            let delays = coordinate_list(&delay.0, length);
+            let behaviors = coordinate_list(&behavior.0, length);
+            Transition(
+                properties
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, property)| SingleTransition {
+                        property,
+                        duration: durations.get(i).cloned(),
+                        timing_function: timing_functions.get(i).cloned(),
+                        delay: delays.get(i).cloned(),
+                        behavior: behaviors.get(i).cloned(),
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/white-space
+    ///
+    /// A shorthand for `white-space-collapse` and `text-wrap-mode` since CSS
+    /// Text 4.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum WhiteSpace {
+        Normal,
+        Pre,
+        PreWrap,
+        PreLine,
+        /// `<'white-space-collapse'> || <'text-wrap-mode'>`, which also covers
+        /// the `nowrap` and `break-spaces` keywords
+        Values {
+            collapse: Option<WhiteSpaceCollapse>,
+            wrap_mode: Option<TextWrapMode>,
+        },
+    }
+    impl WhiteSpace {
+        pub fn into_longhands(self) -> (WhiteSpaceCollapse, TextWrapMode) {
+            match self {
+                WhiteSpace::Normal => (WhiteSpaceCollapse::Collapse, TextWrapMode::Wrap),
+                WhiteSpace::Pre => (WhiteSpaceCollapse::Preserve, TextWrapMode::NoWrap),
+                WhiteSpace::PreWrap => (WhiteSpaceCollapse::Preserve, TextWrapMode::Wrap),
+                WhiteSpace::PreLine => (WhiteSpaceCollapse::PreserveBreaks, TextWrapMode::Wrap),
+                WhiteSpace::Values {
+                    collapse,
+                    wrap_mode,
+                } => (
+                    collapse.unwrap_or(WhiteSpaceCollapse::Collapse),
+                    wrap_mode.unwrap_or(TextWrapMode::Wrap),
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn steps() {
+        let steps = EasingFunction::Steps(4, EasingStepsJump::End);
+        assert_eq!(steps.evaluate(0.0), 0.0);
+        assert_eq!(steps.evaluate(0.3), 0.25);
+        assert_eq!(steps.evaluate(1.0), 1.0);
+
+        let steps = EasingFunction::Steps(2, EasingStepsJump::Both);
+        assert_close(steps.evaluate(0.0), 1.0 / 3.0);
+        assert_close(steps.evaluate(0.5), 2.0 / 3.0);
+
+        let steps = EasingFunction::Steps(3, EasingStepsJump::None);
+        assert_eq!(steps.evaluate(0.0), 0.0);
+        assert_eq!(steps.evaluate(0.5), 0.5);
+        assert_eq!(steps.evaluate(1.0), 1.0);
+    }
+
+    #[test]
+    fn steps_before_flag() {
+        // At a step boundary in the before phase, the output is the step before
+        let steps = EasingFunction::Steps(1, EasingStepsJump::Start);
+        assert_eq!(steps.evaluate_with_before_flag(0.0, false), 1.0);
+        assert_eq!(steps.evaluate_with_before_flag(0.0, true), 0.0);
+        let steps = EasingFunction::Steps(4, EasingStepsJump::End);
+        assert_eq!(steps.evaluate_with_before_flag(0.5, true), 0.25);
+        // Only boundaries are affected
+        assert_eq!(steps.evaluate_with_before_flag(0.6, true), 0.5);
+    }
+
+    #[test]
+    fn invalid_steps() {
+        for steps in [
+            EasingFunction::Steps(0, EasingStepsJump::End),
+            EasingFunction::Steps(0, EasingStepsJump::None),
+            EasingFunction::Steps(1, EasingStepsJump::None),
+        ] {
+            for t in [-0.5, 0.0, 0.5, 1.0, 1.5] {
+                assert!(!steps.evaluate(t).is_nan(), "{:?} at {}", steps, t);
+            }
+        }
+        assert_eq!(
+            EasingFunction::Steps(1, EasingStepsJump::None).evaluate(0.6),
+            EasingFunction::Steps(2, EasingStepsJump::None).evaluate(0.6)
+        );
+    }
+
+    #[test]
+    fn cubic_bezier_output() {
+        assert_eq!(EasingFunction::Ease.evaluate(0.0), 0.0);
+        assert_close(EasingFunction::Ease.evaluate(1.0), 1.0);
+        assert_close(EasingFunction::Ease.evaluate(0.5), 0.8024033877399112);
+        assert_close(EasingFunction::EaseInOut.evaluate(0.5), 0.5);
+        // Equal control points are linear
+        let linear = EasingFunction::CubicBezier(0.3, 0.3, 0.7, 0.7);
+        assert_close(linear.evaluate(0.25), 0.25);
+    }
+
+    #[test]
+    fn cubic_bezier_extrapolation() {
+        // Along the P1-P0 and P2-P3 tangents
+        let curve = EasingFunction::CubicBezier(0.5, 1.0, 0.5, 0.0);
+        assert_close(curve.evaluate(-1.0), -2.0);
+        assert_close(curve.evaluate(2.0), 3.0);
+        // x1 = 0 uses the P2-P0 tangent, and x2 = 1 the P1-P3 tangent, even
+        // when y1 isn't 0 and y2 isn't 1
+        let curve = EasingFunction::CubicBezier(0.0, 0.5, 0.5, 1.0);
+        assert_close(curve.evaluate(-1.0), -2.0);
+        let curve = EasingFunction::CubicBezier(0.5, 0.0, 1.0, 0.5);
+        assert_close(curve.evaluate(2.0), 3.0);
+        // Without either, the output stays at 0 or 1
+        let curve = EasingFunction::CubicBezier(0.0, 0.5, 0.0, 0.5);
+        assert_eq!(curve.evaluate(-1.0), 0.0);
+        let curve = EasingFunction::CubicBezier(1.0, 0.5, 1.0, 0.5);
+        assert_eq!(curve.evaluate(2.0), 1.0);
+    }
+
+    fn stop(output: f32, input: Option<f32>) -> LinearEasingStop {
+        LinearEasingStop {
+            output: data_types::Number(output),
+            input: input.map(|input| data_types::Percentage(data_types::Number(input))),
+        }
+    }
+
+    #[test]
+    fn linear_canonicalization() {
+        // Missing inputs default to 0% and 100% at the ends, and are spaced
+        // evenly between their neighbours
+        let stops = [
+            stop(0.0, None),
+            stop(0.5, None),
+            stop(0.6, None),
+            stop(1.0, None),
+        ];
+        let inputs: Vec<_> = linear_function_points(&stops)
+            .into_iter()
+            .map(|(input, _)| input)
+            .collect();
+        assert_eq!(inputs.len(), 4);
+        assert_close(inputs[1], 1.0 / 3.0);
+        assert_close(inputs[2], 2.0 / 3.0);
+        assert_eq!(inputs[3], 1.0);
+
+        // Inputs can't go backwards
+        let stops = [
+            stop(0.0, None),
+            stop(0.5, Some(60.0)),
+            stop(0.7, Some(20.0)),
+            stop(1.0, None),
+        ];
+        let points = linear_function_points(&stops);
+        assert_close(points[2].0, 0.6);
+    }
+
+    #[test]
+    fn linear_function_output() {
+        let easing = EasingFunction::LinearFunction(vec![
+            stop(0.0, None),
+            stop(0.8, Some(50.0)),
+            stop(1.0, None),
+        ]);
+        assert_close(easing.evaluate(0.25), 0.4);
+        assert_close(easing.evaluate(0.75), 0.9);
+        // Extrapolated from the first and last pairs
+        assert_close(easing.evaluate(-0.5), -0.8);
+        assert_close(easing.evaluate(1.5), 1.2);
+        // Two stops at the same input jump to the later one
+        let easing = EasingFunction::LinearFunction(vec![
+            stop(0.0, None),
+            stop(0.0, Some(50.0)),
+            stop(1.0, Some(50.0)),
+            stop(1.0, None),
+        ]);
+        assert_eq!(easing.evaluate(0.5), 1.0);
+        assert_eq!(easing.evaluate(0.4), 0.0);
+    }
+
+    #[test]
+    fn font_variant_alternates() {
+        let family = FamilyName::Quoted(data_types::String("A".to_owned()));
+        let ident = |name: &str| data_types::CustomIdent(name.to_owned());
+        let mut rule = FontFeatureValues::new(vec![family.clone()]);
+        rule.insert(FeatureValueBlock::Stylistic, ident("alt"), vec![2]);
+        rule.insert(FeatureValueBlock::Styleset, ident("sets"), vec![3, 21, 12]);
+        rule.insert(FeatureValueBlock::CharacterVariant, ident("g"), vec![4]);
+        rule.insert(FeatureValueBlock::CharacterVariant, ident("a"), vec![11, 3]);
+        rule.insert(FeatureValueBlock::Swash, ident("fancy"), vec![0]);
+        rule.insert(FeatureValueBlock::Ornaments, ident("leaf"), vec![5]);
+        rule.insert(FeatureValueBlock::Annotation, ident("circled"), vec![1]);
+        let rules = [rule];
+
+        let alternates = FontVariantAlternates {
+            stylistic: Some(ident("alt")),
+            historical_forms: true,
+            styleset: vec![ident("sets"), ident("undefined")],
+            character_variant: vec![ident("g"), ident("a")],
+            swash: Some(ident("fancy")),
+            ornaments: Some(ident("leaf")),
+            annotation: Some(ident("circled")),
+        };
+        let features: Vec<_> = alternates
+            .feature_settings(&family, &rules)
+            .into_iter()
+            .map(|feature| (feature.tag, feature.value))
+            .collect();
+        let tag = |tag| data_types::OpenTypeTag::new(tag).unwrap();
+        assert_eq!(
+            features,
+            vec![
+                (tag("salt"), 2),
+                (tag("hist"), 1),
+                // Style sets above 20 have no effect
+                (tag("ss03"), 1),
+                (tag("ss12"), 1),
+                (tag("cv04"), 1),
+                (tag("cv11"), 3),
+                (tag("swsh"), 0),
+                (tag("cswh"), 0),
+                (tag("ornm"), 5),
+                (tag("nalt"), 1),
+            ]
+        );
+
+        // Names aren't defined for other families
+        let other = FamilyName::Quoted(data_types::String("B".to_owned()));
+        assert_eq!(
+            alternates.feature_settings(&other, &rules),
+            vec![FontFeatureTagValue {
+                tag: tag("hist"),
+                value: 1
+            }]
+        );
+        assert!(FontVariantAlternates::default()
+            .feature_settings(&family, &rules)
+            .is_empty());
+    }
+
+    fn seconds(value: f32) -> data_types::PositiveTime {
+        data_types::PositiveTime::new(value, data_types::TimeUnit::S).unwrap()
+    }
+
+    fn named(name: &str) -> shorthand::SingleAnimation {
+        shorthand::SingleAnimation {
+            name: Some(AnimationNameValue::Custom(data_types::CustomIdent(
+                name.to_owned(),
+            ))),
+            duration: None,
+            timing_function: None,
+            delay: None,
+            iteration_count: None,
+            direction: None,
+            fill_mode: None,
+            play_state: None,
+        }
+    }
+
+    #[test]
+    fn animation_coordination() {
+        let mut longhands = shorthand::Animation(vec![named("a"), named("b")]).into_longhands();
+        // Longer lists are truncated and shorter lists repeat
+        longhands.duration = AnimationDuration(vec![seconds(1.0), seconds(2.0), seconds(3.0)]);
+        longhands.direction = AnimationDirection(vec![AnimationDirectionValue::Reverse]);
+        let coordinated = longhands.coordinate();
+        assert_eq!(coordinated.animation_count(), 2);
+        assert_eq!(coordinated.duration.0, [seconds(1.0), seconds(2.0)]);
+        assert_eq!(
+            coordinated.direction.0,
+            [AnimationDirectionValue::Reverse; 2]
+        );
+        assert_eq!(coordinated.fill_mode.0.len(), 2);
+        assert_eq!(coordinated.name, longhands.name);
+
+        let animation = shorthand::Animation::from_longhands(&longhands);
+        assert_eq!(animation.0.len(), 2);
+        assert_eq!(animation.0[1].duration, Some(seconds(2.0)));
+        assert_eq!(
+            animation.0[1].direction,
+            Some(AnimationDirectionValue::Reverse)
+        );
+    }
+
+    #[test]
+    fn animation_longhands_round_trip() {
+        let animation = shorthand::Animation(vec![
+            shorthand::SingleAnimation {
+                name: Some(AnimationNameValue::None),
+                duration: Some(seconds(1.0)),
+                timing_function: Some(EasingFunction::EaseIn),
+                delay: Some(data_types::Time(-1.0, data_types::TimeUnit::S)),
+                iteration_count: Some(AnimationIterationCountValue::Infinite),
+                direction: Some(AnimationDirectionValue::Alternate),
+                fill_mode: Some(AnimationFillModeValue::Both),
+                play_state: Some(AnimationPlayStateValue::Paused),
+            },
+            named("slide"),
+        ]);
+        let longhands = animation.clone().into_longhands();
+        // Omitted values and the reset-only longhands take their initial values
+        assert_eq!(longhands.duration.0[1], seconds(0.0));
+        assert_eq!(longhands.timing_function.0[1], EasingFunction::Ease);
+        assert_eq!(
+            longhands.composition.0,
+            [AnimationCompositionValue::Replace; 2]
+        );
+        assert_eq!(longhands.timeline.0, vec![AnimationTimelineValue::Auto; 2]);
+
+        let round_trip = shorthand::Animation::from_longhands(&longhands);
+        assert_eq!(round_trip.0[0], animation.0[0]);
+        assert_eq!(round_trip.0[1].name, animation.0[1].name);
+        assert_eq!(round_trip.clone().into_longhands(), longhands);
+    }
+
+    #[test]
+    fn grid_areas() {
+        let areas = GridAreas::new(["head head", "nav  main", ".    main"]).unwrap();
+        assert_eq!((areas.rows(), areas.columns()), (3, 2));
+        assert_eq!(areas.cells()[2][0], None);
+        assert_eq!(
+            areas.area("main"),
+            Some(&GridNamedArea {
+                name: "main".to_owned(),
+                row_start: 2,
+                row_end: 4,
+                column_start: 2,
+                column_end: 3,
+            })
+        );
+        let names: Vec<_> = areas.areas().iter().map(|area| &area.name[..]).collect();
+        assert_eq!(names, ["head", "nav", "main"]);
+        // Runs of `.` are a single null cell
+        assert_eq!(GridAreas::new(["a ... b"]).unwrap().columns(), 3);
+    }
+
+    #[test]
+    fn invalid_grid_areas() {
+        assert_eq!(
+            GridAreas::new(["a a", "a b"]),
+            Err(GridError::NonRectangularArea("a".to_owned()))
+        );
+        assert_eq!(
+            GridAreas::new(["a b a"]),
+            Err(GridError::NonRectangularArea("a".to_owned()))
+        );
+        assert_eq!(
+            GridAreas::new(["a b", "c", "d e"]),
+            Err(GridError::AreaRowLength {
+                row: 1,
+                expected: 2,
+                found: 1,
+            })
+        );
+        assert_eq!(GridAreas::new(["   "]), Err(GridError::Empty));
+        assert_eq!(GridAreas::new(Vec::<&str>::new()), Err(GridError::Empty));
+        assert_eq!(
+            GridAreas::new(["a # b"]),
+            Err(GridError::InvalidAreaToken('#'))
+        );
+    }
+
+    fn px(value: f32) -> GridTrackBreadth {
+        GridTrackBreadth::LengthPercentage(data_types::LengthPercentage::Length(
+            data_types::Length(value, data_types::LengthUnit::Px),
+        ))
+    }
+
+    fn fr(value: f32) -> GridTrackBreadth {
+        GridTrackBreadth::Flex(data_types::Flex(data_types::Number(value)))
+    }
+
+    fn repeat(count: GridRepeatCount, sizes: Vec<GridTrackSize>) -> GridTrack {
+        GridTrack::Repeat(GridTrackRepeat {
+            count,
+            tracks: sizes
+                .into_iter()
+                .map(|size| (GridLineNames(Vec::new()), size))
+                .collect(),
+            trailing_names: GridLineNames(Vec::new()),
+        })
+    }
+
+    fn track_list(tracks: Vec<GridTrack>) -> Result<GridTrackList, GridError> {
+        GridTrackList::new(
+            tracks
+                .into_iter()
+                .map(|track| (GridLineNames(Vec::new()), track))
+                .collect(),
+            GridLineNames(Vec::new()),
+        )
+    }
+
+    #[test]
+    fn grid_track_list_repeats() {
+        let size = |breadth| GridTrack::Size(GridTrackSize::Breadth(breadth));
+        let list = track_list(vec![
+            size(px(100.0)),
+            repeat(
+                GridRepeatCount::Count(2),
+                vec![GridTrackSize::Breadth(fr(1.0))],
+            ),
+        ])
+        .unwrap();
+        assert_eq!(list.tracks().len(), 2);
+        assert_eq!(list.trailing_names(), &GridLineNames(Vec::new()));
+
+        assert_eq!(track_list(Vec::new()), Err(GridError::Empty));
+        assert_eq!(
+            track_list(vec![repeat(GridRepeatCount::Count(2), Vec::new())]),
+            Err(GridError::Empty)
+        );
+        assert_eq!(
+            track_list(vec![repeat(
+                GridRepeatCount::Count(0),
+                vec![GridTrackSize::Breadth(px(10.0))]
+            )]),
+            Err(GridError::ZeroRepetitions)
+        );
+        assert_eq!(
+            track_list(vec![GridTrack::Size(GridTrackSize::MinMax(
+                fr(1.0),
+                px(10.0)
+            ))]),
+            Err(GridError::FlexibleMinimum)
+        );
+        assert_eq!(
+            track_list(vec![repeat(
+                GridRepeatCount::Count(2),
+                vec![GridTrackSize::MinMax(fr(1.0), px(10.0))]
+            )]),
+            Err(GridError::FlexibleMinimum)
+        );
+    }
+
+    #[test]
+    fn grid_track_list_auto_repeats() {
+        let size = |breadth| GridTrack::Size(GridTrackSize::Breadth(breadth));
+        let auto_fill = || {
+            repeat(
+                GridRepeatCount::AutoFill,
+                vec![GridTrackSize::MinMax(px(100.0), fr(1.0))],
+            )
+        };
+        // Every track must have a fixed size next to an auto repeat
+        assert!(track_list(vec![size(px(10.0)), auto_fill()]).is_ok());
+        assert!(track_list(vec![
+            GridTrack::Size(GridTrackSize::MinMax(GridTrackBreadth::Auto, px(10.0))),
+            auto_fill(),
+        ])
+        .is_ok());
+        assert_eq!(
+            track_list(vec![size(fr(1.0)), auto_fill()]),
+            Err(GridError::AutoRepeatWithIntrinsicSize)
+        );
+        assert_eq!(
+            track_list(vec![size(GridTrackBreadth::Auto), auto_fill()]),
+            Err(GridError::AutoRepeatWithIntrinsicSize)
+        );
+        assert_eq!(
+            track_list(vec![repeat(
+                GridRepeatCount::AutoFit,
+                vec![GridTrackSize::Breadth(GridTrackBreadth::MinContent)]
+            )]),
+            Err(GridError::AutoRepeatWithIntrinsicSize)
+        );
+        assert_eq!(
+            track_list(vec![auto_fill(), auto_fill()]),
+            Err(GridError::MultipleAutoRepeats)
+        );
+        // Intrinsic sizes are fine without one
+        assert!(track_list(vec![
+            size(GridTrackBreadth::Auto),
+            repeat(
+                GridRepeatCount::Count(3),
+                vec![GridTrackSize::FitContent(
+                    data_types::LengthPercentage::Length(data_types::Length(
+                        10.0,
+                        data_types::LengthUnit::Px
+                    ))
+                )]
+            ),
+        ])
+        .is_ok());
+    }
+
+    fn display_type(
+        outside: DisplayOutside,
+        inside: DisplayInside,
+        list_item: bool,
+    ) -> NormalizedDisplay {
+        NormalizedDisplay::Outer(DisplayType {
+            outside,
+            inside,
+            list_item,
+        })
+    }
+
+    #[test]
+    fn legacy_display() {
+        let inline_flex = Display::Legacy(DisplayLegacy::InlineFlex);
+        let normalized = display_type(DisplayOutside::Inline, DisplayInside::Flex, false);
+        assert_eq!(inline_flex.normalize(), normalized);
+        assert_eq!(normalized.to_shortest(), inline_flex);
+        assert_eq!(
+            Display::Pair {
+                outside: Some(DisplayOutside::Inline),
+                inside: Some(DisplayInside::Flex),
+            }
+            .normalize()
+            .to_shortest(),
+            inline_flex
+        );
+
+        // `inline flow-root` is `inline-block`
+        let inline_flow_root = Display::Pair {
+            outside: Some(DisplayOutside::Inline),
+            inside: Some(DisplayInside::FlowRoot),
+        };
+        assert_eq!(
+            inline_flow_root.normalize(),
+            display_type(DisplayOutside::Inline, DisplayInside::FlowRoot, false)
+        );
+        assert_eq!(
+            inline_flow_root.normalize().to_shortest(),
+            Display::Legacy(DisplayLegacy::InlineBlock)
+        );
+        for legacy in [
+            DisplayLegacy::InlineBlock,
+            DisplayLegacy::InlineTable,
+            DisplayLegacy::InlineGrid,
+        ] {
+            let display = Display::Legacy(legacy);
+            assert_eq!(display.normalize().to_shortest(), display);
+        }
+    }
+
+    #[test]
+    fn two_value_display() {
+        // Omitted outer types are `block` (or `inline` for `ruby`) and
+        // omitted inner types are `flow`
+        assert_eq!(
+            Display::FLEX.normalize(),
+            display_type(DisplayOutside::Block, DisplayInside::Flex, false)
+        );
+        assert_eq!(
+            Display::INLINE.normalize(),
+            display_type(DisplayOutside::Inline, DisplayInside::Flow, false)
+        );
+        let ruby = Display::Pair {
+            outside: None,
+            inside: Some(DisplayInside::Ruby),
+        };
+        assert_eq!(
+            ruby.normalize(),
+            display_type(DisplayOutside::Inline, DisplayInside::Ruby, false)
+        );
+        assert_eq!(ruby.normalize().to_shortest(), ruby);
+        assert_eq!(
+            display_type(DisplayOutside::Block, DisplayInside::Flex, false).to_shortest(),
+            Display::FLEX
+        );
+        assert_eq!(
+            display_type(DisplayOutside::Block, DisplayInside::Flow, false).to_shortest(),
+            Display::BLOCK
+        );
+        assert_eq!(
+            display_type(DisplayOutside::Block, DisplayInside::Ruby, false).to_shortest(),
+            Display::Pair {
+                outside: Some(DisplayOutside::Block),
+                inside: Some(DisplayInside::Ruby),
+            }
+        );
+
+        // `run-in` is `run-in flow`
+        let run_in = Display::Pair {
+            outside: Some(DisplayOutside::RunIn),
+            inside: None,
+        };
+        assert_eq!(
+            run_in.normalize(),
+            display_type(DisplayOutside::RunIn, DisplayInside::Flow, false)
+        );
+        assert_eq!(run_in.normalize().to_shortest(), run_in);
+        let run_in_flex = display_type(DisplayOutside::RunIn, DisplayInside::Flex, false);
+        assert_eq!(run_in_flex.to_shortest().normalize(), run_in_flex);
+
+        assert_eq!(
+            Display::NONE.normalize(),
+            NormalizedDisplay::Box(DisplayBox::None)
+        );
+        assert_eq!(Display::NONE.normalize().to_shortest(), Display::NONE);
+    }
+
+    #[test]
+    fn list_item_display() {
+        // `list-item` is `block flow list-item`
+        let list_item = Display::ListItem {
+            outside: None,
+            inside: None,
+        };
+        let normalized = display_type(DisplayOutside::Block, DisplayInside::Flow, true);
+        assert_eq!(list_item.normalize(), normalized);
+        assert_eq!(normalized.to_shortest(), list_item);
+        assert_eq!(
+            Display::ListItem {
+                outside: Some(DisplayOutside::Block),
+                inside: Some(DisplayListItemInside::Flow),
+            }
+            .normalize()
+            .to_shortest(),
+            list_item
+        );
+
+        let inline_flow_root = Display::ListItem {
+            outside: Some(DisplayOutside::Inline),
+            inside: Some(DisplayListItemInside::FlowRoot),
+        };
+        assert_eq!(
+            inline_flow_root.normalize(),
+            display_type(DisplayOutside::Inline, DisplayInside::FlowRoot, true)
+        );
+        assert_eq!(inline_flow_root.normalize().to_shortest(), inline_flow_root);
+    }
+
+    #[test]
+    fn box_values() {
+        use shorthand::expand_box_values;
+
+        assert_eq!(expand_box_values(1, None, None, None), Some((1, 1, 1, 1)));
+        assert_eq!(
+            expand_box_values(1, Some(2), None, None),
+            Some((1, 2, 1, 2))
+        );
+        assert_eq!(
+            expand_box_values(1, Some(2), Some(3), None),
+            Some((1, 2, 3, 2))
+        );
+        assert_eq!(
+            expand_box_values(1, Some(2), Some(3), Some(4)),
+            Some((1, 2, 3, 4))
+        );
+        // Later values can't be given without the ones before them
+        assert_eq!(expand_box_values(1, None, Some(3), None), None);
+        assert_eq!(expand_box_values(1, None, None, Some(4)), None);
+        assert_eq!(expand_box_values(1, Some(2), None, Some(4)), None);
+        assert_eq!(expand_box_values(1, None, Some(3), Some(4)), None);
+
+        let zero = || {
+            data_types::LengthPercentage::Length(data_types::Length(
+                0.0,
+                data_types::LengthUnit::Px,
+            ))
+        };
+        assert!(shorthand::Padding::from_values(zero(), Some(zero()), None, None).is_some());
+        assert!(shorthand::Padding::from_values(zero(), None, None, Some(zero())).is_none());
+    }
 }
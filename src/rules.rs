@@ -0,0 +1,240 @@
+//! Style rules and CSS nesting, as described in
+//! https://drafts.csswg.org/css-nesting-1/
+
+use std::string::String as StdString;
+
+use crate::{
+    at_rules::{AtRule, LayerRule, MediaRule, SupportsRule},
+    properties::Property,
+    selectors::SelectorList,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rule {
+    Style(StyleRule),
+    At(AtRule),
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_syntax/Syntax#css_rulesets
+#[derive(Clone, Debug, PartialEq)]
+pub struct StyleRule {
+    /// For nested rules, these are relative to the parent rule's selectors,
+    /// so they contain `&` (which is implied when parsing selectors like
+    /// `> a`)
+    pub selectors: SelectorList,
+    pub declarations: Vec<Declaration>,
+    /// Nested style rules, conditional rules (like `@media`) and `@layer`
+    /// rules, which apply to this rule's selectors. Declarations directly
+    /// inside a nested conditional rule are a style rule with the selector
+    /// `&`.
+    ///
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/CSS_nesting
+    pub rules: Vec<Rule>,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/Syntax#css_declarations
+#[derive(Clone, Debug, PartialEq)]
+pub struct Declaration {
+    pub value: DeclarationValue,
+    pub important: bool,
+}
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeclarationValue {
+    Parsed(Property),
+    /// A declaration that can't be parsed into a [`Property`] but isn't known
+    /// to be invalid, which is kept as written (without `!important`): one for
+    /// a property without a parser, an unknown or custom property, or with a
+    /// value that uses `var()`
+    Unparsed {
+        name: StdString,
+        value: StdString,
+    },
+}
+
+/// Flatten nested rules into rules without nesting, for engines that don't
+/// support it
+///
+/// `&` is replaced with `:is()` of the parent rule's selectors, which has the
+/// same specificity (the parent's most specific selector), and nested
+/// conditional rules are hoisted out of their style rules, so
+/// `.a, #b { color: red; > .c { color: blue } }` becomes
+/// `.a, #b { color: red } :is(.a, #b) > .c { color: blue }`. A `&` outside of
+/// any style rule is replaced with `:scope`.
+pub fn flatten(rules: &[Rule]) -> Vec<Rule> {
+    let mut flattened = Vec::new();
+    for rule in rules {
+        flatten_rule(rule, None, &mut flattened);
+    }
+    flattened
+}
+
+fn flatten_rule(rule: &Rule, parent: Option<&SelectorList>, flattened: &mut Vec<Rule>) {
+    let flatten_all = |rules: &[Rule]| {
+        let mut flattened = Vec::new();
+        for rule in rules {
+            flatten_rule(rule, parent, &mut flattened);
+        }
+        flattened
+    };
+    match rule {
+        Rule::Style(style) => {
+            let selectors = match parent {
+                Some(parent) => style.selectors.replace_nesting(parent),
+                None => style.selectors.replace_top_level_nesting(),
+            };
+            if !style.declarations.is_empty() {
+                flattened.push(Rule::Style(StyleRule {
+                    selectors: selectors.clone(),
+                    declarations: style.declarations.clone(),
+                    rules: Vec::new(),
+                }));
+            }
+            for rule in &style.rules {
+                flatten_rule(rule, Some(&selectors), flattened);
+            }
+        }
+        Rule::At(AtRule::Media(media)) => flattened.push(Rule::At(AtRule::Media(MediaRule {
+            queries: media.queries.clone(),
+            rules: flatten_all(&media.rules),
+        }))),
+        Rule::At(AtRule::Supports(supports)) => {
+            flattened.push(Rule::At(AtRule::Supports(SupportsRule {
+                condition: supports.condition.clone(),
+                rules: flatten_all(&supports.rules),
+            })))
+        }
+        Rule::At(AtRule::Layer(layer)) => flattened.push(Rule::At(AtRule::Layer(LayerRule {
+            name: layer.name.clone(),
+            rules: flatten_all(&layer.rules),
+        }))),
+        Rule::At(rule) => flattened.push(Rule::At(rule.clone())),
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use super::*;
+    use crate::parser::parse_stylesheet;
+
+    /// The flattened rules, with each style rule as its selectors and number
+    /// of declarations
+    fn flattened(css: &str) -> StdString {
+        fn summary(rules: &[Rule]) -> StdString {
+            let rules: Vec<_> = rules
+                .iter()
+                .map(|rule| match rule {
+                    Rule::Style(style) => {
+                        assert!(style.rules.is_empty());
+                        format!("{} {{{}}}", style.selectors, style.declarations.len())
+                    }
+                    Rule::At(AtRule::Media(media)) => {
+                        format!("@media {{{}}}", summary(&media.rules))
+                    }
+                    Rule::At(AtRule::Supports(supports)) => {
+                        format!("@supports {{{}}}", summary(&supports.rules))
+                    }
+                    rule => panic!("{:?}", rule),
+                })
+                .collect();
+            rules.join(" ")
+        }
+        summary(&flatten(&parse_stylesheet(css)))
+    }
+
+    #[test]
+    fn relative_selectors() {
+        assert_eq!(
+            flattened(".a, #b { color: red; > .c { color: blue } }"),
+            ".a, #b {1} :is(.a, #b) > .c {1}"
+        );
+        assert_eq!(
+            flattened(".a { + .b, ~ .c { x: 1 } }"),
+            ":is(.a) + .b, :is(.a) ~ .c {1}"
+        );
+        // A selector without `&` is a descendant of it
+        assert_eq!(flattened(".a { .c { x: 1 } }"), ":is(.a) .c {1}");
+        assert_eq!(flattened(".a { .b .c { x: 1 } }"), ":is(.a) .b .c {1}");
+    }
+
+    #[test]
+    fn explicit_nesting() {
+        assert_eq!(flattened("a { &:hover { x: 1 } }"), ":is(a):hover {1}");
+        assert_eq!(flattened("a { &.b & { x: 1 } }"), ":is(a).b :is(a) {1}");
+        // `&` doesn't have to come first
+        assert_eq!(flattened(".a { .x & { x: 1 } }"), ".x :is(.a) {1}");
+        assert_eq!(flattened(".a { :not(&) { x: 1 } }"), ":not(:is(.a)) {1}");
+        // But a leading combinator is still relative to it
+        assert_eq!(
+            flattened(".a { > .x & { x: 1 } }"),
+            ":is(.a) > .x :is(.a) {1}"
+        );
+    }
+
+    #[test]
+    fn nested_conditional_rules() {
+        // Bare declarations are a `&` rule, hoisted out with the condition
+        assert_eq!(
+            flattened(".a { x: 1; @media print { y: 2; .b { z: 3 } } }"),
+            ".a {1} @media {:is(.a) {1} :is(.a) .b {1}}"
+        );
+        assert_eq!(
+            flattened(".a { @supports (display: grid) { @media print { y: 2 } } }"),
+            "@supports {@media {:is(.a) {1}}}"
+        );
+        // Conditional rules that aren't nested are left alone
+        assert_eq!(
+            flattened("@media print { .a { x: 1; .b { y: 2 } } }"),
+            "@media {.a {1} :is(.a) .b {1}}"
+        );
+    }
+
+    #[test]
+    fn deep_nesting() {
+        assert_eq!(
+            flattened(".a { .b { x: 1; & > .c { y: 2 } } }"),
+            ":is(.a) .b {1} :is(:is(.a) .b) > .c {1}"
+        );
+        assert_eq!(
+            flattened(".a { @media print { .b { .c { x: 1 } } } }"),
+            "@media {:is(:is(.a) .b) .c {1}}"
+        );
+    }
+
+    #[test]
+    fn nested_specificity() {
+        // `:is()` keeps the most specific of the parent's selectors, unlike
+        // expanding to `.a > .c, #b > .c`
+        let rules = flatten(&parse_stylesheet(".a, #b { > .c { x: 1 } }"));
+        let selectors = match &rules[..] {
+            [Rule::Style(style)] => &style.selectors,
+            rules => panic!("{:?}", rules),
+        };
+        let specificity = selectors.specificity();
+        assert_eq!(
+            (specificity.ids, specificity.classes, specificity.types),
+            (1, 1, 0)
+        );
+        let nested = match &parse_stylesheet(".a, #b { > .c { x: 1 } }")[..] {
+            [Rule::Style(style)] => match &style.rules[..] {
+                [Rule::Style(nested)] => nested.selectors.nested_specificity(&style.selectors),
+                rules => panic!("{:?}", rules),
+            },
+            rules => panic!("{:?}", rules),
+        };
+        assert_eq!(nested, specificity);
+    }
+
+    #[test]
+    fn top_level_nesting() {
+        // Outside of any style rule, `&` is `:scope`
+        assert_eq!(flattened("& .a { x: 1 }"), ":scope .a {1}");
+        assert_eq!(
+            flattened("& { x: 1; .b { y: 2 } }"),
+            ":scope {1} :is(:scope) .b {1}"
+        );
+        assert_eq!(
+            flattened("@media print { &:hover { x: 1 } }"),
+            "@media {:scope:hover {1}}"
+        );
+    }
+}
@@ -1,13 +0,0 @@
-pub enum PseudoClass {
-    Active,
-    After,
-
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:any-link
-    AnyLink,
-
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::backdrop
-    Backdrop,
-
-    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::before
-    Before,
-}
@@ -0,0 +1,257 @@
+//! A Bloom filter of the ancestors of the element being matched, which
+//! rejects most selectors with descendant and child combinators without
+//! walking up the tree, like Servo's
+//!
+//! https://github.com/servo/servo/blob/main/components/selectors/bloom.rs
+
+use super::{matching::Element, *};
+
+const KEY_BITS: u32 = 12;
+const KEY_MASK: u32 = (1 << KEY_BITS) - 1;
+
+/// A counting Bloom filter, so that elements can be removed when leaving them
+/// during a tree traversal
+///
+/// Counters saturate instead of overflowing, and saturated counters are never
+/// decremented, so the filter can only have false positives.
+#[derive(Clone)]
+pub struct AncestorFilter {
+    counters: Box<[u8; 1 << KEY_BITS]>,
+}
+impl AncestorFilter {
+    pub fn new() -> Self {
+        Self {
+            counters: Box::new([0; 1 << KEY_BITS]),
+        }
+    }
+
+    /// A filter of all the ancestors of `element`
+    pub fn for_element<E: Element>(element: &E) -> Self {
+        let mut filter = Self::new();
+        let mut ancestor = element.parent();
+        while let Some(element) = ancestor {
+            filter.push(&element);
+            ancestor = element.parent();
+        }
+        filter
+    }
+
+    /// Add an element, when descending into its children
+    pub fn push<E: Element>(&mut self, element: &E) {
+        for_each_element_hash(element, |hash| self.insert_hash(hash));
+    }
+
+    /// Remove an element that was added, when leaving its children
+    pub fn pop<E: Element>(&mut self, element: &E) {
+        for_each_element_hash(element, |hash| self.remove_hash(hash));
+    }
+
+    pub fn clear(&mut self) {
+        self.counters.fill(0);
+    }
+
+    pub fn insert_hash(&mut self, hash: u32) {
+        for key in keys(hash) {
+            let counter = &mut self.counters[key];
+            *counter = counter.saturating_add(1);
+        }
+    }
+
+    pub fn remove_hash(&mut self, hash: u32) {
+        for key in keys(hash) {
+            let counter = &mut self.counters[key];
+            if *counter != u8::MAX {
+                *counter = counter.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Whether the hash might have been inserted, which is always true if it
+    /// was
+    pub fn might_contain_hash(&self, hash: u32) -> bool {
+        keys(hash).iter().all(|&key| self.counters[key] != 0)
+    }
+}
+impl Default for AncestorFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl fmt::Debug for AncestorFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let occupied = self
+            .counters
+            .iter()
+            .filter(|&&counter| counter != 0)
+            .count();
+        write!(f, "AncestorFilter({} occupied)", occupied)
+    }
+}
+
+fn keys(hash: u32) -> [usize; 2] {
+    [
+        (hash & KEY_MASK) as usize,
+        ((hash >> KEY_BITS) & KEY_MASK) as usize,
+    ]
+}
+
+/// The kinds of names hashed into the filter, so that an id and a class with
+/// the same name have different hashes
+#[derive(Clone, Copy)]
+enum NameKind {
+    LocalName = 1,
+    Id,
+    Class,
+}
+
+/// FNV-1a, which is fast for short names
+fn hash_name(kind: NameKind, name: &str) -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    for byte in std::iter::once(kind as u8).chain(name.bytes()) {
+        hash ^= byte as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+/// Local names are hashed in lowercase, since they're matched
+/// case-insensitively in HTML documents
+fn hash_local_name(name: &str) -> u32 {
+    hash_name(NameKind::LocalName, &name.to_ascii_lowercase())
+}
+
+fn for_each_element_hash<E: Element>(element: &E, mut f: impl FnMut(u32)) {
+    f(hash_local_name(element.local_name()));
+    if let Some(id) = element.id() {
+        f(hash_name(NameKind::Id, id));
+    }
+    for class in element.classes() {
+        f(hash_name(NameKind::Class, class));
+    }
+}
+
+/// The hashes of names that a selector requires the subject's ancestors to
+/// have, like `main` and `intro` in `main p.intro > a`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct AncestorHashes(pub Vec<u32>);
+impl AncestorHashes {
+    pub fn new(selector: &ComplexSelector) -> Self {
+        let mut hashes = Vec::new();
+        // Every compound left of a descendant or child combinator is an
+        // ancestor of the subject (even after sibling combinators, since
+        // siblings share a parent)
+        let compounds = std::iter::once(&selector.first)
+            .chain(selector.rest.iter().map(|(_, compound)| compound));
+        for (compound, (combinator, _)) in compounds.zip(&selector.rest) {
+            if !matches!(combinator, Combinator::Descendant | Combinator::Child) {
+                continue;
+            }
+            if let Some(TypeSelector {
+                name: Some(name), ..
+            }) = &compound.type_selector
+            {
+                hashes.push(hash_local_name(name));
+            }
+            for subclass in &compound.subclasses {
+                match subclass {
+                    SubclassSelector::Id(id) => hashes.push(hash_name(NameKind::Id, id)),
+                    SubclassSelector::Class(class) => {
+                        hashes.push(hash_name(NameKind::Class, class))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Self(hashes)
+    }
+
+    /// Whether the subject's ancestors (in `filter`) might match the
+    /// selector, where `false` means it can't match
+    pub fn might_match(&self, filter: &AncestorFilter) -> bool {
+        self.0.iter().all(|&hash| filter.might_contain_hash(hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counting() {
+        let mut filter = AncestorFilter::new();
+        let hash = hash_name(NameKind::Class, "a");
+        assert!(!filter.might_contain_hash(hash));
+        filter.insert_hash(hash);
+        filter.insert_hash(hash);
+        filter.remove_hash(hash);
+        assert!(filter.might_contain_hash(hash));
+        filter.remove_hash(hash);
+        assert!(!filter.might_contain_hash(hash));
+    }
+
+    #[test]
+    fn saturated_counters_stay() {
+        let mut filter = AncestorFilter::new();
+        let hash = hash_name(NameKind::Id, "a");
+        for _ in 0..300 {
+            filter.insert_hash(hash);
+        }
+        for _ in 0..300 {
+            filter.remove_hash(hash);
+        }
+        // Removing could otherwise give false negatives
+        assert!(filter.might_contain_hash(hash));
+        filter.clear();
+        assert!(!filter.might_contain_hash(hash));
+    }
+
+    #[test]
+    fn names_of_different_kinds() {
+        assert_ne!(
+            hash_name(NameKind::Id, "a"),
+            hash_name(NameKind::Class, "a")
+        );
+        assert_eq!(hash_local_name("DIV"), hash_local_name("div"));
+    }
+
+    #[cfg(feature = "parser")]
+    fn hashes(selector: &str) -> AncestorHashes {
+        AncestorHashes::new(&crate::parser::parse(selector).unwrap())
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn ancestor_hashes() {
+        assert_eq!(hashes("p").0.len(), 0);
+        assert_eq!(hashes("div.a > p").0.len(), 2);
+        assert_eq!(hashes("#main p").0, vec![hash_name(NameKind::Id, "main")]);
+        // Siblings aren't ancestors, but an ancestor of a sibling is
+        assert_eq!(hashes("p + span").0.len(), 0);
+        assert_eq!(hashes("ul > li ~ li").0, vec![hash_local_name("ul")]);
+        // Only names that must be present count
+        assert_eq!(hashes(":is(div, ul) *[lang] p").0.len(), 0);
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn rejects_selectors() {
+        use crate::selectors::matching::tests::{tree, Handle};
+
+        let tree = tree();
+        // A `p` inside `html > body > div#main`
+        let filter = AncestorFilter::for_element(&Handle(&tree, 3));
+        assert!(hashes("html body #main p").might_match(&filter));
+        assert!(hashes("div > p").might_match(&filter));
+        assert!(!hashes("ul p").might_match(&filter));
+        assert!(!hashes(".x p").might_match(&filter));
+        assert!(!hashes("p p").might_match(&filter));
+
+        // Popping an element removes its names
+        let mut filter = filter;
+        filter.pop(&Handle(&tree, 2));
+        assert!(!hashes("div > p").might_match(&filter));
+        assert!(hashes("body p").might_match(&filter));
+        filter.push(&Handle(&tree, 2));
+        assert!(hashes("div > p").might_match(&filter));
+    }
+}
@@ -0,0 +1,252 @@
+//! An index of selectors by the names their subject requires, so matching
+//! an element only has to check the selectors that could match it
+
+use std::collections::HashMap;
+
+use super::{
+    bloom::{AncestorFilter, AncestorHashes},
+    matching::{Element, MatchingCache, MatchingContext},
+    *,
+};
+
+/// Selectors (and associated data, like the rule they're from) indexed by the
+/// rightmost id, class, attribute or type selector of their subject
+#[derive(Clone, Debug)]
+pub struct SelectorMap<T> {
+    ids: HashMap<StdString, Vec<Entry<T>>>,
+    classes: HashMap<StdString, Vec<Entry<T>>>,
+    /// Attributes without a namespace
+    attributes: HashMap<StdString, Vec<Entry<T>>>,
+    /// Lowercase local names
+    local_names: HashMap<StdString, Vec<Entry<T>>>,
+    /// Selectors without any of the above, which have to be checked for
+    /// every element
+    other: Vec<Entry<T>>,
+    len: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Entry<T> {
+    selector: ComplexSelector,
+    hashes: AncestorHashes,
+    /// The insertion order, so matches can be returned in order
+    order: usize,
+    data: T,
+}
+
+/// The bucket a selector is indexed in
+enum Key<'a> {
+    Id(&'a str),
+    Class(&'a str),
+    Attribute(&'a str),
+    LocalName(StdString),
+    Other,
+}
+
+impl<T> SelectorMap<T> {
+    pub fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            classes: HashMap::new(),
+            attributes: HashMap::new(),
+            local_names: HashMap::new(),
+            other: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Add a selector, which is only indexed by its own subject (not by
+    /// selectors nested in pseudo-classes like `:is()`)
+    pub fn insert(&mut self, selector: ComplexSelector, data: T) {
+        let entry = Entry {
+            hashes: AncestorHashes::new(&selector),
+            order: self.len,
+            selector,
+            data,
+        };
+        self.len += 1;
+        let bucket = match key(entry.selector.subject()) {
+            Key::Id(id) => self.ids.entry(id.to_owned()).or_default(),
+            Key::Class(class) => self.classes.entry(class.to_owned()).or_default(),
+            Key::Attribute(name) => self.attributes.entry(name.to_owned()).or_default(),
+            Key::LocalName(name) => self.local_names.entry(name).or_default(),
+            Key::Other => &mut self.other,
+        };
+        bucket.push(entry);
+    }
+
+    /// The data of every selector matching `element`, in insertion order
+    ///
+    /// With a `filter` of `element`'s ancestors, most selectors that can't
+    /// match are rejected without walking up the tree.
+    pub fn matching<E: Element, C: MatchingCache<E>>(
+        &self,
+        element: &E,
+        context: &mut MatchingContext<E, C>,
+        filter: Option<&AncestorFilter>,
+    ) -> Vec<&T> {
+        let mut buckets = vec![&self.other];
+        if let Some(id) = element.id() {
+            buckets.extend(self.ids.get(id));
+        }
+        for class in element.classes() {
+            buckets.extend(self.classes.get(class));
+        }
+        for (name, bucket) in &self.attributes {
+            if element.attribute(None, name).is_some() {
+                buckets.push(bucket);
+            }
+        }
+        buckets.extend(
+            self.local_names
+                .get(&element.local_name().to_ascii_lowercase()),
+        );
+
+        let mut matches: Vec<_> = buckets
+            .into_iter()
+            .flatten()
+            .filter(|entry| filter.is_none_or(|filter| entry.hashes.might_match(filter)))
+            .filter(|entry| entry.selector.matches(element, context))
+            .collect();
+        matches.sort_by_key(|entry| entry.order);
+        // An element with the same class twice would match an entry twice
+        matches.dedup_by_key(|entry| entry.order);
+        matches.into_iter().map(|entry| &entry.data).collect()
+    }
+}
+impl<T> Default for SelectorMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The most selective name a compound requires, preferring ids, then
+/// classes, then attributes, then type selectors
+fn key(compound: &CompoundSelector) -> Key<'_> {
+    let mut key = Key::Other;
+    for subclass in &compound.subclasses {
+        match (subclass, &key) {
+            (SubclassSelector::Id(id), _) => return Key::Id(id),
+            (SubclassSelector::Class(class), Key::Other | Key::Attribute(_)) => {
+                key = Key::Class(class)
+            }
+            (
+                SubclassSelector::Attribute(AttributeSelector {
+                    namespace: None | Some(NamespacePrefix::None),
+                    name,
+                    ..
+                }),
+                Key::Other,
+            ) => key = Key::Attribute(name),
+            _ => {}
+        }
+    }
+    match (key, &compound.type_selector) {
+        (
+            Key::Other,
+            Some(TypeSelector {
+                name: Some(name), ..
+            }),
+        ) => Key::LocalName(name.to_ascii_lowercase()),
+        (key, _) => key,
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use super::*;
+    use crate::selectors::matching::tests::{tree, Handle, Tree};
+
+    fn map(selectors: &[&'static str]) -> SelectorMap<&'static str> {
+        let mut map = SelectorMap::new();
+        for selector in selectors {
+            map.insert(crate::parser::parse(selector).unwrap(), *selector);
+        }
+        map
+    }
+
+    #[test]
+    fn buckets() {
+        let map = map(&[
+            "#main.x",
+            "p.x.z",
+            "p[data-a].x",
+            "p[data-a]",
+            "p",
+            "*",
+            ":is(p)",
+        ]);
+        assert_eq!(map.len(), 7);
+        fn bucket(
+            bucket: &HashMap<StdString, Vec<Entry<&'static str>>>,
+            key: &str,
+        ) -> Vec<&'static str> {
+            bucket[key].iter().map(|entry| entry.data).collect()
+        }
+        assert_eq!(bucket(&map.ids, "main"), ["#main.x"]);
+        // The first class
+        assert_eq!(bucket(&map.classes, "x"), ["p.x.z", "p[data-a].x"]);
+        assert_eq!(bucket(&map.attributes, "data-a"), ["p[data-a]"]);
+        assert_eq!(bucket(&map.local_names, "p"), ["p"]);
+        let other: Vec<_> = map.other.iter().map(|entry| entry.data).collect();
+        assert_eq!(other, ["*", ":is(p)"]);
+    }
+
+    /// Every element's matches, with and without an ancestor filter, which
+    /// must be the same
+    fn all_matches<'a>(tree: &'a Tree, map: &'a SelectorMap<&'static str>) -> Vec<Vec<&'a str>> {
+        let mut context = MatchingContext::new();
+        (0..tree.0.len())
+            .map(|i| {
+                let element = Handle(tree, i);
+                let unfiltered = map.matching(&element, &mut context, None);
+                let filter = AncestorFilter::for_element(&element);
+                let filtered = map.matching(&element, &mut context, Some(&filter));
+                assert_eq!(filtered, unfiltered);
+                unfiltered.into_iter().copied().collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matching_in_order() {
+        let mut tree = tree();
+        tree.0[4].attributes.push(("data-a", ""));
+        let map = map(&[
+            "p",
+            "#main > *",
+            ".z",
+            "ul li",
+            "body > .x",
+            "[data-a]",
+            ":nth-child(2)",
+            "div .x",
+        ]);
+        let matches = all_matches(&tree, &map);
+        assert_eq!(matches[0], Vec::<&str>::new());
+        assert_eq!(matches[2], Vec::<&str>::new());
+        assert_eq!(matches[3], ["p", "#main > *", "div .x"]);
+        assert_eq!(
+            matches[4],
+            ["p", "#main > *", "[data-a]", ":nth-child(2)", "div .x"]
+        );
+        assert_eq!(matches[6], ["p", "#main > *", ".z", "div .x"]);
+        assert_eq!(matches[9], ["ul li", ":nth-child(2)"]);
+    }
+
+    #[test]
+    fn repeated_classes() {
+        let mut tree = tree();
+        tree.0[3].classes.push("x");
+        let map = map(&[".x"]);
+        assert_eq!(all_matches(&tree, &map)[3], [".x"]);
+    }
+}
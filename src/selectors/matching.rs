@@ -0,0 +1,993 @@
+//! Matching selectors against a tree of elements, from right to left
+//!
+//! https://drafts.csswg.org/selectors-4/#match-against-element
+
+use std::{collections::HashMap, hash::Hash, string::String as StdString};
+
+use super::*;
+
+/// An element in a document tree that selectors can be matched against
+///
+/// Elements are usually cheap handles (like references or node ids), so
+/// they're passed around by value.
+pub trait Element: Clone + PartialEq {
+    /// The parent element, or `None` for the root element
+    fn parent(&self) -> Option<Self>;
+    /// The previous sibling element, skipping other nodes like text
+    fn prev_sibling(&self) -> Option<Self>;
+    /// The next sibling element, skipping other nodes like text
+    fn next_sibling(&self) -> Option<Self>;
+    /// The first child element, skipping other nodes like text
+    fn first_child(&self) -> Option<Self>;
+
+    fn local_name(&self) -> &str;
+    /// The namespace URL, like `http://www.w3.org/1999/xhtml`
+    fn namespace(&self) -> Option<&str>;
+    fn id(&self) -> Option<&str>;
+    fn classes(&self) -> Vec<&str>;
+    fn has_class(&self, class: &str) -> bool {
+        self.classes().contains(&class)
+    }
+    /// The value of the attribute `name` in `namespace` (a namespace URL, or
+    /// `None` for attributes without a namespace)
+    fn attribute(&self, namespace: Option<&str>, name: &str) -> Option<&str>;
+    /// The values of the attribute `name` in any namespace, for `[*|name]`
+    fn attribute_in_any_namespace(&self, name: &str) -> Vec<&str> {
+        self.attribute(None, name).into_iter().collect()
+    }
+
+    /// Whether the element has no children other than comments, for `:empty`
+    ///
+    /// Text (even whitespace) counts as a child, so this can't be worked out
+    /// from the child elements alone.
+    fn is_empty(&self) -> bool;
+    /// Whether the element is an HTML element in an HTML document, where type
+    /// selectors are ASCII case-insensitive
+    fn is_html_element_in_html_document(&self) -> bool {
+        true
+    }
+    /// The element's own language (not inherited), for `:lang()`
+    fn lang(&self) -> Option<StdString> {
+        self.attribute(None, "lang").map(str::to_owned)
+    }
+    /// The element's own direction (not inherited), for `:dir()`
+    fn direction(&self) -> Option<TextDirection> {
+        match self.attribute(None, "dir")? {
+            dir if dir.eq_ignore_ascii_case("ltr") => Some(TextDirection::Ltr),
+            dir if dir.eq_ignore_ascii_case("rtl") => Some(TextDirection::Rtl),
+            _ => None,
+        }
+    }
+    /// Whether the element is in the state of a non-structural pseudo-class,
+    /// like `:hover`, `:focus` or `:checked`
+    ///
+    /// This is called for every pseudo-class that depends on the document's
+    /// state rather than its tree, including the pseudo-classes applying to a
+    /// pseudo-element (like `:hover` in `::before:hover`).
+    fn matches_state(&self, pseudo_class: &PseudoClass) -> bool;
+}
+
+/// Namespace prefixes declared with `@namespace`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Namespaces {
+    /// The namespace URL of type selectors without a prefix
+    pub default: Option<StdString>,
+    /// Prefixes and the namespace URLs they refer to
+    pub prefixes: HashMap<StdString, StdString>,
+}
+
+/// The kinds of `:nth-*` pseudo-classes, which count siblings differently
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NthKind {
+    Child,
+    LastChild,
+    OfType,
+    LastOfType,
+}
+
+/// Hooks for caching results that are expensive to compute and shared
+/// between elements, like sibling indexes for `:nth-*` and the results of
+/// `:has()`
+///
+/// Cached results are only valid while the document doesn't change. The
+/// default methods don't cache anything, which is what `()` does.
+pub trait MatchingCache<E> {
+    fn nth_index(
+        &mut self,
+        _element: &E,
+        _kind: NthKind,
+        _of: Option<&SelectorList>,
+    ) -> Option<i32> {
+        None
+    }
+    fn set_nth_index(
+        &mut self,
+        _element: &E,
+        _kind: NthKind,
+        _of: Option<&SelectorList>,
+        _index: i32,
+    ) {
+    }
+    fn has(&mut self, _element: &E, _selectors: &[RelativeSelector]) -> Option<bool> {
+        None
+    }
+    fn set_has(&mut self, _element: &E, _selectors: &[RelativeSelector], _matches: bool) {}
+}
+impl<E> MatchingCache<E> for () {}
+
+/// A [`MatchingCache`] storing results in hash maps, which must be cleared
+/// when the document changes
+#[derive(Clone, Debug)]
+pub struct SelectorCache<E> {
+    nth_indexes: HashMap<(E, NthKind, Option<SelectorList>), i32>,
+    has: HashMap<(E, Vec<RelativeSelector>), bool>,
+}
+impl<E> SelectorCache<E> {
+    pub fn new() -> Self {
+        Self {
+            nth_indexes: HashMap::new(),
+            has: HashMap::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.nth_indexes.clear();
+        self.has.clear();
+    }
+}
+impl<E> Default for SelectorCache<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<E: Clone + Eq + Hash> MatchingCache<E> for SelectorCache<E> {
+    fn nth_index(&mut self, element: &E, kind: NthKind, of: Option<&SelectorList>) -> Option<i32> {
+        self.nth_indexes
+            .get(&(element.clone(), kind, of.cloned()))
+            .copied()
+    }
+    fn set_nth_index(&mut self, element: &E, kind: NthKind, of: Option<&SelectorList>, index: i32) {
+        self.nth_indexes
+            .insert((element.clone(), kind, of.cloned()), index);
+    }
+    fn has(&mut self, element: &E, selectors: &[RelativeSelector]) -> Option<bool> {
+        self.has
+            .get(&(element.clone(), selectors.to_vec()))
+            .copied()
+    }
+    fn set_has(&mut self, element: &E, selectors: &[RelativeSelector], matches: bool) {
+        self.has
+            .insert((element.clone(), selectors.to_vec()), matches);
+    }
+}
+
+/// Everything matching depends on other than the element
+#[derive(Clone, Debug)]
+pub struct MatchingContext<E, C = ()> {
+    /// The element `:scope` matches, which defaults to the root element
+    pub scope: Option<E>,
+    /// The shadow host, when matching the selectors of a shadow tree's
+    /// stylesheet (where `:host` matches it)
+    pub shadow_host: Option<E>,
+    /// The pseudo-element being matched, where only selectors ending in that
+    /// pseudo-element match
+    pub pseudo_element: Option<PseudoElement>,
+    pub namespaces: Namespaces,
+    pub cache: C,
+}
+impl<E> MatchingContext<E> {
+    pub fn new() -> Self {
+        Self::with_cache(())
+    }
+}
+impl<E> Default for MatchingContext<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<E, C> MatchingContext<E, C> {
+    pub fn with_cache(cache: C) -> Self {
+        Self {
+            scope: None,
+            shadow_host: None,
+            pseudo_element: None,
+            namespaces: Namespaces::default(),
+            cache,
+        }
+    }
+
+    /// The namespace URL of a prefix, where `None` is an undeclared prefix
+    /// (which can't match anything)
+    fn resolve(&self, prefix: &str) -> Option<&str> {
+        self.namespaces.prefixes.get(prefix).map(StdString::as_str)
+    }
+}
+
+impl SelectorList {
+    /// Whether any of the selectors match `element`
+    pub fn matches<E: Element, C: MatchingCache<E>>(
+        &self,
+        element: &E,
+        context: &mut MatchingContext<E, C>,
+    ) -> bool {
+        self.0
+            .iter()
+            .any(|selector| selector.matches(element, context))
+    }
+
+    /// Whether any selector matches, ignoring pseudo-elements, for selectors
+    /// nested in pseudo-classes
+    fn matches_nested<E: Element, C: MatchingCache<E>>(
+        &self,
+        element: &E,
+        context: &mut MatchingContext<E, C>,
+    ) -> bool {
+        self.0
+            .iter()
+            .any(|selector| matches_complex(selector, element, None, false, context))
+    }
+}
+
+impl ComplexSelector {
+    /// Whether the selector matches `element` (or `element`'s
+    /// [`MatchingContext::pseudo_element`])
+    pub fn matches<E: Element, C: MatchingCache<E>>(
+        &self,
+        element: &E,
+        context: &mut MatchingContext<E, C>,
+    ) -> bool {
+        let pseudo_element = self
+            .subject()
+            .pseudo_elements
+            .last()
+            .map(|pseudo_element| &pseudo_element.element);
+        pseudo_element == context.pseudo_element.as_ref()
+            && matches_complex(self, element, None, true, context)
+    }
+}
+
+/// Match a complex selector from right to left, where `anchor` is the element
+/// a relative selector (in `:has()`) is relative to. Pseudo-elements are only
+/// allowed in the subject of a top-level selector, where they've already been
+/// checked against the context.
+fn matches_complex<E: Element, C: MatchingCache<E>>(
+    selector: &ComplexSelector,
+    element: &E,
+    anchor: Option<(&E, Combinator)>,
+    top_level: bool,
+    context: &mut MatchingContext<E, C>,
+) -> bool {
+    if !selector.subject().pseudo_elements.is_empty() && !top_level {
+        return false;
+    }
+    let index = selector.rest.len();
+    matches_from(selector, index, element, anchor, context) == MatchResult::Matched
+}
+
+/// The result of matching part of a complex selector, which tells callers
+/// trying other elements for a combinator when they can give up
+///
+/// For example, when matching `a > b c` against `c`, if an ancestor `b`
+/// doesn't have a parent `a`, no other ancestor can help, since any `b`
+/// further up would need an `a` further up too (which was already checked).
+/// This avoids matching taking exponential time.
+///
+/// https://github.com/servo/servo/blob/main/components/selectors/matching.rs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MatchResult {
+    Matched,
+    /// Try the next element for the closest sibling combinator to the right
+    RestartFromClosestLaterSibling,
+    /// Try the next element for the closest descendant combinator to the
+    /// right
+    RestartFromClosestDescendant,
+    /// No other element for any combinator can match
+    NotMatchedGlobally,
+}
+
+/// The compound selector at `index`, counting from the left
+fn compound_at(selector: &ComplexSelector, index: usize) -> &CompoundSelector {
+    match index {
+        0 => &selector.first,
+        index => &selector.rest[index - 1].1,
+    }
+}
+
+/// Match the compound selectors up to `index`, where the one at `index`
+/// must match `element`
+fn matches_from<E: Element, C: MatchingCache<E>>(
+    selector: &ComplexSelector,
+    index: usize,
+    element: &E,
+    anchor: Option<(&E, Combinator)>,
+    context: &mut MatchingContext<E, C>,
+) -> MatchResult {
+    let is_subject = index == selector.rest.len();
+    if !compound_at(selector, index).matches_compound(element, is_subject, context) {
+        return MatchResult::RestartFromClosestLaterSibling;
+    }
+    if index == 0 {
+        return match anchor {
+            Some((anchor, combinator)) if !is_related(anchor, combinator, element) => {
+                MatchResult::RestartFromClosestLaterSibling
+            }
+            _ => MatchResult::Matched,
+        };
+    }
+
+    let combinator = selector.rest[index - 1].0;
+    let next = |element: &E| match combinator {
+        Combinator::Child | Combinator::Descendant => element.parent(),
+        Combinator::NextSibling | Combinator::SubsequentSibling => element.prev_sibling(),
+        // Table columns aren't part of the element tree
+        Combinator::Column => None,
+    };
+    let mut candidate = element.clone();
+    loop {
+        candidate = match next(&candidate) {
+            Some(candidate) => candidate,
+            // Nothing further up can match the combinators to the left
+            None => {
+                return match combinator {
+                    Combinator::NextSibling | Combinator::SubsequentSibling => {
+                        MatchResult::RestartFromClosestDescendant
+                    }
+                    _ => MatchResult::NotMatchedGlobally,
+                }
+            }
+        };
+        let result = matches_from(selector, index - 1, &candidate, anchor, context);
+        match (result, combinator) {
+            (MatchResult::Matched | MatchResult::NotMatchedGlobally, _)
+            | (_, Combinator::NextSibling) => return result,
+            (_, Combinator::Child) => return MatchResult::RestartFromClosestDescendant,
+            (MatchResult::RestartFromClosestDescendant, Combinator::SubsequentSibling) => {
+                return result
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether `element` is related to `anchor` by `combinator`, like being its
+/// child for [`Combinator::Child`]
+fn is_related<E: Element>(anchor: &E, combinator: Combinator, element: &E) -> bool {
+    match combinator {
+        Combinator::Child => element.parent().as_ref() == Some(anchor),
+        Combinator::Descendant => ancestors(element).any(|ancestor| &ancestor == anchor),
+        Combinator::NextSibling => element.prev_sibling().as_ref() == Some(anchor),
+        Combinator::SubsequentSibling => prev_siblings(element).any(|sibling| &sibling == anchor),
+        Combinator::Column => false,
+    }
+}
+
+fn ancestors<E: Element>(element: &E) -> impl Iterator<Item = E> {
+    std::iter::successors(element.parent(), E::parent)
+}
+fn prev_siblings<E: Element>(element: &E) -> impl Iterator<Item = E> {
+    std::iter::successors(element.prev_sibling(), E::prev_sibling)
+}
+fn next_siblings<E: Element>(element: &E) -> impl Iterator<Item = E> {
+    std::iter::successors(element.next_sibling(), E::next_sibling)
+}
+/// Descendants in tree order
+fn descendants<E: Element>(element: &E) -> Vec<E> {
+    let mut descendants = Vec::new();
+    let mut child = element.first_child();
+    while let Some(element) = child {
+        descendants.push(element.clone());
+        descendants.extend(self::descendants(&element));
+        child = element.next_sibling();
+    }
+    descendants
+}
+
+impl CompoundSelector {
+    /// Whether every simple selector matches `element`, where pseudo-elements
+    /// are only allowed in the subject
+    fn matches_compound<E: Element, C: MatchingCache<E>>(
+        &self,
+        element: &E,
+        is_subject: bool,
+        context: &mut MatchingContext<E, C>,
+    ) -> bool {
+        if !self.pseudo_elements.is_empty() && !is_subject {
+            return false;
+        }
+        if let Some(type_selector) = &self.type_selector {
+            if !type_selector.matches(element, context) {
+                return false;
+            }
+        } else if let Some(default) = &context.namespaces.default {
+            if element.namespace() != Some(default.as_str()) {
+                return false;
+            }
+        }
+        self.subclasses
+            .iter()
+            .all(|subclass| subclass.matches(element, context))
+            && self.pseudo_elements.iter().all(|pseudo_element| {
+                pseudo_element
+                    .classes
+                    .iter()
+                    .all(|class| element.matches_state(class))
+            })
+    }
+}
+
+impl TypeSelector {
+    fn matches<E: Element, C>(&self, element: &E, context: &MatchingContext<E, C>) -> bool {
+        let namespace = match &self.namespace {
+            Some(NamespacePrefix::Any) => true,
+            Some(NamespacePrefix::None) => element.namespace().is_none(),
+            Some(NamespacePrefix::Named(prefix)) => context
+                .resolve(prefix)
+                .is_some_and(|url| element.namespace() == Some(url)),
+            None => context
+                .namespaces
+                .default
+                .as_deref()
+                .is_none_or(|url| element.namespace() == Some(url)),
+        };
+        namespace
+            && self.name.as_ref().is_none_or(|name| {
+                if element.is_html_element_in_html_document() {
+                    name.eq_ignore_ascii_case(element.local_name())
+                } else {
+                    name == element.local_name()
+                }
+            })
+    }
+}
+
+impl SubclassSelector {
+    fn matches<E: Element, C: MatchingCache<E>>(
+        &self,
+        element: &E,
+        context: &mut MatchingContext<E, C>,
+    ) -> bool {
+        match self {
+            SubclassSelector::Id(id) => element.id() == Some(id.as_str()),
+            SubclassSelector::Class(class) => element.has_class(class),
+            SubclassSelector::Attribute(attribute) => attribute.matches(element, context),
+            SubclassSelector::PseudoClass(class) => class.matches(element, context),
+            // Unresolved nesting outside a style rule, see `rules::flatten`
+            SubclassSelector::Nesting => matches_scope(element, context),
+        }
+    }
+}
+
+impl AttributeSelector {
+    fn matches<E: Element, C>(&self, element: &E, context: &MatchingContext<E, C>) -> bool {
+        let values = match &self.namespace {
+            None | Some(NamespacePrefix::None) => {
+                element.attribute(None, &self.name).into_iter().collect()
+            }
+            Some(NamespacePrefix::Any) => element.attribute_in_any_namespace(&self.name),
+            Some(NamespacePrefix::Named(prefix)) => match context.resolve(prefix) {
+                Some(url) => element
+                    .attribute(Some(url), &self.name)
+                    .into_iter()
+                    .collect(),
+                None => Vec::new(),
+            },
+        };
+        values.into_iter().any(|value| {
+            self.matcher
+                .as_ref()
+                .is_none_or(|matcher| matcher.matches(value))
+        })
+    }
+}
+
+impl AttributeMatcher {
+    /// Whether an attribute's value matches
+    pub fn matches(&self, value: &str) -> bool {
+        let (value, expected) = match self.case_sensitivity {
+            Some(AttributeCaseSensitivity::Insensitive) => {
+                (value.to_ascii_lowercase(), self.value.to_ascii_lowercase())
+            }
+            _ => (value.to_owned(), self.value.clone()),
+        };
+        match self.operator {
+            AttributeOperator::Equals => value == expected,
+            AttributeOperator::Includes => {
+                !expected.is_empty()
+                    && !expected.contains(char::is_whitespace)
+                    && value.split_whitespace().any(|word| word == expected)
+            }
+            AttributeOperator::DashMatch => {
+                value == expected
+                    || value
+                        .strip_prefix(expected.as_str())
+                        .is_some_and(|rest| rest.starts_with('-'))
+            }
+            AttributeOperator::Prefix => !expected.is_empty() && value.starts_with(&expected),
+            AttributeOperator::Suffix => !expected.is_empty() && value.ends_with(&expected),
+            AttributeOperator::Substring => !expected.is_empty() && value.contains(&expected),
+        }
+    }
+}
+
+impl PseudoClass {
+    fn matches<E: Element, C: MatchingCache<E>>(
+        &self,
+        element: &E,
+        context: &mut MatchingContext<E, C>,
+    ) -> bool {
+        match self {
+            //* Tree-Structural
+            PseudoClass::Root => element.parent().is_none(),
+            PseudoClass::Empty => element.is_empty(),
+            PseudoClass::Scope => matches_scope(element, context),
+            PseudoClass::FirstChild => element.prev_sibling().is_none(),
+            PseudoClass::LastChild => element.next_sibling().is_none(),
+            PseudoClass::OnlyChild => {
+                element.prev_sibling().is_none() && element.next_sibling().is_none()
+            }
+            PseudoClass::FirstOfType => !prev_siblings(element).any(|e| same_type(&e, element)),
+            PseudoClass::LastOfType => !next_siblings(element).any(|e| same_type(&e, element)),
+            PseudoClass::OnlyOfType => {
+                !prev_siblings(element).any(|e| same_type(&e, element))
+                    && !next_siblings(element).any(|e| same_type(&e, element))
+            }
+            PseudoClass::NthChild { nth, of } => {
+                nth_matches(element, *nth, NthKind::Child, of.as_ref(), context)
+            }
+            PseudoClass::NthLastChild { nth, of } => {
+                nth_matches(element, *nth, NthKind::LastChild, of.as_ref(), context)
+            }
+            PseudoClass::NthOfType(nth) => {
+                nth_matches(element, *nth, NthKind::OfType, None, context)
+            }
+            PseudoClass::NthLastOfType(nth) => {
+                nth_matches(element, *nth, NthKind::LastOfType, None, context)
+            }
+
+            //* Logical Combinations
+            PseudoClass::Is(selectors) | PseudoClass::Where(selectors) => {
+                selectors.matches_nested(element, context)
+            }
+            PseudoClass::Not(selectors) => !selectors.matches_nested(element, context),
+            PseudoClass::Has(selectors) => {
+                if let Some(matches) = context.cache.has(element, selectors) {
+                    return matches;
+                }
+                let matches = selectors
+                    .iter()
+                    .any(|selector| has_matches(element, selector, context));
+                context.cache.set_has(element, selectors, matches);
+                matches
+            }
+
+            //* Linguistic
+            PseudoClass::Dir(direction) => {
+                std::iter::once(element.clone())
+                    .chain(ancestors(element))
+                    .find_map(|element| element.direction())
+                    .unwrap_or(TextDirection::Ltr)
+                    == *direction
+            }
+            PseudoClass::Lang(ranges) => {
+                let lang = std::iter::once(element.clone())
+                    .chain(ancestors(element))
+                    .find_map(|element| element.lang());
+                lang.is_some_and(|lang| ranges.iter().any(|range| lang_matches(&lang, range)))
+            }
+
+            //* Shadow DOM
+            PseudoClass::Host(compound) => {
+                context.shadow_host.as_ref() == Some(element)
+                    && compound
+                        .as_ref()
+                        .is_none_or(|compound| compound.matches_compound(element, false, context))
+            }
+            PseudoClass::HostContext(compound) => {
+                context.shadow_host.as_ref() == Some(element)
+                    && std::iter::once(element.clone())
+                        .chain(ancestors(element))
+                        .any(|element| compound.matches_compound(&element, false, context))
+            }
+
+            // Both `:link` and `:visited`
+            PseudoClass::AnyLink => {
+                element.matches_state(&PseudoClass::Link)
+                    || element.matches_state(&PseudoClass::Visited)
+            }
+            _ => element.matches_state(self),
+        }
+    }
+}
+
+fn matches_scope<E: Element, C>(element: &E, context: &MatchingContext<E, C>) -> bool {
+    match &context.scope {
+        Some(scope) => element == scope,
+        None => element.parent().is_none(),
+    }
+}
+
+fn same_type<E: Element>(a: &E, b: &E) -> bool {
+    a.local_name() == b.local_name() && a.namespace() == b.namespace()
+}
+
+/// Whether `element`'s index (counting from 1) among the siblings counted by
+/// `kind` and `of` matches `nth`
+fn nth_matches<E: Element, C: MatchingCache<E>>(
+    element: &E,
+    nth: AnPlusB,
+    kind: NthKind,
+    of: Option<&SelectorList>,
+    context: &mut MatchingContext<E, C>,
+) -> bool {
+    if of.is_some_and(|of| !of.matches_nested(element, context)) {
+        return false;
+    }
+    let index = match context.cache.nth_index(element, kind, of) {
+        Some(index) => index,
+        None => {
+            let siblings: Vec<_> = match kind {
+                NthKind::Child | NthKind::OfType => prev_siblings(element).collect(),
+                NthKind::LastChild | NthKind::LastOfType => next_siblings(element).collect(),
+            };
+            let mut index = 1;
+            for sibling in &siblings {
+                let counts = match kind {
+                    NthKind::OfType | NthKind::LastOfType => same_type(sibling, element),
+                    NthKind::Child | NthKind::LastChild => {
+                        of.is_none_or(|of| of.matches_nested(sibling, context))
+                    }
+                };
+                if counts {
+                    index += 1;
+                }
+            }
+            context.cache.set_nth_index(element, kind, of, index);
+            index
+        }
+    };
+    nth.matches(index)
+}
+
+/// Whether any element relative to `anchor` matches a relative selector from
+/// `:has()`
+fn has_matches<E: Element, C: MatchingCache<E>>(
+    anchor: &E,
+    selector: &RelativeSelector,
+    context: &mut MatchingContext<E, C>,
+) -> bool {
+    // The subject can only be a descendant of the anchor, or a following
+    // sibling (or its descendant)
+    let candidates = match selector.combinator {
+        Combinator::Descendant | Combinator::Child => descendants(anchor),
+        Combinator::NextSibling | Combinator::SubsequentSibling => next_siblings(anchor)
+            .flat_map(|sibling| std::iter::once(sibling.clone()).chain(descendants(&sibling)))
+            .collect(),
+        Combinator::Column => Vec::new(),
+    };
+    candidates.iter().any(|candidate| {
+        matches_complex(
+            &selector.selector,
+            candidate,
+            Some((anchor, selector.combinator)),
+            false,
+            context,
+        )
+    })
+}
+
+/// Whether a language tag matches a language range, using extended filtering
+/// with an implicit wildcard at the end
+///
+/// https://www.rfc-editor.org/rfc/rfc4647#section-3.3.2
+fn lang_matches(lang: &str, range: &str) -> bool {
+    let mut ranges = range.split('-');
+    let mut tags = lang.split('-');
+    match (ranges.next(), tags.next()) {
+        (Some("*"), Some(tag)) if !tag.is_empty() => {}
+        (Some(range), Some(tag)) if range.eq_ignore_ascii_case(tag) => {}
+        _ => return false,
+    }
+    for range in ranges.filter(|range| *range != "*") {
+        loop {
+            match tags.next() {
+                Some(tag) if tag.eq_ignore_ascii_case(range) => break,
+                // Singletons like `x` can't be skipped
+                Some(tag) if tag.len() == 1 => return false,
+                Some(_) => {}
+                None => return false,
+            }
+        }
+    }
+    true
+}
+
+#[cfg(all(test, feature = "parser"))]
+pub(super) mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+
+    #[derive(Default)]
+    pub(in crate::selectors) struct Node {
+        pub name: &'static str,
+        pub id: Option<&'static str>,
+        pub classes: Vec<&'static str>,
+        pub attributes: Vec<(&'static str, &'static str)>,
+        pub has_text: bool,
+        parent: Option<usize>,
+        children: Vec<usize>,
+    }
+
+    /// A document tree of elements, by index
+    #[derive(Default)]
+    pub(in crate::selectors) struct Tree(pub Vec<Node>);
+    impl Tree {
+        pub fn add(
+            &mut self,
+            parent: Option<usize>,
+            name: &'static str,
+            classes: &[&'static str],
+        ) -> usize {
+            let index = self.0.len();
+            self.0.push(Node {
+                name,
+                classes: classes.to_vec(),
+                parent,
+                ..Node::default()
+            });
+            if let Some(parent) = parent {
+                self.0[parent].children.push(index);
+            }
+            index
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    pub(in crate::selectors) struct Handle<'a>(pub &'a Tree, pub usize);
+    impl PartialEq for Handle<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            self.1 == other.1
+        }
+    }
+    impl Eq for Handle<'_> {}
+    impl Hash for Handle<'_> {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.1.hash(state)
+        }
+    }
+    impl<'a> Handle<'a> {
+        fn node(&self) -> &'a Node {
+            &self.0 .0[self.1]
+        }
+        fn sibling(&self, offset: isize) -> Option<Self> {
+            let siblings = &self.0 .0[self.node().parent?].children;
+            let index = siblings.iter().position(|&i| i == self.1)? as isize + offset;
+            let sibling = *siblings.get(usize::try_from(index).ok()?)?;
+            Some(Handle(self.0, sibling))
+        }
+    }
+    impl Element for Handle<'_> {
+        fn parent(&self) -> Option<Self> {
+            Some(Handle(self.0, self.node().parent?))
+        }
+        fn prev_sibling(&self) -> Option<Self> {
+            self.sibling(-1)
+        }
+        fn next_sibling(&self) -> Option<Self> {
+            self.sibling(1)
+        }
+        fn first_child(&self) -> Option<Self> {
+            Some(Handle(self.0, *self.node().children.first()?))
+        }
+        fn local_name(&self) -> &str {
+            self.node().name
+        }
+        fn namespace(&self) -> Option<&str> {
+            Some("http://www.w3.org/1999/xhtml")
+        }
+        fn id(&self) -> Option<&str> {
+            self.node().id
+        }
+        fn classes(&self) -> Vec<&str> {
+            self.node().classes.clone()
+        }
+        fn attribute(&self, namespace: Option<&str>, name: &str) -> Option<&str> {
+            let node = self.node();
+            match (namespace, name) {
+                (None, "id") => node.id,
+                (None, name) => node
+                    .attributes
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, value)| *value),
+                _ => None,
+            }
+        }
+        fn is_empty(&self) -> bool {
+            self.node().children.is_empty() && !self.node().has_text
+        }
+        fn matches_state(&self, _pseudo_class: &PseudoClass) -> bool {
+            false
+        }
+    }
+
+    /// ```html
+    /// <html><body>
+    ///   <div id=main>
+    ///     <p class=x></p> <p class=x>text</p> <span class=y></span>
+    ///     <p class="x z"></p>
+    ///   </div>
+    ///   <ul><li></li><li></li><li></li></ul>
+    /// </body></html>
+    /// ```
+    pub(in crate::selectors) fn tree() -> Tree {
+        let mut tree = Tree::default();
+        let html = tree.add(None, "html", &[]);
+        let body = tree.add(Some(html), "body", &[]);
+        let div = tree.add(Some(body), "div", &[]);
+        tree.0[div].id = Some("main");
+        tree.add(Some(div), "p", &["x"]);
+        let text = tree.add(Some(div), "p", &["x"]);
+        tree.0[text].has_text = true;
+        tree.add(Some(div), "span", &["y"]);
+        tree.add(Some(div), "p", &["x", "z"]);
+        let ul = tree.add(Some(body), "ul", &[]);
+        for _ in 0..3 {
+            tree.add(Some(ul), "li", &[]);
+        }
+        tree
+    }
+
+    /// The indexes of the elements matching `selectors`
+    fn select_with<'a, C: MatchingCache<Handle<'a>>>(
+        tree: &'a Tree,
+        selectors: &str,
+        context: &mut MatchingContext<Handle<'a>, C>,
+    ) -> Vec<usize> {
+        let selectors: SelectorList = crate::parser::parse(selectors).unwrap();
+        (0..tree.0.len())
+            .filter(|&i| selectors.matches(&Handle(tree, i), context))
+            .collect()
+    }
+
+    fn select(tree: &Tree, selectors: &str) -> Vec<usize> {
+        select_with(tree, selectors, &mut MatchingContext::new())
+    }
+
+    #[test]
+    fn combinators() {
+        let tree = tree();
+        assert_eq!(select(&tree, "div p"), [3, 4, 6]);
+        assert_eq!(select(&tree, "body > p"), Vec::<usize>::new());
+        assert_eq!(select(&tree, "p + span"), [5]);
+        assert_eq!(select(&tree, "span ~ p"), [6]);
+        assert_eq!(select(&tree, "#main > .z"), [6]);
+        assert_eq!(select(&tree, "html li:first-child"), [8]);
+        assert_eq!(select(&tree, "div ~ * > li:last-child"), [10]);
+    }
+
+    #[test]
+    fn empty() {
+        let tree = tree();
+        // The second `p` has text, which counts as content
+        assert_eq!(select(&tree, "p:empty"), [3, 6]);
+        assert_eq!(select(&tree, ":root"), [0]);
+    }
+
+    #[test]
+    fn logical_combinations() {
+        let tree = tree();
+        assert_eq!(select(&tree, "div > :not(.x)"), [5]);
+        assert_eq!(select(&tree, ":is(span, .z)"), [5, 6]);
+        assert_eq!(select(&tree, ":where(#main) > .y"), [5]);
+    }
+
+    #[test]
+    fn nth() {
+        let tree = tree();
+        assert_eq!(select(&tree, "div > :nth-child(odd)"), [3, 5]);
+        assert_eq!(select(&tree, "div > :nth-last-child(1)"), [6]);
+        assert_eq!(select(&tree, "p:nth-of-type(3)"), [6]);
+        assert_eq!(select(&tree, "p:nth-last-of-type(3)"), [3]);
+        assert_eq!(select(&tree, "li:nth-child(-n+2)"), [8, 9]);
+        // Only siblings matching `of` are counted
+        assert_eq!(select(&tree, ":nth-child(3 of .x)"), [6]);
+        assert_eq!(select(&tree, ":nth-last-child(2 of .x)"), [4]);
+    }
+
+    #[test]
+    fn has() {
+        let tree = tree();
+        assert_eq!(select(&tree, "div:has(> span)"), [2]);
+        assert_eq!(select(&tree, ":has(> .z)"), [2]);
+        assert_eq!(select(&tree, ":has(.z)"), [0, 1, 2]);
+        assert_eq!(select(&tree, "div:has(+ ul)"), [2]);
+        assert_eq!(select(&tree, "p:has(~ span)"), [3, 4]);
+        assert_eq!(select(&tree, "p:has(+ span)"), [4]);
+        assert_eq!(select(&tree, "body:has(> div li)"), Vec::<usize>::new());
+        // The relative selector's other compounds can be outside the anchor
+        assert_eq!(select(&tree, "li:has(~ li):nth-child(2)"), [9]);
+    }
+
+    #[test]
+    fn caches() {
+        let tree = tree();
+        let selectors = [
+            ":nth-child(2 of .x)",
+            "p:nth-last-of-type(2)",
+            "li:nth-child(even)",
+            ":has(> .x)",
+            "div:has(~ ul > li:nth-child(3))",
+            ":not(:has(p))",
+        ];
+        let mut context = MatchingContext::with_cache(SelectorCache::new());
+        for selector in selectors {
+            // Matching twice, so the second time uses cached results
+            let uncached = select(&tree, selector);
+            assert_eq!(
+                select_with(&tree, selector, &mut context),
+                uncached,
+                "{}",
+                selector
+            );
+            assert_eq!(
+                select_with(&tree, selector, &mut context),
+                uncached,
+                "{}",
+                selector
+            );
+        }
+        let cache = &mut context.cache;
+        assert!(!cache.nth_indexes.is_empty());
+        assert!(!cache.has.is_empty());
+
+        // The cached index of the last `.x` among `.x` siblings
+        let of: SelectorList = crate::parser::parse(".x").unwrap();
+        assert_eq!(
+            cache.nth_index(&Handle(&tree, 6), NthKind::Child, Some(&of)),
+            Some(3)
+        );
+        cache.clear();
+        assert!(cache.nth_indexes.is_empty() && cache.has.is_empty());
+    }
+
+    #[test]
+    fn attribute_operators() {
+        let matcher = |operator, value: &str| AttributeMatcher {
+            operator,
+            value: value.to_owned(),
+            case_sensitivity: None,
+        };
+        assert!(matcher(AttributeOperator::Includes, "b").matches("a b c"));
+        assert!(!matcher(AttributeOperator::Includes, "").matches(""));
+        assert!(matcher(AttributeOperator::DashMatch, "en").matches("en-US"));
+        assert!(!matcher(AttributeOperator::DashMatch, "en").matches("english"));
+        assert!(matcher(AttributeOperator::Prefix, "ab").matches("abc"));
+        assert!(!matcher(AttributeOperator::Suffix, "").matches("abc"));
+        assert!(matcher(AttributeOperator::Substring, "b").matches("abc"));
+        let mut insensitive = matcher(AttributeOperator::Equals, "ABC");
+        assert!(!insensitive.matches("abc"));
+        insensitive.case_sensitivity = Some(AttributeCaseSensitivity::Insensitive);
+        assert!(insensitive.matches("abc"));
+    }
+
+    #[test]
+    fn languages() {
+        assert!(lang_matches("de-DE", "de"));
+        assert!(lang_matches("de-Latn-DE", "de-DE"));
+        assert!(lang_matches("de-DE", "*-DE"));
+        assert!(!lang_matches("de-x-DE", "de-DE"));
+        assert!(!lang_matches("en", "de"));
+    }
+
+    #[test]
+    fn default_namespace() {
+        let tree = tree();
+        let selectors: SelectorList = crate::parser::parse("div").unwrap();
+        let mut context = MatchingContext::new();
+        context.namespaces.default = Some("http://www.w3.org/2000/svg".to_owned());
+        assert!(!selectors.matches(&Handle(&tree, 2), &mut context));
+        context.namespaces.default = Some("http://www.w3.org/1999/xhtml".to_owned());
+        assert!(selectors.matches(&Handle(&tree, 2), &mut context));
+    }
+}
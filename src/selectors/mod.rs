@@ -0,0 +1,1221 @@
+//! Selectors, as described in https://drafts.csswg.org/selectors-4/
+
+use std::{fmt, ops, string::String as StdString};
+
+use strum::{EnumString, IntoStaticStr};
+
+pub mod bloom;
+pub mod index;
+pub mod matching;
+
+/// A comma-separated list of selectors, which matches an element if any of
+/// the selectors do
+///
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/Selector_list
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SelectorList(pub Vec<ComplexSelector>);
+
+/// Compound selectors separated by combinators, like `main > p.intro a`
+///
+/// https://drafts.csswg.org/selectors-4/#complex
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ComplexSelector {
+    /// The leftmost compound selector
+    pub first: CompoundSelector,
+    /// Each combinator, and the compound selector to its right
+    pub rest: Vec<(Combinator, CompoundSelector)>,
+}
+impl ComplexSelector {
+    /// The rightmost compound selector, which is the one that matches the
+    /// subject element
+    pub fn subject(&self) -> &CompoundSelector {
+        self.rest
+            .last()
+            .map_or(&self.first, |(_, compound)| compound)
+    }
+}
+
+/// A complex selector relative to another element, like `> img` in
+/// `a:has(> img)`
+///
+/// https://drafts.csswg.org/selectors-4/#relative
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RelativeSelector {
+    /// The combinator between the anchor element and the selector, which is
+    /// [`Combinator::Descendant`] if omitted
+    pub combinator: Combinator,
+    pub selector: ComplexSelector,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Learn/CSS/Building_blocks/Selectors#combinators
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Combinator {
+    /// Whitespace, as in `A B`
+    Descendant,
+    /// `A > B`
+    Child,
+    /// `A + B`
+    NextSibling,
+    /// `A ~ B`
+    SubsequentSibling,
+    /// `A || B`, for cells in a column
+    Column,
+}
+
+/// Simple selectors that aren't separated by combinators, like
+/// `a.external[href]:hover`
+///
+/// https://drafts.csswg.org/selectors-4/#compound
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CompoundSelector {
+    pub type_selector: Option<TypeSelector>,
+    pub subclasses: Vec<SubclassSelector>,
+    pub pseudo_elements: Vec<PseudoElementSelector>,
+}
+
+/// A type (`p`) or universal (`*`) selector, with an optional namespace
+///
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/Type_selectors
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypeSelector {
+    /// Without a prefix, the default namespace (see `@namespace`) is used
+    pub namespace: Option<NamespacePrefix>,
+    /// `None` is the universal selector
+    pub name: Option<StdString>,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/Type_selectors#namespaces
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NamespacePrefix {
+    /// `*|`, any namespace or none
+    Any,
+    /// `|`, no namespace
+    None,
+    /// `prefix|`, a namespace declared with `@namespace`
+    Named(StdString),
+}
+
+/// https://drafts.csswg.org/selectors-4/#typedef-subclass-selector
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SubclassSelector {
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/ID_selectors
+    Id(StdString),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/Class_selectors
+    Class(StdString),
+    Attribute(AttributeSelector),
+    PseudoClass(PseudoClass),
+    /// `&`, which stands for the parent style rule's selectors when nested,
+    /// and for `:scope` otherwise
+    ///
+    /// https://drafts.csswg.org/css-nesting-1/#nest-selector
+    Nesting,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/Attribute_selectors
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AttributeSelector {
+    /// Without a prefix, only attributes without a namespace match
+    pub namespace: Option<NamespacePrefix>,
+    pub name: StdString,
+    /// `None` matches any element with the attribute, like `[href]`
+    pub matcher: Option<AttributeMatcher>,
+}
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AttributeMatcher {
+    pub operator: AttributeOperator,
+    pub value: StdString,
+    /// Without a flag, case sensitivity depends on the document language
+    /// (like HTML's case-insensitive `type` attribute)
+    pub case_sensitivity: Option<AttributeCaseSensitivity>,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AttributeOperator {
+    /// `[attr=value]`
+    Equals,
+    /// `[attr~=value]`, a whitespace-separated list containing the value
+    Includes,
+    /// `[attr|=value]`, the value or the value followed by `-`
+    DashMatch,
+    /// `[attr^=value]`
+    Prefix,
+    /// `[attr$=value]`
+    Suffix,
+    /// `[attr*=value]`
+    Substring,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AttributeCaseSensitivity {
+    /// `i`, comparing ASCII characters case-insensitively
+    Insensitive,
+    /// `s`
+    Sensitive,
+}
+
+/// A pseudo-element and the pseudo-classes that apply to it, like
+/// `::before:hover`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PseudoElementSelector {
+    pub element: PseudoElement,
+    pub classes: Vec<PseudoClass>,
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/Pseudo-classes
+#[derive(Clone, Debug, PartialEq, Eq, Hash, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum PseudoClass {
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:active
+    Active,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:any-link
+    AnyLink,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:autofill
+    Autofill,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:blank
+    Blank,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:buffering
+    Buffering,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:checked
+    Checked,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:current
+    Current,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:default
+    Default,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:defined
+    Defined,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:disabled
+    Disabled,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:empty
+    Empty,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:enabled
+    Enabled,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:first-child
+    FirstChild,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:first-of-type
+    FirstOfType,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:focus
+    Focus,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:focus-visible
+    FocusVisible,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:focus-within
+    FocusWithin,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:fullscreen
+    Fullscreen,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:future
+    Future,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:hover
+    Hover,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:in-range
+    InRange,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:indeterminate
+    Indeterminate,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:invalid
+    Invalid,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:last-child
+    LastChild,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:last-of-type
+    LastOfType,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:link
+    Link,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:local-link
+    LocalLink,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:modal
+    Modal,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:muted
+    Muted,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:only-child
+    OnlyChild,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:only-of-type
+    OnlyOfType,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:open
+    Open,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:optional
+    Optional,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:out-of-range
+    OutOfRange,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:past
+    Past,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:paused
+    Paused,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:picture-in-picture
+    PictureInPicture,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:placeholder-shown
+    PlaceholderShown,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:playing
+    Playing,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:popover-open
+    PopoverOpen,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:read-only
+    ReadOnly,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:read-write
+    ReadWrite,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:required
+    Required,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:root
+    Root,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:scope
+    Scope,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:seeking
+    Seeking,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:stalled
+    Stalled,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:target
+    Target,
+    /// https://drafts.csswg.org/selectors-4/#the-target-within-pseudo
+    TargetWithin,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:user-invalid
+    UserInvalid,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:user-valid
+    UserValid,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:valid
+    Valid,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:visited
+    Visited,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:volume-locked
+    VolumeLocked,
+
+    //* Functional Pseudo-Classes
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:dir
+    #[strum(disabled)]
+    Dir(TextDirection),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:has
+    #[strum(disabled)]
+    Has(Vec<RelativeSelector>),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:host and
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:host_function
+    ///
+    /// `None` is `:host` without an argument.
+    #[strum(disabled)]
+    Host(Option<Box<CompoundSelector>>),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:host-context
+    #[strum(disabled)]
+    HostContext(Box<CompoundSelector>),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:is
+    ///
+    /// The list is forgiving, so invalid selectors are dropped when parsing
+    /// (and it can be empty).
+    #[strum(disabled)]
+    Is(SelectorList),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:lang
+    ///
+    /// Language ranges, like `en` or `*-CH`
+    #[strum(disabled)]
+    Lang(Vec<StdString>),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:not
+    #[strum(disabled)]
+    Not(SelectorList),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:nth-child
+    #[strum(disabled)]
+    NthChild {
+        nth: AnPlusB,
+        /// Only count siblings matching the selectors
+        of: Option<SelectorList>,
+    },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:nth-last-child
+    #[strum(disabled)]
+    NthLastChild {
+        nth: AnPlusB,
+        of: Option<SelectorList>,
+    },
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:nth-last-of-type
+    #[strum(disabled)]
+    NthLastOfType(AnPlusB),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:nth-of-type
+    #[strum(disabled)]
+    NthOfType(AnPlusB),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/:where
+    ///
+    /// Like [`PseudoClass::Is`], but with no specificity.
+    #[strum(disabled)]
+    Where(SelectorList),
+}
+
+/// The argument of `:dir()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextDirection {
+    Ltr,
+    Rtl,
+}
+
+/// An `An+B` pattern matching every `a`th index (counting from 1), starting
+/// from `b`
+///
+/// https://drafts.csswg.org/css-syntax-3/#anb-microsyntax
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AnPlusB {
+    pub a: i32,
+    pub b: i32,
+}
+impl AnPlusB {
+    /// `odd`
+    pub const ODD: Self = Self { a: 2, b: 1 };
+    /// `even`
+    pub const EVEN: Self = Self { a: 2, b: 0 };
+
+    /// Whether `index` (counting from 1) is `A*n + B` for some `n` of 0 or
+    /// more
+    pub fn matches(&self, index: i32) -> bool {
+        // In i64, as `index - b` can overflow an i32 for large `b`
+        let (a, b) = (self.a as i64, self.b as i64);
+        let offset = index as i64 - b;
+        if a == 0 {
+            offset == 0
+        } else {
+            offset % a == 0 && offset / a >= 0
+        }
+    }
+}
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/Pseudo-elements
+#[derive(Clone, Debug, PartialEq, Eq, Hash, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum PseudoElement {
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::after
+    After,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::backdrop
+    Backdrop,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::before
+    Before,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::cue
+    Cue,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::file-selector-button
+    FileSelectorButton,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::first-letter
+    FirstLetter,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::first-line
+    FirstLine,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::grammar-error
+    GrammarError,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::marker
+    Marker,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::placeholder
+    Placeholder,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::selection
+    Selection,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::spelling-error
+    SpellingError,
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::target-text
+    TargetText,
+
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::highlight
+    #[strum(disabled)]
+    Highlight(StdString),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::part
+    #[strum(disabled)]
+    Part(Vec<StdString>),
+    /// https://developer.mozilla.org/en-US/docs/Web/CSS/::slotted
+    #[strum(disabled)]
+    Slotted(Box<CompoundSelector>),
+}
+impl PseudoElement {
+    /// Whether this can also be written with a single colon, from before
+    /// pseudo-elements had their own syntax
+    pub fn is_legacy(&self) -> bool {
+        matches!(
+            self,
+            PseudoElement::After
+                | PseudoElement::Before
+                | PseudoElement::FirstLetter
+                | PseudoElement::FirstLine
+        )
+    }
+}
+
+//* Specificity
+/// How specific a selector is, which decides which of the rules matching an
+/// element wins. Specificities compare by ids, then classes, then types.
+///
+/// https://drafts.csswg.org/selectors-4/#specificity-rules
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Specificity {
+    /// ID selectors
+    pub ids: u32,
+    /// Class selectors, attribute selectors and pseudo-classes
+    pub classes: u32,
+    /// Type selectors and pseudo-elements
+    pub types: u32,
+}
+impl Specificity {
+    const ID: Self = Self {
+        ids: 1,
+        classes: 0,
+        types: 0,
+    };
+    const CLASS: Self = Self {
+        ids: 0,
+        classes: 1,
+        types: 0,
+    };
+    const TYPE: Self = Self {
+        ids: 0,
+        classes: 0,
+        types: 1,
+    };
+}
+impl ops::Add for Specificity {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            ids: self.ids + other.ids,
+            classes: self.classes + other.classes,
+            types: self.types + other.types,
+        }
+    }
+}
+impl ops::AddAssign for Specificity {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+impl fmt::Display for Specificity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.ids, self.classes, self.types)
+    }
+}
+
+impl SelectorList {
+    /// The specificity of the most specific selector in the list, which is
+    /// what `:is()`, `:not()` and `:has()` use for their arguments
+    ///
+    /// A `&` counts as `:scope`, which is what it means outside of nested
+    /// rules. Use [`SelectorList::nested_specificity`] for nested rules.
+    pub fn specificity(&self) -> Specificity {
+        self.specificity_with(Specificity::CLASS)
+    }
+
+    /// The specificity of the selectors of a nested rule, where `&` has the
+    /// specificity of the parent rule's selectors (with their own nesting
+    /// already resolved), like the `:is()` it stands for
+    pub fn nested_specificity(&self, parent: &SelectorList) -> Specificity {
+        self.specificity_with(parent.specificity())
+    }
+
+    /// The specificity where `&` counts as `nesting`
+    fn specificity_with(&self, nesting: Specificity) -> Specificity {
+        self.0
+            .iter()
+            .map(|selector| selector.specificity_with(nesting))
+            .max()
+            .unwrap_or_default()
+    }
+}
+impl ComplexSelector {
+    /// See [`SelectorList::specificity`]
+    pub fn specificity(&self) -> Specificity {
+        self.specificity_with(Specificity::CLASS)
+    }
+
+    /// See [`SelectorList::nested_specificity`]
+    pub fn nested_specificity(&self, parent: &SelectorList) -> Specificity {
+        self.specificity_with(parent.specificity())
+    }
+
+    fn specificity_with(&self, nesting: Specificity) -> Specificity {
+        self.rest.iter().fold(
+            self.first.specificity_with(nesting),
+            |specificity, (_, compound)| specificity + compound.specificity_with(nesting),
+        )
+    }
+}
+impl CompoundSelector {
+    /// See [`SelectorList::specificity`]
+    pub fn specificity(&self) -> Specificity {
+        self.specificity_with(Specificity::CLASS)
+    }
+
+    fn specificity_with(&self, nesting: Specificity) -> Specificity {
+        let mut specificity = Specificity::default();
+        if let Some(TypeSelector { name: Some(_), .. }) = self.type_selector {
+            specificity += Specificity::TYPE;
+        }
+        for subclass in &self.subclasses {
+            specificity += match subclass {
+                SubclassSelector::Id(_) => Specificity::ID,
+                SubclassSelector::Class(_) | SubclassSelector::Attribute(_) => Specificity::CLASS,
+                SubclassSelector::PseudoClass(class) => class.specificity_with(nesting),
+                SubclassSelector::Nesting => nesting,
+            };
+        }
+        for pseudo_element in &self.pseudo_elements {
+            specificity += Specificity::TYPE;
+            if let PseudoElement::Slotted(compound) = &pseudo_element.element {
+                specificity += compound.specificity_with(nesting);
+            }
+            for class in &pseudo_element.classes {
+                specificity += class.specificity_with(nesting);
+            }
+        }
+        specificity
+    }
+}
+impl PseudoClass {
+    /// See [`SelectorList::specificity`]
+    pub fn specificity(&self) -> Specificity {
+        self.specificity_with(Specificity::CLASS)
+    }
+
+    fn specificity_with(&self, nesting: Specificity) -> Specificity {
+        match self {
+            PseudoClass::Where(_) => Specificity::default(),
+            PseudoClass::Is(selectors) | PseudoClass::Not(selectors) => {
+                selectors.specificity_with(nesting)
+            }
+            PseudoClass::Has(selectors) => selectors
+                .iter()
+                .map(|relative| relative.selector.specificity_with(nesting))
+                .max()
+                .unwrap_or_default(),
+            PseudoClass::NthChild { of: Some(of), .. }
+            | PseudoClass::NthLastChild { of: Some(of), .. } => {
+                Specificity::CLASS + of.specificity_with(nesting)
+            }
+            PseudoClass::Host(Some(compound)) | PseudoClass::HostContext(compound) => {
+                Specificity::CLASS + compound.specificity_with(nesting)
+            }
+            _ => Specificity::CLASS,
+        }
+    }
+}
+
+//* Nesting
+impl SelectorList {
+    /// Whether any selector contains `&`, including in pseudo-class arguments
+    pub fn contains_nesting(&self) -> bool {
+        self.0.iter().any(ComplexSelector::contains_nesting)
+    }
+
+    /// Replace `&` with `:is(parent)`, which keeps the specificity of the
+    /// parent's most specific selector
+    ///
+    /// https://drafts.csswg.org/css-nesting-1/#nest-selector
+    pub fn replace_nesting(&self, parent: &SelectorList) -> SelectorList {
+        self.replace_nesting_with(&SubclassSelector::PseudoClass(PseudoClass::Is(
+            parent.clone(),
+        )))
+    }
+
+    /// Replace `&` with `:scope`, which is what it means outside of nested
+    /// rules
+    pub fn replace_top_level_nesting(&self) -> SelectorList {
+        self.replace_nesting_with(&SubclassSelector::PseudoClass(PseudoClass::Scope))
+    }
+
+    fn replace_nesting_with(&self, replacement: &SubclassSelector) -> SelectorList {
+        SelectorList(
+            self.0
+                .iter()
+                .map(|selector| selector.replace_nesting_with(replacement))
+                .collect(),
+        )
+    }
+}
+impl ComplexSelector {
+    pub fn contains_nesting(&self) -> bool {
+        std::iter::once(&self.first)
+            .chain(self.rest.iter().map(|(_, compound)| compound))
+            .any(CompoundSelector::contains_nesting)
+    }
+
+    /// See [`SelectorList::replace_nesting`]
+    pub fn replace_nesting(&self, parent: &SelectorList) -> ComplexSelector {
+        self.replace_nesting_with(&SubclassSelector::PseudoClass(PseudoClass::Is(
+            parent.clone(),
+        )))
+    }
+
+    fn replace_nesting_with(&self, replacement: &SubclassSelector) -> ComplexSelector {
+        ComplexSelector {
+            first: self.first.replace_nesting_with(replacement),
+            rest: self
+                .rest
+                .iter()
+                .map(|(combinator, compound)| {
+                    (*combinator, compound.replace_nesting_with(replacement))
+                })
+                .collect(),
+        }
+    }
+}
+impl RelativeSelector {
+    /// The selector of a nested style rule, which is relative to `&` unless
+    /// it already contains `&` without a leading combinator (so `> a` is
+    /// `& > a`, and `a` is `& a`)
+    ///
+    /// https://drafts.csswg.org/css-nesting-1/#syntax
+    pub fn to_nested(&self) -> ComplexSelector {
+        if self.combinator == Combinator::Descendant && self.selector.contains_nesting() {
+            return self.selector.clone();
+        }
+        let mut rest = vec![(self.combinator, self.selector.first.clone())];
+        rest.extend(self.selector.rest.iter().cloned());
+        ComplexSelector {
+            first: CompoundSelector {
+                subclasses: vec![SubclassSelector::Nesting],
+                ..CompoundSelector::default()
+            },
+            rest,
+        }
+    }
+}
+impl CompoundSelector {
+    fn contains_nesting(&self) -> bool {
+        self.subclasses.iter().any(|subclass| match subclass {
+            SubclassSelector::Nesting => true,
+            SubclassSelector::PseudoClass(class) => class.contains_nesting(),
+            _ => false,
+        }) || self
+            .pseudo_elements
+            .iter()
+            .any(|pseudo_element| match &pseudo_element.element {
+                PseudoElement::Slotted(compound) => compound.contains_nesting(),
+                _ => false,
+            })
+    }
+
+    fn replace_nesting_with(&self, replacement: &SubclassSelector) -> CompoundSelector {
+        let subclasses = self
+            .subclasses
+            .iter()
+            .map(|subclass| match subclass {
+                SubclassSelector::Nesting => replacement.clone(),
+                SubclassSelector::PseudoClass(class) => {
+                    SubclassSelector::PseudoClass(class.replace_nesting_with(replacement))
+                }
+                subclass => subclass.clone(),
+            })
+            .collect();
+        let pseudo_elements = self
+            .pseudo_elements
+            .iter()
+            .map(|pseudo_element| PseudoElementSelector {
+                element: match &pseudo_element.element {
+                    PseudoElement::Slotted(compound) => {
+                        PseudoElement::Slotted(Box::new(compound.replace_nesting_with(replacement)))
+                    }
+                    element => element.clone(),
+                },
+                classes: pseudo_element.classes.clone(),
+            })
+            .collect();
+        CompoundSelector {
+            type_selector: self.type_selector.clone(),
+            subclasses,
+            pseudo_elements,
+        }
+    }
+}
+impl PseudoClass {
+    fn contains_nesting(&self) -> bool {
+        match self {
+            PseudoClass::Is(selectors)
+            | PseudoClass::Where(selectors)
+            | PseudoClass::Not(selectors)
+            | PseudoClass::NthChild {
+                of: Some(selectors),
+                ..
+            }
+            | PseudoClass::NthLastChild {
+                of: Some(selectors),
+                ..
+            } => selectors.contains_nesting(),
+            PseudoClass::Has(selectors) => selectors
+                .iter()
+                .any(|relative| relative.selector.contains_nesting()),
+            PseudoClass::Host(Some(compound)) | PseudoClass::HostContext(compound) => {
+                compound.contains_nesting()
+            }
+            _ => false,
+        }
+    }
+
+    fn replace_nesting_with(&self, replacement: &SubclassSelector) -> PseudoClass {
+        match self {
+            PseudoClass::Is(selectors) => {
+                PseudoClass::Is(selectors.replace_nesting_with(replacement))
+            }
+            PseudoClass::Where(selectors) => {
+                PseudoClass::Where(selectors.replace_nesting_with(replacement))
+            }
+            PseudoClass::Not(selectors) => {
+                PseudoClass::Not(selectors.replace_nesting_with(replacement))
+            }
+            PseudoClass::NthChild { nth, of } => PseudoClass::NthChild {
+                nth: *nth,
+                of: of.as_ref().map(|of| of.replace_nesting_with(replacement)),
+            },
+            PseudoClass::NthLastChild { nth, of } => PseudoClass::NthLastChild {
+                nth: *nth,
+                of: of.as_ref().map(|of| of.replace_nesting_with(replacement)),
+            },
+            PseudoClass::Has(selectors) => PseudoClass::Has(
+                selectors
+                    .iter()
+                    .map(|relative| RelativeSelector {
+                        combinator: relative.combinator,
+                        selector: relative.selector.replace_nesting_with(replacement),
+                    })
+                    .collect(),
+            ),
+            PseudoClass::Host(Some(compound)) => {
+                PseudoClass::Host(Some(Box::new(compound.replace_nesting_with(replacement))))
+            }
+            PseudoClass::HostContext(compound) => {
+                PseudoClass::HostContext(Box::new(compound.replace_nesting_with(replacement)))
+            }
+            class => class.clone(),
+        }
+    }
+}
+
+//* Namespaces
+impl SelectorList {
+    /// Call `rename` with each namespace prefix (like `svg` in `svg|a`),
+    /// including in pseudo-class arguments, so that it can be changed
+    pub fn rename_namespaces(&mut self, rename: &mut dyn FnMut(&mut StdString)) {
+        for selector in &mut self.0 {
+            selector.rename_namespaces(rename);
+        }
+    }
+}
+impl ComplexSelector {
+    /// See [`SelectorList::rename_namespaces`]
+    pub fn rename_namespaces(&mut self, rename: &mut dyn FnMut(&mut StdString)) {
+        self.first.rename_namespaces(rename);
+        for (_, compound) in &mut self.rest {
+            compound.rename_namespaces(rename);
+        }
+    }
+}
+impl CompoundSelector {
+    fn rename_namespaces(&mut self, rename: &mut dyn FnMut(&mut StdString)) {
+        if let Some(TypeSelector {
+            namespace: Some(NamespacePrefix::Named(prefix)),
+            ..
+        }) = &mut self.type_selector
+        {
+            rename(prefix);
+        }
+        for subclass in &mut self.subclasses {
+            match subclass {
+                SubclassSelector::Attribute(AttributeSelector {
+                    namespace: Some(NamespacePrefix::Named(prefix)),
+                    ..
+                }) => rename(prefix),
+                SubclassSelector::PseudoClass(class) => class.rename_namespaces(rename),
+                _ => {}
+            }
+        }
+        for pseudo_element in &mut self.pseudo_elements {
+            if let PseudoElement::Slotted(compound) = &mut pseudo_element.element {
+                compound.rename_namespaces(rename);
+            }
+            for class in &mut pseudo_element.classes {
+                class.rename_namespaces(rename);
+            }
+        }
+    }
+}
+impl PseudoClass {
+    fn rename_namespaces(&mut self, rename: &mut dyn FnMut(&mut StdString)) {
+        match self {
+            PseudoClass::Is(selectors)
+            | PseudoClass::Where(selectors)
+            | PseudoClass::Not(selectors)
+            | PseudoClass::NthChild {
+                of: Some(selectors),
+                ..
+            }
+            | PseudoClass::NthLastChild {
+                of: Some(selectors),
+                ..
+            } => selectors.rename_namespaces(rename),
+            PseudoClass::Has(selectors) => {
+                for relative in selectors {
+                    relative.selector.rename_namespaces(rename);
+                }
+            }
+            PseudoClass::Host(Some(compound)) | PseudoClass::HostContext(compound) => {
+                compound.rename_namespaces(rename)
+            }
+            _ => {}
+        }
+    }
+}
+
+//* Serialization
+// https://drafts.csswg.org/cssom-1/#serializing-selectors
+
+impl fmt::Display for SelectorList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, selector) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", selector)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ComplexSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.first)?;
+        for (combinator, compound) in &self.rest {
+            write!(f, "{}{}", combinator, compound)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Combinator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Combinator::Descendant => " ",
+            Combinator::Child => " > ",
+            Combinator::NextSibling => " + ",
+            Combinator::SubsequentSibling => " ~ ",
+            Combinator::Column => " || ",
+        })
+    }
+}
+
+impl fmt::Display for CompoundSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.type_selector {
+            Some(type_selector) => write!(f, "{}", type_selector)?,
+            None if self.subclasses.is_empty() && self.pseudo_elements.is_empty() => {
+                f.write_str("*")?
+            }
+            None => {}
+        }
+        for subclass in &self.subclasses {
+            write!(f, "{}", subclass)?;
+        }
+        for pseudo_element in &self.pseudo_elements {
+            write!(f, "::{}", pseudo_element.element)?;
+            for class in &pseudo_element.classes {
+                write!(f, ":{}", class)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TypeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(namespace) = &self.namespace {
+            write!(f, "{}", namespace)?;
+        }
+        match &self.name {
+            Some(name) => write_identifier(f, name),
+            None => f.write_str("*"),
+        }
+    }
+}
+
+impl fmt::Display for NamespacePrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NamespacePrefix::Any => f.write_str("*|"),
+            NamespacePrefix::None => f.write_str("|"),
+            NamespacePrefix::Named(prefix) => {
+                write_identifier(f, prefix)?;
+                f.write_str("|")
+            }
+        }
+    }
+}
+
+impl fmt::Display for SubclassSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubclassSelector::Id(id) => {
+                f.write_str("#")?;
+                write_identifier(f, id)
+            }
+            SubclassSelector::Class(class) => {
+                f.write_str(".")?;
+                write_identifier(f, class)
+            }
+            SubclassSelector::Attribute(attribute) => write!(f, "{}", attribute),
+            SubclassSelector::PseudoClass(class) => write!(f, ":{}", class),
+            SubclassSelector::Nesting => f.write_str("&"),
+        }
+    }
+}
+
+impl fmt::Display for AttributeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[")?;
+        if let Some(namespace) = &self.namespace {
+            write!(f, "{}", namespace)?;
+        }
+        write_identifier(f, &self.name)?;
+        if let Some(matcher) = &self.matcher {
+            f.write_str(match matcher.operator {
+                AttributeOperator::Equals => "=",
+                AttributeOperator::Includes => "~=",
+                AttributeOperator::DashMatch => "|=",
+                AttributeOperator::Prefix => "^=",
+                AttributeOperator::Suffix => "$=",
+                AttributeOperator::Substring => "*=",
+            })?;
+            write_string(f, &matcher.value)?;
+            match matcher.case_sensitivity {
+                Some(AttributeCaseSensitivity::Insensitive) => f.write_str(" i")?,
+                Some(AttributeCaseSensitivity::Sensitive) => f.write_str(" s")?,
+                None => {}
+            }
+        }
+        f.write_str("]")
+    }
+}
+
+impl fmt::Display for RelativeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.combinator {
+            Combinator::Descendant => {}
+            combinator => write!(f, "{} ", combinator.to_string().trim())?,
+        }
+        write!(f, "{}", self.selector)
+    }
+}
+
+impl fmt::Display for PseudoClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nth = |f: &mut fmt::Formatter<'_>, name, nth, of: &Option<SelectorList>| {
+            write!(f, "{}({}", name, nth)?;
+            if let Some(of) = of {
+                write!(f, " of {}", of)?;
+            }
+            f.write_str(")")
+        };
+        match self {
+            PseudoClass::Dir(TextDirection::Ltr) => f.write_str("dir(ltr)"),
+            PseudoClass::Dir(TextDirection::Rtl) => f.write_str("dir(rtl)"),
+            PseudoClass::Has(selectors) => {
+                f.write_str("has(")?;
+                for (i, selector) in selectors.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", selector)?;
+                }
+                f.write_str(")")
+            }
+            PseudoClass::Host(None) => f.write_str("host"),
+            PseudoClass::Host(Some(compound)) => write!(f, "host({})", compound),
+            PseudoClass::HostContext(compound) => write!(f, "host-context({})", compound),
+            PseudoClass::Is(selectors) => write!(f, "is({})", selectors),
+            PseudoClass::Lang(ranges) => {
+                f.write_str("lang(")?;
+                for (i, range) in ranges.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write_string(f, range)?;
+                }
+                f.write_str(")")
+            }
+            PseudoClass::Not(selectors) => write!(f, "not({})", selectors),
+            PseudoClass::NthChild { nth: n, of } => nth(f, "nth-child", n, of),
+            PseudoClass::NthLastChild { nth: n, of } => nth(f, "nth-last-child", n, of),
+            PseudoClass::NthLastOfType(n) => nth(f, "nth-last-of-type", n, &None),
+            PseudoClass::NthOfType(n) => nth(f, "nth-of-type", n, &None),
+            PseudoClass::Where(selectors) => write!(f, "where({})", selectors),
+            _ => f.write_str(self.into()),
+        }
+    }
+}
+
+/// https://drafts.csswg.org/cssom-1/#serialize-an-anb-value
+impl fmt::Display for AnPlusB {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.a {
+            0 => return write!(f, "{}", self.b),
+            1 => f.write_str("n")?,
+            -1 => f.write_str("-n")?,
+            a => write!(f, "{}n", a)?,
+        }
+        match self.b {
+            0 => Ok(()),
+            b if b > 0 => write!(f, "+{}", b),
+            b => write!(f, "{}", b),
+        }
+    }
+}
+
+impl fmt::Display for PseudoElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PseudoElement::Highlight(name) => {
+                f.write_str("highlight(")?;
+                write_identifier(f, name)?;
+                f.write_str(")")
+            }
+            PseudoElement::Part(names) => {
+                f.write_str("part(")?;
+                for (i, name) in names.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    write_identifier(f, name)?;
+                }
+                f.write_str(")")
+            }
+            PseudoElement::Slotted(compound) => write!(f, "slotted({})", compound),
+            _ => f.write_str(self.into()),
+        }
+    }
+}
+
+/// https://drafts.csswg.org/cssom-1/#serialize-an-identifier
+pub(crate) fn write_identifier(f: &mut impl fmt::Write, identifier: &str) -> fmt::Result {
+    let chars: Vec<char> = identifier.chars().collect();
+    if chars == ['-'] {
+        return f.write_str("\\-");
+    }
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            '\0' => f.write_char('\u{fffd}')?,
+            '\u{1}'..='\u{1f}' | '\u{7f}' => write!(f, "\\{:x} ", c as u32)?,
+            '0'..='9' if i == 0 || (i == 1 && chars[0] == '-') => write!(f, "\\{:x} ", c as u32)?,
+            c if c == '-' || c == '_' || c.is_ascii_alphanumeric() || !c.is_ascii() => {
+                f.write_char(c)?
+            }
+            c => write!(f, "\\{}", c)?,
+        }
+    }
+    Ok(())
+}
+
+/// https://drafts.csswg.org/cssom-1/#serialize-a-string
+pub(crate) fn write_string(f: &mut impl fmt::Write, string: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in string.chars() {
+        match c {
+            '\0' => f.write_char('\u{fffd}')?,
+            '\u{1}'..='\u{1f}' | '\u{7f}' => write!(f, "\\{:x} ", c as u32)?,
+            '"' | '\\' => write!(f, "\\{}", c)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matching(a: i32, b: i32) -> Vec<i32> {
+        (1..=10).filter(|&i| AnPlusB { a, b }.matches(i)).collect()
+    }
+
+    #[test]
+    fn an_plus_b() {
+        assert_eq!(matching(2, 1), [1, 3, 5, 7, 9]);
+        assert_eq!(matching(2, 0), [2, 4, 6, 8, 10]);
+        assert_eq!(matching(0, 3), [3]);
+        assert_eq!(matching(3, -2), [1, 4, 7, 10]);
+        // Negative steps count down from B
+        assert_eq!(matching(-1, 3), [1, 2, 3]);
+        assert_eq!(matching(-2, 5), [1, 3, 5]);
+        assert_eq!(matching(-1, 0), Vec::<i32>::new());
+        assert_eq!(matching(1, -5), (1..=10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn an_plus_b_extremes() {
+        // `index - b` would overflow an i32
+        assert!(!AnPlusB { a: 0, b: i32::MIN }.matches(1));
+        assert!(AnPlusB { a: 1, b: i32::MIN }.matches(1));
+        assert!(!AnPlusB { a: -1, b: i32::MIN }.matches(i32::MAX));
+        assert!(AnPlusB { a: i32::MIN, b: 1 }.matches(1));
+        assert!(!AnPlusB { a: i32::MAX, b: 0 }.matches(i32::MAX - 1));
+    }
+
+    #[cfg(feature = "parser")]
+    fn specificity(selectors: &str) -> (u32, u32, u32) {
+        let list: SelectorList = crate::parser::parse(selectors).unwrap();
+        let Specificity {
+            ids,
+            classes,
+            types,
+        } = list.specificity();
+        (ids, classes, types)
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn simple_specificity() {
+        assert_eq!(specificity("*"), (0, 0, 0));
+        assert_eq!(specificity("li"), (0, 0, 1));
+        assert_eq!(specificity("ul li"), (0, 0, 2));
+        assert_eq!(specificity("ul ol+li"), (0, 0, 3));
+        assert_eq!(specificity("h1 + *[rel=up]"), (0, 1, 1));
+        assert_eq!(specificity("ul ol li.red"), (0, 1, 3));
+        assert_eq!(specificity("li.red.level"), (0, 2, 1));
+        assert_eq!(specificity("#x34y"), (1, 0, 0));
+        assert_eq!(specificity("a:hover::before"), (0, 1, 2));
+        // The most specific selector of a list
+        assert_eq!(specificity("a, .b, #c d"), (1, 0, 1));
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn functional_pseudo_class_specificity() {
+        // The most specific argument
+        assert_eq!(specificity(":is(em, #foo)"), (1, 0, 0));
+        assert_eq!(specificity(":not(.a, span)"), (0, 1, 0));
+        assert_eq!(specificity(":has(> a, .b .c)"), (0, 2, 0));
+        // Nothing at all
+        assert_eq!(specificity(".a:where(#b, .c)"), (0, 1, 0));
+        // A pseudo-class plus the most specific argument
+        assert_eq!(specificity(":nth-child(2n of .a, #b)"), (1, 1, 0));
+        assert_eq!(specificity(":nth-child(2n)"), (0, 1, 0));
+        assert_eq!(specificity(":host(.a)"), (0, 2, 0));
+    }
+
+    #[cfg(feature = "parser")]
+    #[test]
+    fn nesting_specificity() {
+        // Outside of nested rules, `&` is `:scope`
+        assert_eq!(specificity("& .a"), (0, 2, 0));
+        assert_eq!(specificity(":is(&, #a)"), (1, 0, 0));
+
+        // In nested rules, it's the parent's most specific selector
+        let nested = |selectors: &str, parent: &str| {
+            let parent = crate::parser::parse::<SelectorList>(parent).unwrap();
+            let selectors = crate::parser::Ast::new(selectors)
+                .parse_nested_selector_list()
+                .unwrap();
+            let specificity = selectors.nested_specificity(&parent);
+            // Which is the same as once the nesting is replaced
+            assert_eq!(
+                specificity,
+                selectors.replace_nesting(&parent).specificity()
+            );
+            (specificity.ids, specificity.classes, specificity.types)
+        };
+        assert_eq!(nested("> .c", ".a, #b"), (1, 1, 0));
+        assert_eq!(nested("&:hover", "li"), (0, 1, 1));
+        assert_eq!(nested(".x &", "ul li"), (0, 1, 2));
+        assert_eq!(nested(":where(&) .c", "#a"), (0, 1, 0));
+        assert_eq!(nested(":not(&)", "#a"), (1, 0, 0));
+    }
+
+    #[test]
+    fn specificity_order() {
+        let id = Specificity {
+            ids: 1,
+            classes: 0,
+            types: 0,
+        };
+        let classes = Specificity {
+            ids: 0,
+            classes: 10,
+            types: 10,
+        };
+        assert!(id > classes);
+        assert!(Specificity::CLASS + Specificity::TYPE > Specificity::CLASS);
+    }
+}
@@ -0,0 +1,175 @@
+//! Feature queries, as described in
+//! https://drafts.csswg.org/css-conditional-5/#at-supports
+
+use std::string::String as StdString;
+
+use crate::{
+    at_rules::{FontFormat, FontTech},
+    selectors::ComplexSelector,
+};
+
+/// https://developer.mozilla.org/en-US/docs/Web/CSS/@supports#syntax
+#[derive(Clone, Debug, PartialEq)]
+pub enum SupportsCondition {
+    /// `(property: value)`, where the value is kept as written (without
+    /// `!important`) so that any property can be queried
+    Declaration {
+        property: StdString,
+        value: StdString,
+    },
+    /// `selector(...)`
+    Selector(ComplexSelector),
+    /// `font-tech(...)`
+    FontTech(FontTech),
+    /// `font-format(...)`
+    FontFormat(FontFormat),
+    Not(Box<SupportsCondition>),
+    And(Vec<SupportsCondition>),
+    Or(Vec<SupportsCondition>),
+    /// Anything else in parentheses or a function (including a `selector()`,
+    /// `font-tech()` or `font-format()` that can't be parsed), which is never
+    /// supported
+    GeneralEnclosed,
+}
+
+#[cfg(feature = "parser")]
+impl SupportsCondition {
+    /// Evaluate the condition for this crate with three-valued logic, where
+    /// `None` is unknown
+    ///
+    /// A declaration is supported if its property has a parser that accepts
+    /// the value, so that rules for other properties (like fallbacks for
+    /// older browsers) can be told apart. Only animation and transition
+    /// properties have parsers, so a declaration for any other known
+    /// property is unknown rather than unsupported, since this crate can't
+    /// tell whether the value is valid.
+    ///
+    /// https://drafts.csswg.org/css-conditional-5/#evaluate-a-supports-condition
+    pub fn evaluate(&self) -> Option<bool> {
+        match self {
+            SupportsCondition::Declaration { property, value } => {
+                // Custom properties accept any value
+                if property.starts_with("--") {
+                    return Some(true);
+                }
+                let mut ast = crate::parser::Ast::new(value);
+                match crate::properties::Property::from_declaration(property, &mut ast)
+                    .and_then(|_| ast.expect_exhausted())
+                {
+                    Ok(()) => Some(true),
+                    Err(crate::parser::ParseError::UnsupportedProperty(_)) => None,
+                    Err(_) => Some(false),
+                }
+            }
+            // Selectors and font keywords have already been parsed
+            SupportsCondition::Selector(_)
+            | SupportsCondition::FontTech(_)
+            | SupportsCondition::FontFormat(_) => Some(true),
+            SupportsCondition::Not(condition) => condition.evaluate().map(|matches| !matches),
+            SupportsCondition::And(conditions) => {
+                let mut result = Some(true);
+                for condition in conditions {
+                    match condition.evaluate() {
+                        Some(false) => return Some(false),
+                        None => result = None,
+                        Some(true) => {}
+                    }
+                }
+                result
+            }
+            SupportsCondition::Or(conditions) => {
+                let mut result = Some(false);
+                for condition in conditions {
+                    match condition.evaluate() {
+                        Some(true) => return Some(true),
+                        None => result = None,
+                        Some(false) => {}
+                    }
+                }
+                result
+            }
+            SupportsCondition::GeneralEnclosed => Some(false),
+        }
+    }
+
+    /// Whether the condition is true, where an unknown result doesn't match
+    pub fn matches(&self) -> bool {
+        self.evaluate().unwrap_or(false)
+    }
+}
+
+#[cfg(all(test, feature = "parser"))]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn evaluate(condition: &str) -> Option<bool> {
+        parse::<SupportsCondition>(condition).unwrap().evaluate()
+    }
+
+    #[test]
+    fn declarations() {
+        assert_eq!(evaluate("(transition: opacity 1s)"), Some(true));
+        assert_eq!(evaluate("(animation-duration: 1s !important)"), Some(true));
+        assert_eq!(evaluate("(transition-duration: red)"), Some(false));
+        assert_eq!(evaluate("(transition-duration: 1s 2s)"), Some(false));
+        assert_eq!(evaluate("(--anything: { whatever })"), Some(true));
+        // Unknown properties are unsupported
+        assert_eq!(evaluate("(no-such-property: 1)"), Some(false));
+        // Known properties without a parser can't be checked
+        assert_eq!(evaluate("(display: grid)"), None);
+        assert_eq!(evaluate("(display: not-a-display)"), None);
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(evaluate("selector(a > b)"), Some(true));
+        assert_eq!(evaluate("selector(a >)"), Some(false));
+        assert_eq!(evaluate("font-tech(color-colrv1)"), Some(true));
+        assert_eq!(evaluate("font-format(woff2)"), Some(true));
+        assert_eq!(evaluate("font-format(unknown)"), Some(false));
+        assert_eq!(evaluate("unknown(anything)"), Some(false));
+        assert_eq!(evaluate("(anything at all)"), Some(false));
+    }
+
+    #[test]
+    fn logic() {
+        assert_eq!(evaluate("not (transition-duration: red)"), Some(true));
+        assert_eq!(
+            evaluate("(transition: none) and (animation: none)"),
+            Some(true)
+        );
+        assert_eq!(
+            evaluate("(transition: none) and (transition-duration: red)"),
+            Some(false)
+        );
+        assert_eq!(
+            evaluate("(transition-duration: red) or (transition: none)"),
+            Some(true)
+        );
+        assert_eq!(
+            evaluate("((transition-duration: red) or (transition: none)) and (--x: 1)"),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(evaluate("not (display: grid)"), None);
+        assert_eq!(
+            evaluate("(display: grid) and (transition-duration: red)"),
+            Some(false)
+        );
+        assert_eq!(evaluate("(display: grid) and (transition: none)"), None);
+        assert_eq!(
+            evaluate("(display: grid) or (transition: none)"),
+            Some(true)
+        );
+        assert_eq!(
+            evaluate("(display: grid) or (transition-duration: red)"),
+            None
+        );
+        let condition = parse::<SupportsCondition>("not (display: grid)").unwrap();
+        assert!(!condition.matches());
+    }
+}
@@ -0,0 +1,939 @@
+//! Transform matrices, and their decomposition for interpolation, as described
+//! in https://drafts.csswg.org/css-transforms-2/#interpolation-of-transforms
+
+use crate::data_types::{LengthContext, TransformFunction};
+
+/// The values needed to resolve the lengths and percentages of a transform
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransformContext {
+    pub lengths: LengthContext,
+    /// Width of the reference box (see `transform-box`), which horizontal
+    /// percentages are relative to
+    pub reference_width: f32,
+    /// Height of the reference box, which vertical percentages are relative
+    /// to
+    pub reference_height: f32,
+}
+
+/// A 4x4 transformation matrix, indexed as `[row][column]` (for column
+/// vectors, so the translation is in the last column)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Matrix3d(pub [[f64; 4]; 4]);
+
+impl Default for Matrix3d {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Matrix3d {
+    pub const IDENTITY: Self = Self([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    /// The matrix of `matrix(a, b, c, d, tx, ty)`
+    pub fn from_2d(a: f64, b: f64, c: f64, d: f64, tx: f64, ty: f64) -> Self {
+        Self([
+            [a, c, 0.0, tx],
+            [b, d, 0.0, ty],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// The matrix of `matrix3d()`, whose values are in column-major order
+    pub fn from_column_major(values: [f64; 16]) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, value) in values.iter().enumerate() {
+            m[i % 4][i / 4] = *value;
+        }
+        Self(m)
+    }
+
+    /// The values of the equivalent `matrix3d()`
+    pub fn to_column_major(&self) -> [f64; 16] {
+        let mut values = [0.0; 16];
+        for (i, value) in values.iter_mut().enumerate() {
+            *value = self.0[i % 4][i / 4];
+        }
+        values
+    }
+
+    pub fn translate(x: f64, y: f64, z: f64) -> Self {
+        let mut m = Self::IDENTITY;
+        m.0[0][3] = x;
+        m.0[1][3] = y;
+        m.0[2][3] = z;
+        m
+    }
+
+    pub fn scale(x: f64, y: f64, z: f64) -> Self {
+        let mut m = Self::IDENTITY;
+        m.0[0][0] = x;
+        m.0[1][1] = y;
+        m.0[2][2] = z;
+        m
+    }
+
+    /// A clockwise rotation of `angle` radians around the vector `(x, y, z)`.
+    /// A zero vector results in the identity matrix.
+    pub fn rotate(x: f64, y: f64, z: f64, angle: f64) -> Self {
+        let length = (x * x + y * y + z * z).sqrt();
+        if length == 0.0 {
+            return Self::IDENTITY;
+        }
+        let (x, y, z) = (x / length, y / length, z / length);
+        let sc = (angle / 2.0).sin() * (angle / 2.0).cos();
+        let sq = (angle / 2.0).sin().powi(2);
+        Self([
+            [
+                1.0 - 2.0 * (y * y + z * z) * sq,
+                2.0 * (x * y * sq - z * sc),
+                2.0 * (x * z * sq + y * sc),
+                0.0,
+            ],
+            [
+                2.0 * (x * y * sq + z * sc),
+                1.0 - 2.0 * (x * x + z * z) * sq,
+                2.0 * (y * z * sq - x * sc),
+                0.0,
+            ],
+            [
+                2.0 * (x * z * sq - y * sc),
+                2.0 * (y * z * sq + x * sc),
+                1.0 - 2.0 * (x * x + y * y) * sq,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// A skew of `x` and `y` radians along the respective axes
+    pub fn skew(x: f64, y: f64) -> Self {
+        Self::from_2d(1.0, y.tan(), x.tan(), 1.0, 0.0, 0.0)
+    }
+
+    /// `perspective()`, where `None` is an infinite distance. Distances below
+    /// 1px are clamped to 1px.
+    pub fn perspective(distance: Option<f64>) -> Self {
+        let mut m = Self::IDENTITY;
+        if let Some(distance) = distance {
+            m.0[3][2] = -1.0 / distance.max(1.0);
+        }
+        m
+    }
+
+    /// The matrix of a single transform function
+    pub fn from_function(function: &TransformFunction, context: &TransformContext) -> Self {
+        Primitive::new(function, context).to_matrix()
+    }
+
+    /// The matrix of a transform function list, which is the product of each
+    /// function's matrix from left to right
+    pub fn from_functions(functions: &[TransformFunction], context: &TransformContext) -> Self {
+        functions.iter().fold(Self::IDENTITY, |matrix, function| {
+            matrix.multiply(&Self::from_function(function, context))
+        })
+    }
+
+    /// The product `self × other`, i.e. `other` is applied first
+    pub fn multiply(&self, other: &Self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (r, row) in m.iter_mut().enumerate() {
+            for (c, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.0[r][k] * other.0[k][c]).sum();
+            }
+        }
+        Self(m)
+    }
+
+    /// Apply to a point, dividing by the resulting `w`
+    pub fn transform_point(&self, point: [f64; 3]) -> [f64; 3] {
+        let p = [point[0], point[1], point[2], 1.0];
+        let mut out = [0.0; 4];
+        for (r, value) in out.iter_mut().enumerate() {
+            *value = (0..4).map(|k| self.0[r][k] * p[k]).sum();
+        }
+        [out[0] / out[3], out[1] / out[3], out[2] / out[3]]
+    }
+
+    /// Whether this matrix is equivalent to a `matrix()`
+    pub fn is_2d(&self) -> bool {
+        let m = &self.0;
+        m[0][2] == 0.0
+            && m[1][2] == 0.0
+            && m[2][0] == 0.0
+            && m[2][1] == 0.0
+            && m[2][2] == 1.0
+            && m[2][3] == 0.0
+            && m[3][0] == 0.0
+            && m[3][1] == 0.0
+            && m[3][2] == 0.0
+            && m[3][3] == 1.0
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (r, row) in m.iter_mut().enumerate() {
+            for (c, value) in row.iter_mut().enumerate() {
+                *value = self.0[c][r];
+            }
+        }
+        Self(m)
+    }
+
+    pub fn determinant(&self) -> f64 {
+        // Gaussian elimination with partial pivoting
+        let mut m = self.0;
+        let mut determinant = 1.0;
+        for c in 0..4 {
+            let pivot = (c..4)
+                .max_by(|&a, &b| m[a][c].abs().total_cmp(&m[b][c].abs()))
+                .unwrap();
+            if m[pivot][c] == 0.0 {
+                return 0.0;
+            }
+            if pivot != c {
+                m.swap(pivot, c);
+                determinant = -determinant;
+            }
+            determinant *= m[c][c];
+            let pivot_row = m[c];
+            for row in m.iter_mut().skip(c + 1) {
+                let factor = row[c] / pivot_row[c];
+                for (value, pivot_value) in row.iter_mut().zip(&pivot_row).skip(c) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+        determinant
+    }
+
+    /// Returns `None` if the matrix is singular
+    pub fn inverse(&self) -> Option<Self> {
+        // Gauss-Jordan elimination with partial pivoting
+        let mut m = self.0;
+        let mut inverse = Self::IDENTITY.0;
+        for c in 0..4 {
+            let pivot = (c..4)
+                .max_by(|&a, &b| m[a][c].abs().total_cmp(&m[b][c].abs()))
+                .unwrap();
+            if m[pivot][c] == 0.0 {
+                return None;
+            }
+            m.swap(pivot, c);
+            inverse.swap(pivot, c);
+
+            let scale = m[c][c];
+            for k in 0..4 {
+                m[c][k] /= scale;
+                inverse[c][k] /= scale;
+            }
+            for r in 0..4 {
+                if r != c {
+                    let factor = m[r][c];
+                    for k in 0..4 {
+                        m[r][k] -= factor * m[c][k];
+                        inverse[r][k] -= factor * inverse[c][k];
+                    }
+                }
+            }
+        }
+        Some(Self(inverse))
+    }
+
+    /// Decompose into translation, scale, skew, perspective and a rotation
+    /// quaternion. Returns `None` if the matrix can't be decomposed.
+    ///
+    /// https://drafts.csswg.org/css-transforms-2/#decomposing-a-3d-matrix
+    pub fn decompose(&self) -> Option<Decomposed3d> {
+        let mut m = self.0;
+        if m[3][3] == 0.0 {
+            return None;
+        }
+        let w = m[3][3];
+        for row in m.iter_mut() {
+            for value in row.iter_mut() {
+                *value /= w;
+            }
+        }
+
+        // The matrix without perspective, which is also used to check that
+        // the upper 3x3 isn't singular
+        let mut affine = Matrix3d(m);
+        affine.0[3] = [0.0, 0.0, 0.0, 1.0];
+        if affine.determinant() == 0.0 {
+            return None;
+        }
+
+        let perspective = if m[3][0] != 0.0 || m[3][1] != 0.0 || m[3][2] != 0.0 {
+            // Solve `perspective × affine = last row`
+            let inverse = affine.inverse()?;
+            let mut perspective = [0.0; 4];
+            for (i, value) in perspective.iter_mut().enumerate() {
+                *value = (0..4).map(|j| m[3][j] * inverse.0[j][i]).sum();
+            }
+            perspective
+        } else {
+            [0.0, 0.0, 0.0, 1.0]
+        };
+
+        let translate = [m[0][3], m[1][3], m[2][3]];
+
+        // The images of each axis (the columns of the upper 3x3)
+        let mut columns = [[0.0; 3]; 3];
+        for (c, column) in columns.iter_mut().enumerate() {
+            for (r, value) in column.iter_mut().enumerate() {
+                *value = m[r][c];
+            }
+        }
+
+        let mut scale = [0.0; 3];
+        let mut skew = [0.0; 3];
+
+        scale[0] = length(columns[0]);
+        columns[0] = normalize(columns[0]);
+
+        skew[0] = dot(columns[0], columns[1]);
+        columns[1] = combine(columns[1], columns[0], 1.0, -skew[0]);
+        scale[1] = length(columns[1]);
+        columns[1] = normalize(columns[1]);
+        skew[0] /= scale[1];
+
+        skew[1] = dot(columns[0], columns[2]);
+        columns[2] = combine(columns[2], columns[0], 1.0, -skew[1]);
+        skew[2] = dot(columns[1], columns[2]);
+        columns[2] = combine(columns[2], columns[1], 1.0, -skew[2]);
+        scale[2] = length(columns[2]);
+        columns[2] = normalize(columns[2]);
+        skew[1] /= scale[2];
+        skew[2] /= scale[2];
+
+        // The axes are now orthonormal, so check for a coordinate system flip
+        if dot(columns[0], cross(columns[1], columns[2])) < 0.0 {
+            for i in 0..3 {
+                scale[i] = -scale[i];
+                for value in columns[i].iter_mut() {
+                    *value = -*value;
+                }
+            }
+        }
+
+        // `r[row][column]` of the rotation matrix
+        let r = |row: usize, column: usize| columns[column][row];
+        let mut quaternion = [
+            0.5 * (1.0 + r(0, 0) - r(1, 1) - r(2, 2)).max(0.0).sqrt(),
+            0.5 * (1.0 - r(0, 0) + r(1, 1) - r(2, 2)).max(0.0).sqrt(),
+            0.5 * (1.0 - r(0, 0) - r(1, 1) + r(2, 2)).max(0.0).sqrt(),
+            0.5 * (1.0 + r(0, 0) + r(1, 1) + r(2, 2)).max(0.0).sqrt(),
+        ];
+        if r(1, 2) > r(2, 1) {
+            quaternion[0] = -quaternion[0];
+        }
+        if r(2, 0) > r(0, 2) {
+            quaternion[1] = -quaternion[1];
+        }
+        if r(0, 1) > r(1, 0) {
+            quaternion[2] = -quaternion[2];
+        }
+
+        Some(Decomposed3d {
+            translate,
+            scale,
+            skew,
+            perspective,
+            quaternion,
+        })
+    }
+
+    /// Decompose a 2D matrix into translation, scale, rotation and the
+    /// remaining 2x2 matrix. Returns `None` if the matrix isn't 2D.
+    ///
+    /// https://drafts.csswg.org/css-transforms-1/#decomposing-a-2d-matrix
+    pub fn decompose_2d(&self) -> Option<Decomposed2d> {
+        if !self.is_2d() {
+            return None;
+        }
+        let m = &self.0;
+        let (mut row0x, mut row0y) = (m[0][0], m[1][0]);
+        let (mut row1x, mut row1y) = (m[0][1], m[1][1]);
+        let translate = [m[0][3], m[1][3]];
+
+        let mut scale = [
+            (row0x * row0x + row0y * row0y).sqrt(),
+            (row1x * row1x + row1y * row1y).sqrt(),
+        ];
+
+        // If the determinant is negative, one axis was flipped: flip the axis
+        // with the minimum unit vector dot product
+        if row0x * row1y - row0y * row1x < 0.0 {
+            if row0x < row1y {
+                scale[0] = -scale[0];
+            } else {
+                scale[1] = -scale[1];
+            }
+        }
+
+        if scale[0] != 0.0 {
+            row0x /= scale[0];
+            row0y /= scale[0];
+        }
+        if scale[1] != 0.0 {
+            row1x /= scale[1];
+            row1y /= scale[1];
+        }
+
+        let angle = row0y.atan2(row0x);
+        if angle != 0.0 {
+            // Remove the rotation: rotate(-angle) is [cos, sin, -sin, cos],
+            // which is [row0x, -row0y, row0y, row0x] after normalization
+            let (sn, cs) = (-row0y, row0x);
+            let (m11, m12, m21, m22) = (row0x, row0y, row1x, row1y);
+            row0x = cs * m11 + sn * m21;
+            row0y = cs * m12 + sn * m22;
+            row1x = -sn * m11 + cs * m21;
+            row1y = -sn * m12 + cs * m22;
+        }
+
+        Some(Decomposed2d {
+            translate,
+            scale,
+            angle: angle.to_degrees(),
+            matrix: [row0x, row0y, row1x, row1y],
+        })
+    }
+
+    /// Interpolate between two matrices by decomposing them, using the 2D
+    /// decomposition if both are 2D.
+    ///
+    /// If either matrix can't be decomposed, this falls back to a discrete
+    /// step at `progress` 0.5.
+    ///
+    /// https://drafts.csswg.org/css-transforms-2/#matrix-interpolation
+    pub fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        let interpolated = if self.is_2d() && to.is_2d() {
+            self.decompose_2d()
+                .zip(to.decompose_2d())
+                .map(|(from, to)| from.interpolate(&to, progress).recompose())
+        } else {
+            self.decompose()
+                .zip(to.decompose())
+                .map(|(from, to)| from.interpolate(&to, progress).recompose())
+        };
+        interpolated.unwrap_or(if progress < 0.5 { *self } else { *to })
+    }
+}
+
+/// A 3D matrix decomposed for interpolation (see [`Matrix3d::decompose`])
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decomposed3d {
+    pub translate: [f64; 3],
+    pub scale: [f64; 3],
+    /// The XY, XZ and YZ shears
+    pub skew: [f64; 3],
+    pub perspective: [f64; 4],
+    /// The rotation as an `(x, y, z, w)` quaternion
+    pub quaternion: [f64; 4],
+}
+
+impl Decomposed3d {
+    /// Interpolate each component linearly, except for the quaternions which
+    /// use a spherical linear interpolation
+    ///
+    /// https://drafts.csswg.org/css-transforms-2/#interpolation-of-decomposed-3d-matrix-values
+    pub fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        Self {
+            translate: lerp_array(self.translate, to.translate, progress),
+            scale: lerp_array(self.scale, to.scale, progress),
+            skew: lerp_array(self.skew, to.skew, progress),
+            perspective: lerp_array(self.perspective, to.perspective, progress),
+            quaternion: slerp(self.quaternion, to.quaternion, progress),
+        }
+    }
+
+    /// https://drafts.csswg.org/css-transforms-2/#recomposing-to-a-3d-matrix
+    pub fn recompose(&self) -> Matrix3d {
+        let mut perspective = Matrix3d::IDENTITY;
+        perspective.0[3] = self.perspective;
+
+        let [x, y, z, w] = self.quaternion;
+        let rotation = Matrix3d([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - z * w),
+                2.0 * (x * z + y * w),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - x * w),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - y * w),
+                2.0 * (y * z + x * w),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ]);
+
+        let mut skew = Matrix3d::IDENTITY;
+        skew.0[0][1] = self.skew[0];
+        skew.0[0][2] = self.skew[1];
+        skew.0[1][2] = self.skew[2];
+
+        let [tx, ty, tz] = self.translate;
+        let [sx, sy, sz] = self.scale;
+        perspective
+            .multiply(&Matrix3d::translate(tx, ty, tz))
+            .multiply(&rotation)
+            .multiply(&skew)
+            .multiply(&Matrix3d::scale(sx, sy, sz))
+    }
+}
+
+/// A 2D matrix decomposed for interpolation (see [`Matrix3d::decompose_2d`])
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decomposed2d {
+    pub translate: [f64; 2],
+    pub scale: [f64; 2],
+    /// Rotation in degrees
+    pub angle: f64,
+    /// The remaining `[m11, m12, m21, m22]`
+    pub matrix: [f64; 4],
+}
+
+impl Decomposed2d {
+    /// https://drafts.csswg.org/css-transforms-1/#interpolation-of-decomposed-2d-matrix-values
+    pub fn interpolate(&self, to: &Self, progress: f64) -> Self {
+        let mut from = *self;
+        let mut to = *to;
+
+        // If the x-axis of one is flipped and the y-axis of the other is,
+        // convert to an unflipped rotation
+        if (from.scale[0] < 0.0 && to.scale[1] < 0.0) || (from.scale[1] < 0.0 && to.scale[0] < 0.0)
+        {
+            from.scale = [-from.scale[0], -from.scale[1]];
+            from.angle += if from.angle < 0.0 { 180.0 } else { -180.0 };
+        }
+
+        // Don't rotate the long way around
+        if from.angle == 0.0 {
+            from.angle = 360.0;
+        }
+        if to.angle == 0.0 {
+            to.angle = 360.0;
+        }
+        if (from.angle - to.angle).abs() > 180.0 {
+            if from.angle > to.angle {
+                from.angle -= 360.0;
+            } else {
+                to.angle -= 360.0;
+            }
+        }
+
+        Self {
+            translate: lerp_array(from.translate, to.translate, progress),
+            scale: lerp_array(from.scale, to.scale, progress),
+            angle: lerp(from.angle, to.angle, progress),
+            matrix: lerp_array(from.matrix, to.matrix, progress),
+        }
+    }
+
+    pub fn recompose(&self) -> Matrix3d {
+        let [m11, m12, m21, m22] = self.matrix;
+        let [tx, ty] = self.translate;
+        let [sx, sy] = self.scale;
+        Matrix3d::translate(tx, ty, 0.0)
+            .multiply(&Matrix3d::from_2d(m11, m12, m21, m22, 0.0, 0.0))
+            .multiply(&Matrix3d::rotate(0.0, 0.0, 1.0, self.angle.to_radians()))
+            .multiply(&Matrix3d::scale(sx, sy, 1.0))
+    }
+}
+
+/// Interpolate between two transform lists (where an empty list is `none`),
+/// resolving to a matrix.
+///
+/// The shorter list is padded with identity functions, then pairs of
+/// functions sharing a primitive (like `translateX()` and `translate()`) are
+/// interpolated individually. From the first pair that doesn't share a
+/// primitive, the rest of each list is interpolated as a matrix.
+///
+/// https://drafts.csswg.org/css-transforms-2/#interpolation-of-transforms
+pub fn interpolate_transforms(
+    from: &[TransformFunction],
+    to: &[TransformFunction],
+    progress: f64,
+    context: &TransformContext,
+) -> Matrix3d {
+    let mut from: Vec<_> = from.iter().map(|f| Primitive::new(f, context)).collect();
+    let mut to: Vec<_> = to.iter().map(|f| Primitive::new(f, context)).collect();
+    if from.len() < to.len() {
+        let padding: Vec<_> = to[from.len()..].iter().map(Primitive::identity).collect();
+        from.extend(padding);
+    } else {
+        let padding: Vec<_> = from[to.len()..].iter().map(Primitive::identity).collect();
+        to.extend(padding);
+    }
+
+    let mut matrix = Matrix3d::IDENTITY;
+    for (i, (a, b)) in from.iter().zip(&to).enumerate() {
+        match a.interpolate(b, progress) {
+            Some(interpolated) => matrix = matrix.multiply(&interpolated),
+            None => {
+                let product = |primitives: &[Primitive]| {
+                    primitives
+                        .iter()
+                        .fold(Matrix3d::IDENTITY, |matrix, primitive| {
+                            matrix.multiply(&primitive.to_matrix())
+                        })
+                };
+                let rest = product(&from[i..]).interpolate(&product(&to[i..]), progress);
+                return matrix.multiply(&rest);
+            }
+        }
+    }
+    matrix
+}
+
+/// A transform function resolved to its primitive (the most general function
+/// of its family, like `translate3d()` for `translateX()`)
+#[derive(Clone, Copy, Debug)]
+enum Primitive {
+    Translate([f64; 3]),
+    Scale([f64; 3]),
+    /// A normalized axis and an angle in radians
+    Rotate([f64; 3], f64),
+    /// Angles in radians
+    Skew([f64; 2]),
+    /// The inverse of the negated distance, which is 0 for `none`
+    Perspective(f64),
+    Matrix(Matrix3d),
+}
+
+impl Primitive {
+    fn new(function: &TransformFunction, context: &TransformContext) -> Self {
+        use TransformFunction::*;
+
+        let lengths = &context.lengths;
+        let x = |value: &crate::data_types::LengthPercentage| {
+            value.to_px(lengths, context.reference_width) as f64
+        };
+        let y = |value: &crate::data_types::LengthPercentage| {
+            value.to_px(lengths, context.reference_height) as f64
+        };
+        let number = |value: &crate::data_types::Number| value.0 as f64;
+        let angle = |value: &crate::data_types::Angle| value.to_radians() as f64;
+
+        match function {
+            Matrix { a, b, c, d, tx, ty } => Primitive::Matrix(self::Matrix3d::from_2d(
+                number(a),
+                number(b),
+                number(c),
+                number(d),
+                number(tx),
+                number(ty),
+            )),
+            Matrix3d { values } => {
+                let mut m = [0.0; 16];
+                for (value, number) in m.iter_mut().zip(values) {
+                    *value = number.0 as f64;
+                }
+                Primitive::Matrix(self::Matrix3d::from_column_major(m))
+            }
+            Perspective { distance } => Primitive::Perspective(match distance {
+                Some(distance) => -1.0 / (distance.to_px(lengths) as f64).max(1.0),
+                None => 0.0,
+            }),
+            Rotate { angle: a } | RotateZ { angle: a } => {
+                Primitive::Rotate([0.0, 0.0, 1.0], angle(a))
+            }
+            RotateX { angle: a } => Primitive::Rotate([1.0, 0.0, 0.0], angle(a)),
+            RotateY { angle: a } => Primitive::Rotate([0.0, 1.0, 0.0], angle(a)),
+            Rotate3d {
+                x: ax,
+                y: ay,
+                z: az,
+                angle: a,
+            } => {
+                let axis = [number(ax), number(ay), number(az)];
+                if length(axis) == 0.0 {
+                    Primitive::Rotate([0.0, 0.0, 1.0], 0.0)
+                } else {
+                    Primitive::Rotate(normalize(axis), angle(a))
+                }
+            }
+            Scale { x: sx, y: sy } => {
+                let sx = sx.value() as f64;
+                let sy = sy.as_ref().map_or(sx, |sy| sy.value() as f64);
+                Primitive::Scale([sx, sy, 1.0])
+            }
+            Scale3d {
+                x: sx,
+                y: sy,
+                z: sz,
+            } => Primitive::Scale([sx.value() as f64, sy.value() as f64, sz.value() as f64]),
+            ScaleX { x: sx } => Primitive::Scale([sx.value() as f64, 1.0, 1.0]),
+            ScaleY { y: sy } => Primitive::Scale([1.0, sy.value() as f64, 1.0]),
+            ScaleZ { z: sz } => Primitive::Scale([1.0, 1.0, sz.value() as f64]),
+            Skew { x: ax, y: ay } => Primitive::Skew([angle(ax), ay.as_ref().map_or(0.0, angle)]),
+            SkewX { x: ax } => Primitive::Skew([angle(ax), 0.0]),
+            SkewY { y: ay } => Primitive::Skew([0.0, angle(ay)]),
+            Translate { x: tx, y: ty } => {
+                Primitive::Translate([x(tx), ty.as_ref().map_or(0.0, y), 0.0])
+            }
+            Translate3d {
+                x: tx,
+                y: ty,
+                z: tz,
+            } => Primitive::Translate([x(tx), y(ty), tz.to_px(lengths) as f64]),
+            TranslateX { x: tx } => Primitive::Translate([x(tx), 0.0, 0.0]),
+            TranslateY { y: ty } => Primitive::Translate([0.0, y(ty), 0.0]),
+            TranslateZ { z: tz } => Primitive::Translate([0.0, 0.0, tz.to_px(lengths) as f64]),
+        }
+    }
+
+    /// The identity function with the same primitive
+    fn identity(&self) -> Self {
+        match self {
+            Primitive::Translate(_) => Primitive::Translate([0.0; 3]),
+            Primitive::Scale(_) => Primitive::Scale([1.0; 3]),
+            Primitive::Rotate(axis, _) => Primitive::Rotate(*axis, 0.0),
+            Primitive::Skew(_) => Primitive::Skew([0.0; 2]),
+            Primitive::Perspective(_) => Primitive::Perspective(0.0),
+            Primitive::Matrix(_) => Primitive::Matrix(Matrix3d::IDENTITY),
+        }
+    }
+
+    fn to_matrix(self) -> Matrix3d {
+        match self {
+            Primitive::Translate([x, y, z]) => Matrix3d::translate(x, y, z),
+            Primitive::Scale([x, y, z]) => Matrix3d::scale(x, y, z),
+            Primitive::Rotate([x, y, z], angle) => Matrix3d::rotate(x, y, z, angle),
+            Primitive::Skew([x, y]) => Matrix3d::skew(x, y),
+            Primitive::Perspective(inverse) => {
+                let mut m = Matrix3d::IDENTITY;
+                m.0[3][2] = inverse;
+                m
+            }
+            Primitive::Matrix(m) => m,
+        }
+    }
+
+    /// Interpolate two functions sharing a primitive, or `None` if they don't
+    fn interpolate(&self, to: &Self, progress: f64) -> Option<Matrix3d> {
+        let primitive = match (*self, *to) {
+            (Primitive::Translate(a), Primitive::Translate(b)) => {
+                Primitive::Translate(lerp_array(a, b, progress))
+            }
+            (Primitive::Scale(a), Primitive::Scale(b)) => {
+                Primitive::Scale(lerp_array(a, b, progress))
+            }
+            (Primitive::Skew(a), Primitive::Skew(b)) => Primitive::Skew(lerp_array(a, b, progress)),
+            (Primitive::Perspective(a), Primitive::Perspective(b)) => {
+                Primitive::Perspective(lerp(a, b, progress))
+            }
+            (Primitive::Matrix(a), Primitive::Matrix(b)) => {
+                return Some(a.interpolate(&b, progress));
+            }
+            (Primitive::Rotate(axis_a, a), Primitive::Rotate(axis_b, b)) => {
+                // Rotations around the same axis (or where one is zero)
+                // interpolate their angle, which allows turns of over 180deg
+                if a == 0.0 || b == 0.0 || same_axis(axis_a, axis_b) {
+                    let axis = if a == 0.0 { axis_b } else { axis_a };
+                    Primitive::Rotate(axis, lerp(a, b, progress))
+                } else {
+                    let from = quaternion(axis_a, a);
+                    let to = quaternion(axis_b, b);
+                    let [x, y, z, w] = slerp(from, to, progress);
+                    // Back to an axis and angle
+                    let angle = 2.0 * w.clamp(-1.0, 1.0).acos();
+                    let axis = [x, y, z];
+                    if length(axis) == 0.0 {
+                        Primitive::Rotate([0.0, 0.0, 1.0], 0.0)
+                    } else {
+                        Primitive::Rotate(normalize(axis), angle)
+                    }
+                }
+            }
+            _ => return None,
+        };
+        Some(primitive.to_matrix())
+    }
+}
+
+fn same_axis(a: [f64; 3], b: [f64; 3]) -> bool {
+    a.iter().zip(&b).all(|(a, b)| (a - b).abs() < 1e-6)
+}
+
+/// The quaternion of a rotation of `angle` radians around a normalized axis
+pub(crate) fn quaternion(axis: [f64; 3], angle: f64) -> [f64; 4] {
+    let (sin, cos) = (angle / 2.0).sin_cos();
+    [axis[0] * sin, axis[1] * sin, axis[2] * sin, cos]
+}
+
+/// https://drafts.csswg.org/css-transforms-2/#interpolation-of-decomposed-3d-matrix-values
+pub(crate) fn slerp(from: [f64; 4], to: [f64; 4], progress: f64) -> [f64; 4] {
+    let product = (0..4)
+        .map(|i| from[i] * to[i])
+        .sum::<f64>()
+        .clamp(-1.0, 1.0);
+    if product.abs() == 1.0 {
+        return from;
+    }
+    let theta = product.acos();
+    let w = (progress * theta).sin() / (1.0 - product * product).sqrt();
+    let scale = (progress * theta).cos() - product * w;
+    let mut result = [0.0; 4];
+    for (i, value) in result.iter_mut().enumerate() {
+        *value = from[i] * scale + to[i] * w;
+    }
+    result
+}
+
+fn lerp(from: f64, to: f64, progress: f64) -> f64 {
+    from + (to - from) * progress
+}
+
+fn lerp_array<const N: usize>(from: [f64; N], to: [f64; N], progress: f64) -> [f64; N] {
+    let mut result = from;
+    for (value, to) in result.iter_mut().zip(&to) {
+        *value = lerp(*value, *to, progress);
+    }
+    result
+}
+
+fn length(v: [f64; 3]) -> f64 {
+    dot(v, v).sqrt()
+}
+
+pub(crate) fn normalize(v: [f64; 3]) -> [f64; 3] {
+    let length = length(v);
+    if length == 0.0 {
+        return v;
+    }
+    [v[0] / length, v[1] / length, v[2] / length]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+/// `a * scale_a + b * scale_b`
+fn combine(a: [f64; 3], b: [f64; 3], scale_a: f64, scale_b: f64) -> [f64; 3] {
+    [
+        a[0] * scale_a + b[0] * scale_b,
+        a[1] * scale_a + b[1] * scale_b,
+        a[2] * scale_a + b[2] * scale_b,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &Matrix3d, b: &Matrix3d) {
+        for (a, b) in a.0.iter().flatten().zip(b.0.iter().flatten()) {
+            assert!((a - b).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn inverse() {
+        let m = Matrix3d::translate(10.0, -5.0, 2.0)
+            .multiply(&Matrix3d::rotate(1.0, 2.0, 3.0, 0.7))
+            .multiply(&Matrix3d::scale(2.0, 3.0, 0.5));
+        let inverse = m.inverse().unwrap();
+        assert_close(&m.multiply(&inverse), &Matrix3d::IDENTITY);
+        assert_close(&inverse.multiply(&m), &Matrix3d::IDENTITY);
+        assert_eq!(Matrix3d::scale(1.0, 0.0, 1.0).inverse(), None);
+    }
+
+    #[test]
+    fn nan_does_not_panic() {
+        let m = Matrix3d::scale(f64::NAN, 1.0, 1.0);
+        assert!(m.determinant().is_nan());
+        let _ = m.inverse();
+    }
+
+    #[test]
+    fn determinant() {
+        assert_eq!(Matrix3d::IDENTITY.determinant(), 1.0);
+        assert!((Matrix3d::scale(2.0, 3.0, 4.0).determinant() - 24.0).abs() < 1e-9);
+        // Swapping rows flips the sign
+        let swapped = Matrix3d::from_2d(0.0, 1.0, 1.0, 0.0, 0.0, 0.0);
+        assert!((swapped.determinant() + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn decompose_and_recompose() {
+        let m = Matrix3d::perspective(Some(500.0))
+            .multiply(&Matrix3d::translate(10.0, 20.0, 0.0))
+            .multiply(&Matrix3d::rotate(0.0, 1.0, 1.0, 0.5))
+            .multiply(&Matrix3d::scale(2.0, 3.0, 4.0));
+        let decomposed = m.decompose().unwrap();
+        assert!((decomposed.scale[0] - 2.0).abs() < 1e-9);
+        assert!((decomposed.scale[1] - 3.0).abs() < 1e-9);
+        assert!((decomposed.scale[2] - 4.0).abs() < 1e-9);
+        assert_close(&decomposed.recompose(), &m);
+
+        let mut singular = Matrix3d::IDENTITY;
+        singular.0[3][3] = 0.0;
+        assert_eq!(singular.decompose(), None);
+    }
+
+    #[test]
+    fn decompose_2d() {
+        let m = Matrix3d::translate(5.0, 6.0, 0.0)
+            .multiply(&Matrix3d::rotate(0.0, 0.0, 1.0, 30f64.to_radians()))
+            .multiply(&Matrix3d::scale(2.0, 3.0, 1.0));
+        let decomposed = m.decompose_2d().unwrap();
+        assert_eq!(decomposed.translate, [5.0, 6.0]);
+        assert!((decomposed.angle - 30.0).abs() < 1e-9);
+        assert!((decomposed.scale[0] - 2.0).abs() < 1e-9);
+        assert!((decomposed.scale[1] - 3.0).abs() < 1e-9);
+        assert_close(&decomposed.recompose(), &m);
+    }
+
+    #[test]
+    fn slerp_quaternions() {
+        let from = quaternion([0.0, 0.0, 1.0], 0.0);
+        let to = quaternion([0.0, 0.0, 1.0], std::f64::consts::FRAC_PI_2);
+        let halfway = slerp(from, to, 0.5);
+        let expected = quaternion([0.0, 0.0, 1.0], std::f64::consts::FRAC_PI_4);
+        for (a, b) in halfway.iter().zip(&expected) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        assert_eq!(slerp(from, to, 0.0), from);
+        // Identical rotations don't divide by zero
+        assert_eq!(slerp(to, to, 0.3), to);
+    }
+
+    #[test]
+    fn interpolate_matrices() {
+        let from = Matrix3d::IDENTITY;
+        let to = Matrix3d::rotate(1.0, 0.0, 0.0, 1.0);
+        assert_close(
+            &from.interpolate(&to, 0.5),
+            &Matrix3d::rotate(1.0, 0.0, 0.0, 0.5),
+        );
+        assert_close(&from.interpolate(&to, 1.0), &to);
+        // Matrices that can't be decomposed switch halfway
+        let singular = Matrix3d::scale(0.0, 0.0, 0.0);
+        assert_eq!(from.interpolate(&singular, 0.4), from);
+        assert_eq!(from.interpolate(&singular, 0.6), singular);
+    }
+}